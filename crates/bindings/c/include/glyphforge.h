@@ -0,0 +1,164 @@
+/* Generated with cbindgen from crates/bindings/c; see cbindgen.toml. Do not edit by hand. */
+
+#ifndef GLYPHFORGE_H
+#define GLYPHFORGE_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+// Version of this API. Callers pass the version their header declares to
+// [`glyphforge_engine_new`], which refuses versions it was not built for.
+#define GLYPHFORGE_ABI_VERSION 1
+
+// One 8-bit luma sample per pixel.
+#define GLYPHFORGE_FORMAT_GRAY 0
+
+// Three 8-bit samples per pixel, in `R, G, B` order.
+#define GLYPHFORGE_FORMAT_RGB 1
+
+typedef enum GlyphforgeStatus {
+  GLYPHFORGE_STATUS_OK = 0,
+  // A required pointer argument was null.
+  GLYPHFORGE_STATUS_NULL_POINTER = 1,
+  // A size, stride or pixel format does not describe a valid image.
+  GLYPHFORGE_STATUS_INVALID_ARGUMENT = 2,
+  // The charset is empty or not valid UTF-8.
+  GLYPHFORGE_STATUS_INVALID_CHARSET = 3,
+  // The caller was built against a different [`GLYPHFORGE_ABI_VERSION`].
+  GLYPHFORGE_STATUS_ABI_MISMATCH = 4,
+  // glyphforge panicked; the call had no effect.
+  GLYPHFORGE_STATUS_INTERNAL = 5,
+} GlyphforgeStatus;
+
+// Rendered cells, row by row; created by [`glyphforge_render_cells`].
+typedef struct GlyphforgeCells GlyphforgeCells;
+
+// Maps luma to glyphs; created by [`glyphforge_engine_new`].
+typedef struct GlyphforgeEngine GlyphforgeEngine;
+
+// Rendered UTF-8 text; created by [`glyphforge_render_text`].
+typedef struct GlyphforgeText GlyphforgeText;
+
+// Pixels owned by the caller, which only need to stay valid for the duration of a call.
+typedef struct GlyphforgeImage {
+  const uint8_t *data;
+  size_t width;
+  size_t height;
+  // Distance in bytes between the starts of two consecutive rows.
+  size_t stride;
+  // `GLYPHFORGE_FORMAT_GRAY` or `GLYPHFORGE_FORMAT_RGB`.
+  uint32_t format;
+} GlyphforgeImage;
+
+// One rendered cell: a Unicode code point and the color of the pixels it covers.
+typedef struct GlyphforgeCell {
+  uint32_t codepoint;
+  uint8_t r;
+  uint8_t g;
+  uint8_t b;
+} GlyphforgeCell;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+uint32_t glyphforge_abi_version(void);
+
+// A static, NUL-terminated description of `status`, one of the `GlyphforgeStatus` codes;
+// other values get a generic "unknown status" message.
+const char *glyphforge_status_message(uint32_t status);
+
+// Creates an engine mapping luma onto `charset`, a NUL-terminated UTF-8 string of glyphs from
+// dark to bright, or the default ` .:-=+*#%@` ramp when it is null.
+//
+// # Safety
+//
+// `charset` must be null or point to a NUL-terminated string, and `out` must be valid for a
+// write.
+enum GlyphforgeStatus glyphforge_engine_new(uint32_t abi_version,
+                                            const char *charset,
+                                            struct GlyphforgeEngine **out);
+
+// Whether [`glyphforge_render_text`] tints RGB images with 24-bit ANSI escapes.
+//
+// # Safety
+//
+// `engine` must be null or a live handle from [`glyphforge_engine_new`].
+enum GlyphforgeStatus glyphforge_engine_set_color(struct GlyphforgeEngine *engine, bool color);
+
+// # Safety
+//
+// `engine` must be null or a live handle from [`glyphforge_engine_new`], and is dangling
+// afterwards.
+void glyphforge_engine_free(struct GlyphforgeEngine *engine);
+
+// Renders `image`, stretched to `columns` by `rows` cells, as lines joined by `\n`.
+//
+// # Safety
+//
+// `engine` must be a live handle, `image` must describe `stride * (height - 1)` plus one row
+// of readable bytes, and `out` must be valid for a write.
+enum GlyphforgeStatus glyphforge_render_text(const struct GlyphforgeEngine *engine,
+                                             const struct GlyphforgeImage *image,
+                                             size_t columns,
+                                             size_t rows,
+                                             struct GlyphforgeText **out);
+
+// The rendered UTF-8 text, NUL-terminated, or null for a null handle.
+//
+// # Safety
+//
+// `text` must be null or a live handle; the pointer is valid until the handle is freed.
+const char *glyphforge_text_data(const struct GlyphforgeText *text);
+
+// Length in bytes of the rendered text, without the NUL.
+//
+// # Safety
+//
+// `text` must be null or a live handle.
+size_t glyphforge_text_len(const struct GlyphforgeText *text);
+
+// # Safety
+//
+// `text` must be null or a live handle, and is dangling afterwards.
+void glyphforge_text_free(struct GlyphforgeText *text);
+
+// Renders `image`, stretched to `columns` by `rows` cells, as a glyph and color per cell.
+//
+// # Safety
+//
+// As for [`glyphforge_render_text`].
+enum GlyphforgeStatus glyphforge_render_cells(const struct GlyphforgeEngine *engine,
+                                              const struct GlyphforgeImage *image,
+                                              size_t columns,
+                                              size_t rows,
+                                              struct GlyphforgeCells **out);
+
+// The first of `columns * rows` cells, or null for a null handle.
+//
+// # Safety
+//
+// `cells` must be null or a live handle; the pointer is valid until the handle is freed.
+const struct GlyphforgeCell *glyphforge_cells_data(const struct GlyphforgeCells *cells);
+
+// # Safety
+//
+// `cells` must be null or a live handle.
+size_t glyphforge_cells_columns(const struct GlyphforgeCells *cells);
+
+// # Safety
+//
+// `cells` must be null or a live handle.
+size_t glyphforge_cells_rows(const struct GlyphforgeCells *cells);
+
+// # Safety
+//
+// `cells` must be null or a live handle, and is dangling afterwards.
+void glyphforge_cells_free(struct GlyphforgeCells *cells);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* GLYPHFORGE_H */
@@ -0,0 +1,595 @@
+//! C API for embedding glyphforge in native applications.
+//!
+//! Engines, rendered text and cell arrays are opaque handles that the caller frees with the
+//! matching `_free` function. Every fallible call returns a [`GlyphforgeStatus`] and writes its
+//! result through an out-pointer, which is left untouched on failure.
+
+use std::{
+    ffi::{CStr, c_char},
+    panic::{self, AssertUnwindSafe},
+    ptr, slice,
+};
+
+use lib_ascii::domain::entities::{GlyphEngineAnsiColorImpl, GlyphEngineAsciiImpl};
+use lib_core::domain::entities::{Glyph, GlyphRenderingEngine};
+use lib_image::domain::entities::{
+    GlyphImage, LumaImage, LumaPixel, ResampleFilter, RgbImage, RgbPixel,
+};
+
+/// Version of this API. Callers pass the version their header declares to
+/// [`glyphforge_engine_new`], which refuses versions it was not built for.
+pub const GLYPHFORGE_ABI_VERSION: u32 = 1;
+
+/// One 8-bit luma sample per pixel.
+pub const GLYPHFORGE_FORMAT_GRAY: u32 = 0;
+/// Three 8-bit samples per pixel, in `R, G, B` order.
+pub const GLYPHFORGE_FORMAT_RGB: u32 = 1;
+
+#[repr(C)]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum GlyphforgeStatus {
+    Ok = 0,
+    /// A required pointer argument was null.
+    NullPointer = 1,
+    /// A size, stride or pixel format does not describe a valid image.
+    InvalidArgument = 2,
+    /// The charset is empty or not valid UTF-8.
+    InvalidCharset = 3,
+    /// The caller was built against a different [`GLYPHFORGE_ABI_VERSION`].
+    AbiMismatch = 4,
+    /// glyphforge panicked; the call had no effect.
+    Internal = 5,
+}
+
+impl GlyphforgeStatus {
+    /// The status with the integer value `code`, which C callers may have made up.
+    fn from_code(code: u32) -> Option<Self> {
+        [
+            Self::Ok,
+            Self::NullPointer,
+            Self::InvalidArgument,
+            Self::InvalidCharset,
+            Self::AbiMismatch,
+            Self::Internal,
+        ]
+        .into_iter()
+        .find(|status: &Self| *status as u32 == code)
+    }
+}
+
+/// Pixels owned by the caller, which only need to stay valid for the duration of a call.
+#[repr(C)]
+pub struct GlyphforgeImage {
+    pub data: *const u8,
+    pub width: usize,
+    pub height: usize,
+    /// Distance in bytes between the starts of two consecutive rows.
+    pub stride: usize,
+    /// `GLYPHFORGE_FORMAT_GRAY` or `GLYPHFORGE_FORMAT_RGB`.
+    pub format: u32,
+}
+
+/// One rendered cell: a Unicode code point and the color of the pixels it covers.
+#[repr(C)]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct GlyphforgeCell {
+    pub codepoint: u32,
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+/// Maps luma to glyphs; created by [`glyphforge_engine_new`].
+pub struct GlyphforgeEngine {
+    glyphs: GlyphEngineAsciiImpl,
+    color: bool,
+}
+
+/// Rendered UTF-8 text; created by [`glyphforge_render_text`].
+pub struct GlyphforgeText {
+    /// The text followed by a NUL, so it can be read as a C string too.
+    bytes: Vec<u8>,
+}
+
+/// Rendered cells, row by row; created by [`glyphforge_render_cells`].
+pub struct GlyphforgeCells {
+    columns: usize,
+    rows: usize,
+    cells: Vec<GlyphforgeCell>,
+}
+
+/// A caller's image, copied and resized to the cell grid.
+enum Frame {
+    Gray(LumaImage),
+    Rgb(RgbImage),
+}
+
+impl Frame {
+    fn luma(&self) -> LumaImage {
+        match self {
+            Frame::Gray(image) => image.clone(),
+            Frame::Rgb(image) => LumaImage::from(image),
+        }
+    }
+
+    fn colors(&self) -> Vec<RgbPixel> {
+        match self {
+            Frame::Gray(image) => image
+                .pixels()
+                .map(|pixel: LumaPixel| RgbPixel::new(pixel.y, pixel.y, pixel.y))
+                .collect(),
+            Frame::Rgb(image) => image.pixels().collect(),
+        }
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn glyphforge_abi_version() -> u32 {
+    GLYPHFORGE_ABI_VERSION
+}
+
+/// A static, NUL-terminated description of `status`, one of the `GlyphforgeStatus` codes;
+/// other values get a generic "unknown status" message.
+#[unsafe(no_mangle)]
+pub extern "C" fn glyphforge_status_message(status: u32) -> *const c_char {
+    let message: &CStr = match GlyphforgeStatus::from_code(status) {
+        Some(GlyphforgeStatus::Ok) => c"ok",
+        Some(GlyphforgeStatus::NullPointer) => c"null pointer argument",
+        Some(GlyphforgeStatus::InvalidArgument) => c"invalid image size, stride or format",
+        Some(GlyphforgeStatus::InvalidCharset) => c"charset is empty or not valid UTF-8",
+        Some(GlyphforgeStatus::AbiMismatch) => c"caller was built for another ABI version",
+        Some(GlyphforgeStatus::Internal) => c"internal error",
+        None => c"unknown status",
+    };
+
+    message.as_ptr()
+}
+
+/// Creates an engine mapping luma onto `charset`, a NUL-terminated UTF-8 string of glyphs from
+/// dark to bright, or the default ` .:-=+*#%@` ramp when it is null.
+///
+/// # Safety
+///
+/// `charset` must be null or point to a NUL-terminated string, and `out` must be valid for a
+/// write.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn glyphforge_engine_new(
+    abi_version: u32,
+    charset: *const c_char,
+    out: *mut *mut GlyphforgeEngine,
+) -> GlyphforgeStatus {
+    guard(|| {
+        if abi_version != GLYPHFORGE_ABI_VERSION {
+            return Err(GlyphforgeStatus::AbiMismatch);
+        }
+        if out.is_null() {
+            return Err(GlyphforgeStatus::NullPointer);
+        }
+
+        let glyphs: GlyphEngineAsciiImpl = match charset.is_null() {
+            true => GlyphEngineAsciiImpl::new(),
+            false => unsafe { CStr::from_ptr(charset) }
+                .to_str()
+                .ok()
+                .and_then(|charset: &str| GlyphEngineAsciiImpl::with_charset(charset.chars()))
+                .ok_or(GlyphforgeStatus::InvalidCharset)?,
+        };
+        let engine: GlyphforgeEngine = GlyphforgeEngine {
+            glyphs,
+            color: false,
+        };
+
+        unsafe { out.write(Box::into_raw(Box::new(engine))) };
+        Ok(())
+    })
+}
+
+/// Whether [`glyphforge_render_text`] tints RGB images with 24-bit ANSI escapes.
+///
+/// # Safety
+///
+/// `engine` must be null or a live handle from [`glyphforge_engine_new`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn glyphforge_engine_set_color(
+    engine: *mut GlyphforgeEngine,
+    color: bool,
+) -> GlyphforgeStatus {
+    guard(|| {
+        let engine: &mut GlyphforgeEngine =
+            unsafe { engine.as_mut() }.ok_or(GlyphforgeStatus::NullPointer)?;
+
+        engine.color = color;
+        Ok(())
+    })
+}
+
+/// # Safety
+///
+/// `engine` must be null or a live handle from [`glyphforge_engine_new`], and is dangling
+/// afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn glyphforge_engine_free(engine: *mut GlyphforgeEngine) {
+    if !engine.is_null() {
+        drop(unsafe { Box::from_raw(engine) });
+    }
+}
+
+/// Renders `image`, stretched to `columns` by `rows` cells, as lines joined by `\n`.
+///
+/// # Safety
+///
+/// `engine` must be a live handle, `image` must describe `stride * (height - 1)` plus one row
+/// of readable bytes, and `out` must be valid for a write.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn glyphforge_render_text(
+    engine: *const GlyphforgeEngine,
+    image: *const GlyphforgeImage,
+    columns: usize,
+    rows: usize,
+    out: *mut *mut GlyphforgeText,
+) -> GlyphforgeStatus {
+    guard(|| {
+        let (engine, frame) = unsafe { prepare(engine, image, columns, rows) }?;
+        if out.is_null() {
+            return Err(GlyphforgeStatus::NullPointer);
+        }
+
+        let text: String = match (&frame, engine.color) {
+            (Frame::Rgb(image), true) => {
+                image.as_text(&GlyphEngineAnsiColorImpl::new(engine.glyphs.clone()))
+            }
+            _ => frame.luma().as_text(&engine.glyphs),
+        };
+        let mut bytes: Vec<u8> = text.into_bytes();
+        bytes.push(0);
+
+        unsafe { out.write(Box::into_raw(Box::new(GlyphforgeText { bytes }))) };
+        Ok(())
+    })
+}
+
+/// The rendered UTF-8 text, NUL-terminated, or null for a null handle.
+///
+/// # Safety
+///
+/// `text` must be null or a live handle; the pointer is valid until the handle is freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn glyphforge_text_data(text: *const GlyphforgeText) -> *const c_char {
+    match unsafe { text.as_ref() } {
+        Some(text) => text.bytes.as_ptr().cast(),
+        None => ptr::null(),
+    }
+}
+
+/// Length in bytes of the rendered text, without the NUL.
+///
+/// # Safety
+///
+/// `text` must be null or a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn glyphforge_text_len(text: *const GlyphforgeText) -> usize {
+    unsafe { text.as_ref() }.map_or(0, |text: &GlyphforgeText| text.bytes.len() - 1)
+}
+
+/// # Safety
+///
+/// `text` must be null or a live handle, and is dangling afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn glyphforge_text_free(text: *mut GlyphforgeText) {
+    if !text.is_null() {
+        drop(unsafe { Box::from_raw(text) });
+    }
+}
+
+/// Renders `image`, stretched to `columns` by `rows` cells, as a glyph and color per cell.
+///
+/// # Safety
+///
+/// As for [`glyphforge_render_text`].
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn glyphforge_render_cells(
+    engine: *const GlyphforgeEngine,
+    image: *const GlyphforgeImage,
+    columns: usize,
+    rows: usize,
+    out: *mut *mut GlyphforgeCells,
+) -> GlyphforgeStatus {
+    guard(|| {
+        let (engine, frame) = unsafe { prepare(engine, image, columns, rows) }?;
+        if out.is_null() {
+            return Err(GlyphforgeStatus::NullPointer);
+        }
+
+        let cells: Vec<GlyphforgeCell> = frame
+            .luma()
+            .pixels()
+            .zip(frame.colors())
+            .map(|(luma, color): (LumaPixel, RgbPixel)| GlyphforgeCell {
+                codepoint: engine.glyphs.get_char_based_on_luma_value(luma.y) as u32,
+                r: color.r,
+                g: color.g,
+                b: color.b,
+            })
+            .collect();
+        let cells: GlyphforgeCells = GlyphforgeCells {
+            columns,
+            rows,
+            cells,
+        };
+
+        unsafe { out.write(Box::into_raw(Box::new(cells))) };
+        Ok(())
+    })
+}
+
+/// The first of `columns * rows` cells, or null for a null handle.
+///
+/// # Safety
+///
+/// `cells` must be null or a live handle; the pointer is valid until the handle is freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn glyphforge_cells_data(
+    cells: *const GlyphforgeCells,
+) -> *const GlyphforgeCell {
+    match unsafe { cells.as_ref() } {
+        Some(cells) => cells.cells.as_ptr(),
+        None => ptr::null(),
+    }
+}
+
+/// # Safety
+///
+/// `cells` must be null or a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn glyphforge_cells_columns(cells: *const GlyphforgeCells) -> usize {
+    unsafe { cells.as_ref() }.map_or(0, |cells: &GlyphforgeCells| cells.columns)
+}
+
+/// # Safety
+///
+/// `cells` must be null or a live handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn glyphforge_cells_rows(cells: *const GlyphforgeCells) -> usize {
+    unsafe { cells.as_ref() }.map_or(0, |cells: &GlyphforgeCells| cells.rows)
+}
+
+/// # Safety
+///
+/// `cells` must be null or a live handle, and is dangling afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn glyphforge_cells_free(cells: *mut GlyphforgeCells) {
+    if !cells.is_null() {
+        drop(unsafe { Box::from_raw(cells) });
+    }
+}
+
+/// Runs `body`, turning a panic into [`GlyphforgeStatus::Internal`] rather than unwinding into
+/// the caller.
+fn guard(body: impl FnOnce() -> Result<(), GlyphforgeStatus>) -> GlyphforgeStatus {
+    match panic::catch_unwind(AssertUnwindSafe(body)) {
+        Ok(Ok(())) => GlyphforgeStatus::Ok,
+        Ok(Err(status)) => status,
+        Err(_) => GlyphforgeStatus::Internal,
+    }
+}
+
+/// Checks the arguments shared by the render functions and copies the image into a frame of
+/// `columns` by `rows` pixels.
+unsafe fn prepare<'a>(
+    engine: *const GlyphforgeEngine,
+    image: *const GlyphforgeImage,
+    columns: usize,
+    rows: usize,
+) -> Result<(&'a GlyphforgeEngine, Frame), GlyphforgeStatus> {
+    let engine: &GlyphforgeEngine =
+        unsafe { engine.as_ref() }.ok_or(GlyphforgeStatus::NullPointer)?;
+    let image: &GlyphforgeImage = unsafe { image.as_ref() }.ok_or(GlyphforgeStatus::NullPointer)?;
+    if image.data.is_null() {
+        return Err(GlyphforgeStatus::NullPointer);
+    }
+
+    let channels: usize = match image.format {
+        GLYPHFORGE_FORMAT_GRAY => 1,
+        GLYPHFORGE_FORMAT_RGB => 3,
+        _ => return Err(GlyphforgeStatus::InvalidArgument),
+    };
+    let row_len: usize = image
+        .width
+        .checked_mul(channels)
+        .ok_or(GlyphforgeStatus::InvalidArgument)?;
+    if image.width == 0 || image.height == 0 || columns == 0 || rows == 0 || image.stride < row_len
+    {
+        return Err(GlyphforgeStatus::InvalidArgument);
+    }
+
+    // The last row does not need its padding.
+    let len: usize = image
+        .stride
+        .checked_mul(image.height - 1)
+        .and_then(|len: usize| len.checked_add(row_len))
+        .ok_or(GlyphforgeStatus::InvalidArgument)?;
+    let data: Vec<u8> = unsafe { slice::from_raw_parts(image.data, len) }.to_vec();
+
+    let frame: Frame = match channels {
+        1 => Frame::Gray(
+            LumaImage::from_strided_image_data(data, image.width, image.height, image.stride)
+                .and_then(|image: LumaImage| image.resize(columns, rows, ResampleFilter::Box))
+                .map_err(|_| GlyphforgeStatus::InvalidArgument)?,
+        ),
+        _ => Frame::Rgb(
+            RgbImage::from_strided_image_data(data, image.width, image.height, image.stride)
+                .and_then(|image: RgbImage| image.resize(columns, rows, ResampleFilter::Box))
+                .map_err(|_| GlyphforgeStatus::InvalidArgument)?,
+        ),
+    };
+
+    Ok((engine, frame))
+}
+
+#[cfg(test)]
+mod test {
+    use std::{ffi::CStr, ptr, slice};
+
+    use pretty_assertions::assert_eq;
+
+    use crate::{
+        GLYPHFORGE_ABI_VERSION, GLYPHFORGE_FORMAT_GRAY, GLYPHFORGE_FORMAT_RGB, GlyphforgeCell,
+        GlyphforgeCells, GlyphforgeEngine, GlyphforgeImage, GlyphforgeStatus, GlyphforgeText,
+        glyphforge_cells_columns, glyphforge_cells_data, glyphforge_cells_free,
+        glyphforge_engine_free, glyphforge_engine_new, glyphforge_render_cells,
+        glyphforge_render_text, glyphforge_status_message, glyphforge_text_data,
+        glyphforge_text_free, glyphforge_text_len,
+    };
+
+    fn engine(charset: &CStr) -> *mut GlyphforgeEngine {
+        let mut engine: *mut GlyphforgeEngine = ptr::null_mut();
+        let status: GlyphforgeStatus =
+            unsafe { glyphforge_engine_new(GLYPHFORGE_ABI_VERSION, charset.as_ptr(), &mut engine) };
+
+        assert_eq!(status, GlyphforgeStatus::Ok);
+        engine
+    }
+
+    #[test]
+    fn renders_strided_gray_rows_as_text() {
+        let engine: *mut GlyphforgeEngine = engine(c" -@");
+        // Two rows of three pixels, each padded to four bytes.
+        let data: [u8; 8] = [0, 128, 255, 99, 255, 128, 0, 99];
+        let image: GlyphforgeImage = GlyphforgeImage {
+            data: data.as_ptr(),
+            width: 3,
+            height: 2,
+            stride: 4,
+            format: GLYPHFORGE_FORMAT_GRAY,
+        };
+        let mut text: *mut GlyphforgeText = ptr::null_mut();
+
+        let status: GlyphforgeStatus =
+            unsafe { glyphforge_render_text(engine, &image, 3, 2, &mut text) };
+
+        assert_eq!(status, GlyphforgeStatus::Ok);
+        let rendered: &CStr = unsafe { CStr::from_ptr(glyphforge_text_data(text)) };
+        assert_eq!(rendered.to_str(), Ok(" -@\n@- "));
+        assert_eq!(unsafe { glyphforge_text_len(text) }, 7);
+        unsafe {
+            glyphforge_text_free(text);
+            glyphforge_engine_free(engine);
+        }
+    }
+
+    #[test]
+    fn renders_rgb_pixels_as_colored_cells() {
+        let engine: *mut GlyphforgeEngine = engine(c" @");
+        let data: [u8; 6] = [255, 255, 255, 0, 0, 0];
+        let image: GlyphforgeImage = GlyphforgeImage {
+            data: data.as_ptr(),
+            width: 2,
+            height: 1,
+            stride: 6,
+            format: GLYPHFORGE_FORMAT_RGB,
+        };
+        let mut cells: *mut GlyphforgeCells = ptr::null_mut();
+
+        let status: GlyphforgeStatus =
+            unsafe { glyphforge_render_cells(engine, &image, 2, 1, &mut cells) };
+
+        assert_eq!(status, GlyphforgeStatus::Ok);
+        let columns: usize = unsafe { glyphforge_cells_columns(cells) };
+        let rendered: &[GlyphforgeCell] =
+            unsafe { slice::from_raw_parts(glyphforge_cells_data(cells), columns) };
+        assert_eq!(
+            rendered,
+            [
+                GlyphforgeCell {
+                    codepoint: '@' as u32,
+                    r: 255,
+                    g: 255,
+                    b: 255,
+                },
+                GlyphforgeCell {
+                    codepoint: ' ' as u32,
+                    r: 0,
+                    g: 0,
+                    b: 0,
+                },
+            ]
+        );
+        unsafe {
+            glyphforge_cells_free(cells);
+            glyphforge_engine_free(engine);
+        }
+    }
+
+    #[test]
+    fn invalid_arguments_return_error_codes() {
+        let mut handle: *mut GlyphforgeEngine = ptr::null_mut();
+        let engine: *mut GlyphforgeEngine = engine(c" @");
+        let data: [u8; 4] = [0; 4];
+        let image: GlyphforgeImage = GlyphforgeImage {
+            data: data.as_ptr(),
+            width: 2,
+            height: 2,
+            stride: 1,
+            format: GLYPHFORGE_FORMAT_GRAY,
+        };
+        let mut text: *mut GlyphforgeText = ptr::null_mut();
+
+        unsafe {
+            assert_eq!(
+                glyphforge_engine_new(GLYPHFORGE_ABI_VERSION + 1, ptr::null(), &mut handle),
+                GlyphforgeStatus::AbiMismatch
+            );
+            assert_eq!(
+                glyphforge_engine_new(GLYPHFORGE_ABI_VERSION, c"".as_ptr(), &mut handle),
+                GlyphforgeStatus::InvalidCharset
+            );
+            assert_eq!(
+                glyphforge_render_text(engine, &image, 2, 2, &mut text),
+                GlyphforgeStatus::InvalidArgument
+            );
+            assert_eq!(
+                glyphforge_render_text(ptr::null(), &image, 2, 2, &mut text),
+                GlyphforgeStatus::NullPointer
+            );
+            glyphforge_engine_free(engine);
+        }
+        assert!(handle.is_null());
+        assert!(text.is_null());
+    }
+
+    #[test]
+    fn status_messages_cover_unknown_codes() {
+        let message = |status: u32| unsafe { CStr::from_ptr(glyphforge_status_message(status)) };
+
+        assert_eq!(message(GlyphforgeStatus::Ok as u32), c"ok");
+        assert_eq!(
+            message(GlyphforgeStatus::Internal as u32),
+            c"internal error"
+        );
+        assert_eq!(
+            message(GlyphforgeStatus::Internal as u32 + 1),
+            c"unknown status"
+        );
+        assert_eq!(message(u32::MAX), c"unknown status");
+    }
+
+    #[test]
+    fn header_declares_every_exported_function() {
+        let header: &str = include_str!("../include/glyphforge.h");
+        let source: &str = include_str!("lib.rs");
+
+        for line in source.lines() {
+            if let Some((_, rest)) = line.split_once("extern \"C\" fn ") {
+                let name: &str = rest.split('(').next().unwrap_or(rest);
+
+                assert!(
+                    header.contains(&format!("{name}(")),
+                    "{name} missing from header"
+                );
+            }
+        }
+        assert!(header.contains(&format!(
+            "#define GLYPHFORGE_ABI_VERSION {GLYPHFORGE_ABI_VERSION}"
+        )));
+    }
+}
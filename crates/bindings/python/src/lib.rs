@@ -0,0 +1,293 @@
+//! Python bindings that render NumPy `uint8` images, `HxW` gray or `HxWx3`/`HxWx4` color, as
+//! glyph text or cell arrays.
+
+mod rendering;
+
+use std::{borrow::Cow, collections::HashMap};
+
+use lib_ascii::domain::entities::GlyphEngineAsciiImpl;
+use lib_image::domain::entities::{Dither, ResampleFilter, RgbPixel, ToneAdjustment};
+use numpy::{
+    IntoPyArray, PyArray2, PyArray3, PyReadonlyArrayDyn,
+    ndarray::{Array2, Array3, ArrayViewD},
+};
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::{PyDict, PyType},
+};
+
+use crate::rendering::{Frame, Pixels, RenderOptions, Rendered};
+
+/// Glyph ramps from dark to bright, by the names `charset` accepts.
+const CHARSETS: [(&str, &str); 4] = [
+    ("standard", " .:-=+*#%@"),
+    ("simple", " .oO@"),
+    ("blocks", " ░▒▓█"),
+    ("binary", " #"),
+];
+
+/// Render settings that can be reused across many images, such as the frames of a video.
+#[pyclass(module = "glyphforge", frozen)]
+struct Engine {
+    options: RenderOptions,
+}
+
+/// A rendered image as arrays: `glyphs` holds code points, `(rows, columns)`, and `colors` the
+/// RGB color of each cell, `(rows, columns, 3)`.
+#[pyclass(module = "glyphforge", frozen)]
+struct Cells {
+    glyphs: Py<PyArray2<u32>>,
+    colors: Py<PyArray3<u8>>,
+    text: String,
+}
+
+#[pymethods]
+impl Engine {
+    /// `charset` is one of the `CHARSETS` names or the glyphs themselves, from dark to bright.
+    /// `adjust` takes the CLI's `--adjust` specs, such as `"gamma=0.8"` or `"clahe"`, applied in
+    /// order. `dither` is `"floyd-steinberg"` or `"ordered"`. `cell_aspect`, the height of a
+    /// glyph cell divided by its width, must be a positive number.
+    #[new]
+    #[pyo3(signature = (
+        charset=None, color=false, adjust=Vec::new(), dither=None, filter="box", cell_aspect=2.0,
+        background=(0, 0, 0)
+    ))]
+    fn new(
+        charset: Option<&str>,
+        color: bool,
+        adjust: Vec<String>,
+        dither: Option<&str>,
+        filter: &str,
+        cell_aspect: f32,
+        background: (u8, u8, u8),
+    ) -> PyResult<Self> {
+        if !(cell_aspect.is_finite() && cell_aspect > 0.0) {
+            return Err(PyValueError::new_err(format!(
+                "cell_aspect must be a positive number, got {cell_aspect}"
+            )));
+        }
+
+        let ramp: &str = match charset {
+            Some(charset) => CHARSETS
+                .iter()
+                .find(|(name, _)| *name == charset)
+                .map_or(charset, |(_, ramp)| ramp),
+            None => CHARSETS[0].1,
+        };
+
+        Ok(Self {
+            options: RenderOptions {
+                charset: GlyphEngineAsciiImpl::with_charset(ramp.chars())
+                    .ok_or_else(|| PyValueError::new_err("charset must not be empty"))?,
+                color,
+                adjustments: adjust
+                    .iter()
+                    .map(|spec: &String| spec.parse::<ToneAdjustment>())
+                    .collect::<Result<_, _>>()
+                    .map_err(value_error)?,
+                dither: dither
+                    .map(str::parse::<Dither>)
+                    .transpose()
+                    .map_err(value_error)?,
+                filter: filter.parse::<ResampleFilter>().map_err(value_error)?,
+                cell_aspect,
+                background: RgbPixel::new(background.0, background.1, background.2),
+            },
+        })
+    }
+
+    /// Text with one line per row, tinted with ANSI escapes when `color` is on. `height`
+    /// defaults to keeping the image's aspect ratio.
+    #[pyo3(signature = (array, width=80, height=None))]
+    fn render(
+        &self,
+        py: Python<'_>,
+        array: PyReadonlyArrayDyn<'_, u8>,
+        width: usize,
+        height: Option<usize>,
+    ) -> PyResult<String> {
+        let rendered: Rendered = self.rendered(py, array, width, height)?;
+
+        Ok(self.options.text(&rendered))
+    }
+
+    /// Like [`Engine::render`], but as arrays for further processing.
+    #[pyo3(signature = (array, width=80, height=None))]
+    fn render_cells(
+        &self,
+        py: Python<'_>,
+        array: PyReadonlyArrayDyn<'_, u8>,
+        width: usize,
+        height: Option<usize>,
+    ) -> PyResult<Cells> {
+        let rendered: Rendered = self.rendered(py, array, width, height)?;
+        let shape: (usize, usize) = (rendered.rows, rendered.columns);
+        let glyphs: Vec<u32> = self
+            .options
+            .glyphs(&rendered)
+            .into_iter()
+            .map(u32::from)
+            .collect();
+        let colors: Vec<u8> = rendered
+            .colors
+            .iter()
+            .flat_map(|color: &RgbPixel| [color.r, color.g, color.b])
+            .collect();
+
+        Ok(Cells {
+            glyphs: Array2::from_shape_vec(shape, glyphs)
+                .map_err(value_error)?
+                .into_pyarray(py)
+                .unbind(),
+            colors: Array3::from_shape_vec((shape.0, shape.1, 3), colors)
+                .map_err(value_error)?
+                .into_pyarray(py)
+                .unbind(),
+            text: self.options.plain_text(&rendered),
+        })
+    }
+}
+
+impl Engine {
+    fn rendered(
+        &self,
+        py: Python<'_>,
+        array: PyReadonlyArrayDyn<'_, u8>,
+        width: usize,
+        height: Option<usize>,
+    ) -> PyResult<Rendered> {
+        if width == 0 || height == Some(0) {
+            return Err(PyValueError::new_err("width and height must be positive"));
+        }
+
+        let pixels: Pixels = pixels(array.as_array())?;
+        let rows: usize = height.unwrap_or_else(|| self.options.rows_for(&pixels, width));
+        let resample = || self.options.resample(&pixels, width, rows);
+        // Python code may write to the array once the GIL is released, so it is only read with
+        // the GIL held; copies are resampled without it.
+        let frame: Frame = match pixels.is_borrowed() {
+            true => resample(),
+            false => py.detach(resample),
+        }
+        .map_err(value_error)?;
+
+        // Mapping touches no Python objects, so other threads may run meanwhile.
+        Ok(py.detach(|| self.options.render(frame)))
+    }
+}
+
+#[pymethods]
+impl Cells {
+    #[getter]
+    fn glyphs<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray2<u32>> {
+        self.glyphs.bind(py).clone()
+    }
+
+    #[getter]
+    fn colors<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray3<u8>> {
+        self.colors.bind(py).clone()
+    }
+
+    /// The glyphs as plain text, without color.
+    #[getter]
+    fn text(&self) -> &str {
+        &self.text
+    }
+
+    fn __str__(&self) -> &str {
+        &self.text
+    }
+}
+
+/// Renders `array` as text; keyword arguments other than `width` and `height` are passed to
+/// [`Engine`].
+#[pyfunction]
+#[pyo3(signature = (array, width=80, height=None, **options))]
+fn render(
+    py: Python<'_>,
+    array: PyReadonlyArrayDyn<'_, u8>,
+    width: usize,
+    height: Option<usize>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<String> {
+    engine(py, options)?.get().render(py, array, width, height)
+}
+
+/// Renders `array` as [`Cells`]; keyword arguments other than `width` and `height` are passed
+/// to [`Engine`].
+#[pyfunction]
+#[pyo3(signature = (array, width=80, height=None, **options))]
+fn render_cells(
+    py: Python<'_>,
+    array: PyReadonlyArrayDyn<'_, u8>,
+    width: usize,
+    height: Option<usize>,
+    options: Option<&Bound<'_, PyDict>>,
+) -> PyResult<Cells> {
+    engine(py, options)?
+        .get()
+        .render_cells(py, array, width, height)
+}
+
+fn engine<'py>(
+    py: Python<'py>,
+    options: Option<&Bound<'py, PyDict>>,
+) -> PyResult<Bound<'py, Engine>> {
+    let engine_type: Bound<'py, PyType> = py.get_type::<Engine>();
+
+    Ok(engine_type.call((), options)?.cast_into::<Engine>()?)
+}
+
+/// Reads an `HxW` or `HxWxC` array as packed samples, borrowed from NumPy's memory when the
+/// array is C-contiguous. Other arrays are copied, row by row when their rows are contiguous,
+/// as they are in crops of a larger frame, and sample by sample otherwise.
+fn pixels(array: ArrayViewD<'_, u8>) -> PyResult<Pixels<'_>> {
+    let (height, width, channels): (usize, usize, usize) = match *array.shape() {
+        [height, width] => (height, width, 1),
+        [height, width, channels @ (1 | 3 | 4)] => (height, width, channels),
+        ref shape => {
+            return Err(PyValueError::new_err(format!(
+                "expected an HxW, HxWx1, HxWx3 or HxWx4 array, got shape {shape:?}"
+            )));
+        }
+    };
+    if width == 0 || height == 0 {
+        return Err(PyValueError::new_err("image must not be empty"));
+    }
+
+    let data: Cow<'_, [u8]> = match array.to_slice() {
+        Some(data) => Cow::Borrowed(data),
+        None => {
+            let mut data: Vec<u8> = Vec::with_capacity(width * height * channels);
+            for row in array.outer_iter() {
+                match row.as_slice() {
+                    Some(row) => data.extend_from_slice(row),
+                    None => data.extend(row.iter()),
+                }
+            }
+            Cow::Owned(data)
+        }
+    };
+
+    Pixels::new(data, width, height, channels)
+        .ok_or_else(|| PyValueError::new_err(format!("unsupported channel count {channels}")))
+}
+
+fn value_error(error: impl std::fmt::Display) -> PyErr {
+    PyValueError::new_err(error.to_string())
+}
+
+#[pymodule]
+fn glyphforge(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<Engine>()?;
+    m.add_class::<Cells>()?;
+    m.add_function(wrap_pyfunction!(render, m)?)?;
+    m.add_function(wrap_pyfunction!(render_cells, m)?)?;
+    m.add(
+        "CHARSETS",
+        CHARSETS.into_iter().collect::<HashMap<&str, &str>>(),
+    )?;
+
+    Ok(())
+}
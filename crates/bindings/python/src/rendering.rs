@@ -0,0 +1,185 @@
+use std::borrow::Cow;
+
+use lib_ascii::domain::entities::{GlyphEngineAnsiColorImpl, GlyphEngineAsciiImpl};
+use lib_core::domain::entities::GlyphRenderingEngine;
+use lib_image::domain::entities::{
+    Dither, GlyphImage, InvalidImageDataError, LumaImage, LumaPixel, ResampleFilter, RgbImage,
+    RgbPixel, RgbaImage, ToneAdjustment,
+};
+
+/// Tightly packed `height x width x channels` samples of a NumPy array, borrowed from the
+/// array when it is C-contiguous and copied otherwise.
+pub struct Pixels<'a> {
+    data: Cow<'a, [u8]>,
+    width: usize,
+    height: usize,
+    channels: usize,
+}
+
+impl<'a> Pixels<'a> {
+    /// Wraps gray, RGB or RGBA samples; `None` for other channel counts.
+    pub fn new(data: Cow<'a, [u8]>, width: usize, height: usize, channels: usize) -> Option<Self> {
+        matches!(channels, 1 | 3 | 4).then_some(Self {
+            data,
+            width,
+            height,
+            channels,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Whether the samples are still the array's own memory.
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self.data, Cow::Borrowed(_))
+    }
+}
+
+/// An image resampled to one pixel per cell.
+pub enum Frame {
+    Gray(LumaImage),
+    Rgb(RgbImage),
+    Rgba(RgbaImage),
+}
+
+/// Everything an [`crate::Engine`] applies to each image.
+pub struct RenderOptions {
+    pub charset: GlyphEngineAsciiImpl,
+    pub color: bool,
+    pub adjustments: Vec<ToneAdjustment>,
+    pub dither: Option<Dither>,
+    pub filter: ResampleFilter,
+    /// Height of a glyph cell divided by its width, used when only a width is given.
+    pub cell_aspect: f32,
+    /// Color transparent pixels are blended over.
+    pub background: RgbPixel,
+}
+
+/// An image mapped onto a grid of cells: the luma that picks each glyph, after any dithering,
+/// and the color of the pixels it covers.
+pub struct Rendered {
+    pub columns: usize,
+    pub rows: usize,
+    pub luma: Vec<u8>,
+    pub colors: Vec<RgbPixel>,
+}
+
+impl RenderOptions {
+    /// Rows that keep the image's aspect ratio at `columns` cells across.
+    pub fn rows_for(&self, pixels: &Pixels, columns: usize) -> usize {
+        let ratio: f32 = pixels.height() as f32 / pixels.width().max(1) as f32;
+
+        ((columns as f32 * ratio / self.cell_aspect).round() as usize).max(1)
+    }
+
+    /// Resamples `pixels` to `columns` by `rows`, reading gray and RGB samples where they are.
+    /// RGBA samples are copied, as they are premultiplied by their alpha first. Fails when the
+    /// cells do not fit in memory.
+    pub fn resample(
+        &self,
+        pixels: &Pixels<'_>,
+        columns: usize,
+        rows: usize,
+    ) -> Result<Frame, InvalidImageDataError> {
+        let (width, height): (usize, usize) = (pixels.width, pixels.height);
+
+        if pixels.channels == 4 {
+            return RgbaImage::from_rgba_data(width, height, pixels.data.to_vec())
+                .resize(columns, rows, self.filter)
+                .map(Frame::Rgba);
+        }
+
+        let data: Vec<u8> =
+            self.filter
+                .resize(&pixels.data, width, height, pixels.channels, columns, rows)?;
+
+        Ok(match pixels.channels {
+            1 => Frame::Gray(LumaImage::from_luma_data(columns, rows, data)),
+            _ => Frame::Rgb(RgbImage::from_rgb_data(columns, rows, data)),
+        })
+    }
+
+    pub fn render(&self, frame: Frame) -> Rendered {
+        let (luma, colors): (LumaImage, Vec<RgbPixel>) = match frame {
+            Frame::Gray(image) => {
+                let image: LumaImage = image.adjust_all(&self.adjustments);
+                let colors: Vec<RgbPixel> = image
+                    .pixels()
+                    .map(|pixel: LumaPixel| RgbPixel::new(pixel.y, pixel.y, pixel.y))
+                    .collect();
+
+                (image, colors)
+            }
+            Frame::Rgb(image) => self.color_frame(image),
+            Frame::Rgba(image) => self.color_frame(image.composite_over(self.background)),
+        };
+        let luma: LumaImage = match self.dither {
+            Some(dither) => luma.dither(self.charset.levels(), dither),
+            None => luma,
+        };
+
+        Rendered {
+            columns: luma.width(),
+            rows: luma.height(),
+            luma: luma.data().into_owned(),
+            colors,
+        }
+    }
+
+    pub fn glyphs(&self, rendered: &Rendered) -> Vec<char> {
+        rendered
+            .luma
+            .iter()
+            .map(|y: &u8| self.charset.get_char_based_on_luma_value(*y))
+            .collect()
+    }
+
+    /// Lines joined by `\n`, tinted with 24-bit ANSI escapes when color is on.
+    pub fn text(&self, rendered: &Rendered) -> String {
+        match self.color {
+            true => self.lines(
+                rendered,
+                &GlyphEngineAnsiColorImpl::new(self.charset.clone()),
+            ),
+            false => self.plain_text(rendered),
+        }
+    }
+
+    /// Lines joined by `\n`, without color whatever the options say.
+    pub fn plain_text(&self, rendered: &Rendered) -> String {
+        self.lines(rendered, &self.charset)
+    }
+
+    fn lines(&self, rendered: &Rendered, engine: &dyn GlyphRenderingEngine) -> String {
+        rendered
+            .luma
+            .chunks(rendered.columns)
+            .zip(rendered.colors.chunks(rendered.columns))
+            .map(|(luma, colors): (&[u8], &[RgbPixel])| {
+                let mut line: String = luma
+                    .iter()
+                    .zip(colors)
+                    .map(|(y, c): (&u8, &RgbPixel)| {
+                        engine.get_text_based_on_rgb_value(c.r, c.g, c.b, *y)
+                    })
+                    .collect();
+
+                line.push_str(engine.get_style_reset());
+                line
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    fn color_frame(&self, image: RgbImage) -> (LumaImage, Vec<RgbPixel>) {
+        let image: RgbImage = image.adjust_all(&self.adjustments);
+
+        (LumaImage::from(&image), image.pixels().collect())
+    }
+}
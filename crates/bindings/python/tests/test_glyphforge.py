@@ -0,0 +1,92 @@
+import numpy as np
+import pytest
+
+import glyphforge
+
+
+def test_gray_values_map_onto_the_charset():
+    array = np.array([[0, 128, 255]], dtype=np.uint8)
+
+    assert glyphforge.render(array, width=3, height=1, charset=" -@") == " -@"
+
+
+def test_row_padded_views_are_read_through_their_strides():
+    frame = np.zeros((2, 4), dtype=np.uint8)
+    frame[:, 2:] = 255
+
+    assert glyphforge.render(frame[:, 1:3], width=2, height=2, charset=" @") == " @\n @"
+
+
+def test_height_follows_the_aspect_ratio():
+    text = glyphforge.render(np.zeros((50, 100), dtype=np.uint8), width=40, cell_aspect=2.0)
+
+    assert len(text.splitlines()) == 10
+
+
+def test_color_tints_each_cell_with_ansi_escapes():
+    array = np.full((1, 1, 3), 255, dtype=np.uint8)
+
+    assert glyphforge.render(array, width=1, height=1, color=True) == "\x1b[38;2;255;255;255m@\x1b[0m"
+
+
+def test_cells_hold_code_points_and_colors():
+    array = np.array([[[255, 0, 0], [0, 0, 255]]], dtype=np.uint8)
+
+    cells = glyphforge.render_cells(array, width=2, height=1, charset=" @")
+
+    assert cells.glyphs.shape == (1, 2)
+    assert cells.glyphs.dtype == np.uint32
+    assert cells.colors.tolist() == [[[255, 0, 0], [0, 0, 255]]]
+    assert cells.text == "  "
+
+
+def test_transparent_pixels_show_the_background():
+    array = np.array([[[0, 0, 0, 0], [0, 0, 0, 255]]], dtype=np.uint8)
+
+    text = glyphforge.render(array, width=2, height=1, charset=" @", background=(255, 255, 255))
+
+    assert text == "@ "
+
+
+def test_engine_applies_adjustments_and_dithering():
+    engine = glyphforge.Engine(charset=" @", adjust=["invert"], dither="ordered")
+
+    text = engine.render(np.full((4, 4), 127, dtype=np.uint8), width=4, height=4)
+
+    assert text.count("@") == 8
+
+
+def test_named_charsets():
+    array = np.array([[0, 255]], dtype=np.uint8)
+
+    assert glyphforge.render(array, width=2, height=1, charset="blocks") == " █"
+    assert glyphforge.CHARSETS["binary"] == " #"
+
+
+def test_invalid_input_is_rejected():
+    with pytest.raises(TypeError):
+        glyphforge.render(np.zeros((2, 2), dtype=np.float32))
+    with pytest.raises(ValueError):
+        glyphforge.render(np.zeros((2, 2, 2), dtype=np.uint8))
+    with pytest.raises(ValueError):
+        glyphforge.Engine(adjust=["sharpen"])
+    with pytest.raises(ValueError):
+        glyphforge.Engine(charset="")
+
+
+@pytest.mark.parametrize("cell_aspect", [0.0, -1.0, float("nan"), float("inf")])
+def test_cell_aspect_must_be_a_positive_number(cell_aspect):
+    with pytest.raises(ValueError):
+        glyphforge.Engine(cell_aspect=cell_aspect)
+
+
+def test_contiguous_and_strided_arrays_render_alike():
+    frame = np.arange(48, dtype=np.uint8).reshape(4, 4, 3) * 5
+
+    larger = np.zeros((4, 6, 3), dtype=np.uint8)
+    larger[:, 1:5] = frame
+
+    contiguous = glyphforge.render(frame, width=2, height=2, color=True)
+
+    assert glyphforge.render(larger[:, 1:5], width=2, height=2, color=True) == contiguous
+    assert glyphforge.render(np.asfortranarray(frame), width=2, height=2, color=True) == contiguous
@@ -0,0 +1,217 @@
+//! JavaScript bindings that turn RGBA pixels, such as a canvas's `ImageData`, into glyph text
+//! or cells in the browser.
+
+use lib_ascii::domain::entities::GlyphEngineAsciiImpl;
+use lib_core::domain::entities::GlyphRenderingEngine;
+use lib_image::domain::entities::{
+    Dither, LumaImage, LumaPixel, Placement, PlacementLayout, ResampleFilter, RgbImage, RgbPixel,
+    RgbaImage,
+};
+use wasm_bindgen::prelude::*;
+
+/// Converts frames to a fixed grid of glyph cells. Settings persist, so one converter can be
+/// reused for every frame of a video or canvas animation.
+#[wasm_bindgen]
+pub struct Converter {
+    columns: usize,
+    rows: usize,
+    charset: String,
+    color: bool,
+    dither: Option<Dither>,
+    cell_aspect: f32,
+    background: RgbPixel,
+}
+
+/// The result of [`Converter::render_cells`]: one glyph and one color per cell, row by row.
+#[wasm_bindgen]
+pub struct Cells {
+    columns: usize,
+    rows: usize,
+    glyphs: Vec<u32>,
+    colors: Vec<u32>,
+}
+
+#[wasm_bindgen]
+impl Converter {
+    /// A converter for a `columns` by `rows` grid, with the standard ` .:-=+*#%@` ramp, no
+    /// color, no dithering, square cells and a black background.
+    #[wasm_bindgen(constructor)]
+    pub fn new(columns: usize, rows: usize) -> Converter {
+        Converter {
+            columns: columns.max(1),
+            rows: rows.max(1),
+            charset: " .:-=+*#%@".to_string(),
+            color: false,
+            dither: None,
+            cell_aspect: 1.0,
+            background: RgbPixel::new(0, 0, 0),
+        }
+    }
+
+    /// Glyphs luma is mapped onto, from dark to bright.
+    #[wasm_bindgen(js_name = setCharset)]
+    pub fn set_charset(&mut self, charset: &str) -> Result<(), JsError> {
+        if charset.is_empty() {
+            return Err(JsError::new("charset must not be empty"));
+        }
+
+        self.charset = charset.to_string();
+        Ok(())
+    }
+
+    /// Whether cells keep the source colors; otherwise every cell is white.
+    #[wasm_bindgen(setter)]
+    pub fn set_color(&mut self, color: bool) {
+        self.color = color;
+    }
+
+    /// `"none"`, `"floyd-steinberg"` or `"ordered"`.
+    #[wasm_bindgen(js_name = setDither)]
+    pub fn set_dither(&mut self, dither: &str) -> Result<(), JsError> {
+        self.dither = match dither {
+            "none" => None,
+            name => Some(name.parse::<Dither>()?),
+        };
+        Ok(())
+    }
+
+    /// Height of a glyph cell divided by its width, usually about 2 for monospace fonts.
+    #[wasm_bindgen(js_name = setCellAspect)]
+    pub fn set_cell_aspect(&mut self, cell_aspect: f32) -> Result<(), JsError> {
+        if !(cell_aspect.is_finite() && cell_aspect > 0.0) {
+            return Err(JsError::new(&format!(
+                "cell aspect must be a positive number, got {cell_aspect}"
+            )));
+        }
+
+        self.cell_aspect = cell_aspect;
+        Ok(())
+    }
+
+    /// Color transparent pixels are blended over, as `0xRRGGBB`.
+    #[wasm_bindgen(setter)]
+    pub fn set_background(&mut self, background: u32) {
+        let [_, r, g, b] = background.to_be_bytes();
+
+        self.background = RgbPixel::new(r, g, b);
+    }
+
+    /// Renders `data`, RGBA bytes as in `ImageData.data`, as plain text with one line per row.
+    #[wasm_bindgen(js_name = renderText)]
+    pub fn render_text(&self, data: &[u8], width: usize, height: usize) -> Result<String, JsError> {
+        let cells: Cells = self.render_cells(data, width, height)?;
+
+        Ok(cells
+            .glyphs
+            .chunks(cells.columns)
+            .map(|row: &[u32]| {
+                row.iter()
+                    .filter_map(|glyph: &u32| char::from_u32(*glyph))
+                    .collect()
+            })
+            .collect::<Vec<String>>()
+            .join("\n"))
+    }
+
+    /// Renders `data`, RGBA bytes as in `ImageData.data`, as cells for drawing with styles or on
+    /// a canvas.
+    #[wasm_bindgen(js_name = renderCells)]
+    pub fn render_cells(&self, data: &[u8], width: usize, height: usize) -> Result<Cells, JsError> {
+        let Some(len) = width
+            .checked_mul(height)
+            .and_then(|pixels: usize| pixels.checked_mul(4))
+        else {
+            return Err(JsError::new(&format!(
+                "{width}x{height} RGBA pixels do not fit in memory"
+            )));
+        };
+        if len == 0 || data.len() != len {
+            return Err(JsError::new(&format!(
+                "expected {width}x{height} RGBA pixels ({len} bytes), got {} bytes",
+                data.len()
+            )));
+        }
+
+        let placement: Placement = Placement {
+            cell_aspect: self.cell_aspect,
+            ..Placement::default()
+        };
+        let layout: PlacementLayout = placement.layout(width, height, self.columns, self.rows);
+        let image: RgbImage = RgbaImage::from_rgba_data(width, height, data.to_vec())
+            .crop(layout.source)
+            .resize(
+                layout.target.width,
+                layout.target.height,
+                ResampleFilter::Box,
+            )?
+            .composite_over(self.background);
+        let pixels: Vec<RgbPixel> = image
+            .data()
+            .chunks_exact(3)
+            .map(|p: &[u8]| RgbPixel::new(p[0], p[1], p[2]))
+            .collect();
+
+        let engine: GlyphEngineAsciiImpl = GlyphEngineAsciiImpl::with_charset(self.charset.chars())
+            .unwrap_or_else(GlyphEngineAsciiImpl::new);
+        let luma: LumaImage = LumaImage::from_luma_data(
+            image.width(),
+            image.height(),
+            pixels
+                .iter()
+                .map(|p: &RgbPixel| LumaPixel::from_rgb(*p).y)
+                .collect(),
+        );
+        let luma: LumaImage = match self.dither {
+            Some(dither) => luma.dither(engine.levels(), dither),
+            None => luma,
+        };
+
+        // Cells outside the placed image are blank, like the bars of a contain fit.
+        let mut cells: Cells = Cells {
+            columns: self.columns,
+            rows: self.rows,
+            glyphs: vec![' ' as u32; self.columns * self.rows],
+            colors: vec![0xFFFFFF; self.columns * self.rows],
+        };
+        for (i, (y, pixel)) in luma.data().iter().zip(&pixels).enumerate() {
+            let column: usize = layout.target.x + i % layout.target.width;
+            let row: usize = layout.target.y + i / layout.target.width;
+            if column >= self.columns || row >= self.rows {
+                continue;
+            }
+
+            let cell: usize = row * self.columns + column;
+            cells.glyphs[cell] = engine.get_char_based_on_luma_value(*y) as u32;
+            if self.color {
+                cells.colors[cell] = u32::from_be_bytes([0, pixel.r, pixel.g, pixel.b]);
+            }
+        }
+
+        Ok(cells)
+    }
+}
+
+#[wasm_bindgen]
+impl Cells {
+    #[wasm_bindgen(getter)]
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Each cell's glyph as a Unicode code point, for `String.fromCodePoint`.
+    #[wasm_bindgen(getter)]
+    pub fn glyphs(&self) -> Vec<u32> {
+        self.glyphs.clone()
+    }
+
+    /// Each cell's color as `0xRRGGBB`.
+    #[wasm_bindgen(getter)]
+    pub fn colors(&self) -> Vec<u32> {
+        self.colors.clone()
+    }
+}
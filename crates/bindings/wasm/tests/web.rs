@@ -0,0 +1,81 @@
+//! Run with `wasm-pack test --node crates/bindings/wasm`.
+
+use glyphforge_wasm::Converter;
+use wasm_bindgen_test::wasm_bindgen_test;
+
+/// RGBA bytes for a row of opaque pixels.
+fn opaque(pixels: &[[u8; 3]]) -> Vec<u8> {
+    pixels
+        .iter()
+        .flat_map(|[r, g, b]| [*r, *g, *b, 255])
+        .collect()
+}
+
+#[wasm_bindgen_test]
+fn renders_text_with_a_custom_charset() {
+    let mut converter: Converter = Converter::new(3, 1);
+    assert!(converter.set_charset(" -@").is_ok());
+
+    let data: Vec<u8> = opaque(&[[0, 0, 0], [128, 128, 128], [255, 255, 255]]);
+    let Ok(text) = converter.render_text(&data, 3, 1) else {
+        panic!("render failed");
+    };
+
+    assert_eq!(text, " -@");
+}
+
+#[wasm_bindgen_test]
+fn cells_keep_source_colors_when_color_is_on() {
+    let mut converter: Converter = Converter::new(2, 1);
+    converter.set_color(true);
+
+    let data: Vec<u8> = opaque(&[[255, 0, 0], [0, 0, 255]]);
+    let Ok(cells) = converter.render_cells(&data, 2, 1) else {
+        panic!("render failed");
+    };
+
+    assert_eq!((cells.columns(), cells.rows()), (2, 1));
+    assert_eq!(cells.colors(), vec![0xFF0000, 0x0000FF]);
+}
+
+#[wasm_bindgen_test]
+fn transparent_pixels_show_the_background() {
+    let mut converter: Converter = Converter::new(2, 1);
+    assert!(converter.set_charset(" @").is_ok());
+    converter.set_background(0xFFFFFF);
+
+    let data: Vec<u8> = vec![0, 0, 0, 0, 0, 0, 0, 255];
+    let Ok(text) = converter.render_text(&data, 2, 1) else {
+        panic!("render failed");
+    };
+
+    assert_eq!(text, "@ ");
+}
+
+#[wasm_bindgen_test]
+fn dithering_mixes_neighbouring_glyphs() {
+    let mut converter: Converter = Converter::new(4, 4);
+    assert!(converter.set_charset(" @").is_ok());
+    assert!(converter.set_dither("ordered").is_ok());
+
+    let data: Vec<u8> = opaque(&[[128, 128, 128]; 16]);
+    let Ok(text) = converter.render_text(&data, 4, 4) else {
+        panic!("render failed");
+    };
+
+    assert_eq!(text.matches('@').count(), 8);
+}
+
+#[wasm_bindgen_test]
+fn invalid_input_is_rejected() {
+    let mut converter: Converter = Converter::new(2, 2);
+
+    assert!(converter.set_charset("").is_err());
+    assert!(converter.set_dither("noise").is_err());
+    assert!(converter.render_text(&[0; 7], 1, 2).is_err());
+    assert!(converter.render_text(&[0; 8], usize::MAX, 2).is_err());
+    assert!(converter.set_cell_aspect(0.0).is_err());
+    assert!(converter.set_cell_aspect(-2.0).is_err());
+    assert!(converter.set_cell_aspect(f32::NAN).is_err());
+    assert!(converter.set_cell_aspect(2.0).is_ok());
+}
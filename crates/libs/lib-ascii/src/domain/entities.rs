@@ -1,3 +1,13 @@
+mod figlet_banner;
+mod figlet_font;
+mod glyph_engine_ansi_color_impl;
 mod glyph_engine_ascii_impl;
+mod subtitle_glyph;
+mod subtitle_track;
 
+pub use figlet_banner::{BannerFilter, FigletBanner};
+pub use figlet_font::{BUNDLED_FONTS, FigletFont, FigletFontError, FigletLayout, SmushRules};
+pub use glyph_engine_ansi_color_impl::GlyphEngineAnsiColorImpl;
 pub use glyph_engine_ascii_impl::GlyphEngineAsciiImpl;
+pub use subtitle_glyph::SubtitleGlyph;
+pub use subtitle_track::{SubtitleCue, SubtitleError, SubtitleFormat, SubtitleTrack};
@@ -0,0 +1,196 @@
+use std::fmt;
+
+use lib_core::domain::entities::{Glyph, GlyphRenderingEngine};
+
+/// Colors letters by their position, like TOIlet's filters.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum BannerFilter {
+    /// Diagonal stripes of the six rainbow colors.
+    Rainbow,
+    /// Bands of blue and gray.
+    Metal,
+}
+
+const RAINBOW: [(u8, u8, u8); 6] = [
+    (255, 0, 0),
+    (255, 255, 0),
+    (0, 255, 0),
+    (0, 255, 255),
+    (0, 0, 255),
+    (255, 0, 255),
+];
+
+const METAL: [(u8, u8, u8); 4] = [(85, 85, 255), (0, 0, 170), (170, 170, 170), (85, 85, 85)];
+
+impl BannerFilter {
+    /// Color of the letter cell at column `x` of line `y`.
+    pub fn color_at(&self, x: usize, y: usize) -> (u8, u8, u8) {
+        match self {
+            BannerFilter::Rainbow => RAINBOW[(x / 2 + y) % RAINBOW.len()],
+            BannerFilter::Metal => METAL[(y + x / 8) / 2 % METAL.len()],
+        }
+    }
+}
+
+/// Text drawn in a [`FigletFont`](crate::domain::entities::FigletFont). Spaces around and
+/// between letters are transparent, so a banner can be layered over an image with
+/// [`GlyphOverlay`](lib_core::domain::entities::GlyphOverlay); hardblanks inside letters are
+/// not.
+#[derive(Debug, PartialEq, Clone)]
+pub struct FigletBanner {
+    rows: Vec<Vec<char>>,
+    hardblank: char,
+    filter: Option<BannerFilter>,
+    column: usize,
+    row: usize,
+}
+
+impl FigletBanner {
+    pub(crate) fn new(rows: Vec<Vec<char>>, hardblank: char) -> Self {
+        Self {
+            rows,
+            hardblank,
+            filter: None,
+            column: 0,
+            row: 0,
+        }
+    }
+
+    /// Colors letters with `filter` when the engine supports color.
+    pub fn with_filter(mut self, filter: BannerFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Moves the banner `column` cells right and `row` lines down.
+    pub fn offset(mut self, column: usize, row: usize) -> Self {
+        self.column = column;
+        self.row = row;
+        self
+    }
+
+    /// Width of the widest line, in cells, without the offset.
+    pub fn width(&self) -> usize {
+        self.rows.iter().map(Vec::len).max().unwrap_or(0)
+    }
+
+    /// Number of lines, without the offset.
+    pub fn height(&self) -> usize {
+        self.rows.len()
+    }
+
+    fn cell(&self, x: usize, y: usize) -> Option<char> {
+        let x: usize = x.checked_sub(self.column)?;
+        let y: usize = y.checked_sub(self.row)?;
+
+        self.rows.get(y)?.get(x).copied()
+    }
+}
+
+impl Glyph for FigletBanner {
+    fn render_into(
+        &self,
+        engine: &dyn GlyphRenderingEngine,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        let reset: &str = engine.get_style_reset();
+
+        for line in 0..self.row + self.rows.len() {
+            if line > 0 {
+                out.write_char('\n')?;
+            }
+
+            // Lines above the banner are left empty.
+            let Some(y) = line.checked_sub(self.row) else {
+                continue;
+            };
+            let row: &Vec<char> = &self.rows[y];
+            let mut styled: bool = false;
+
+            (0..self.column).try_for_each(|_| out.write_char(' '))?;
+
+            for (x, c) in row.iter().enumerate() {
+                match self.filter {
+                    _ if *c == ' ' || *c == self.hardblank => out.write_char(' ')?,
+                    Some(filter) => {
+                        let (r, g, b) = filter.color_at(x, y);
+
+                        out.write_str(&engine.get_text_based_on_colored_char(*c, r, g, b))?;
+                        styled = true;
+                    }
+                    None => out.write_char(*c)?,
+                }
+            }
+
+            if styled {
+                out.write_str(reset)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_child(&mut self, _child: Box<dyn Glyph>) {}
+
+    fn get_child_mut(&mut self, _index: usize) -> Option<&mut dyn Glyph> {
+        None
+    }
+
+    fn is_transparent_at(&self, x: usize, y: usize) -> bool {
+        matches!(self.cell(x, y), None | Some(' '))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use lib_core::domain::entities::{Glyph, GlyphOverlay};
+
+    use super::*;
+    use crate::domain::entities::{GlyphEngineAnsiColorImpl, GlyphEngineAsciiImpl};
+
+    fn banner() -> FigletBanner {
+        FigletBanner::new(vec!["#$#".chars().collect(), "# #".chars().collect()], '$')
+    }
+
+    #[test]
+    fn hardblanks_are_opaque_spaces() {
+        let banner: FigletBanner = banner().offset(1, 1);
+
+        assert_eq!(banner.as_text(&GlyphEngineAsciiImpl::new()), "\n # #\n # #");
+        assert!(!banner.is_transparent_at(2, 1));
+        assert!(banner.is_transparent_at(2, 2));
+        assert!(banner.is_transparent_at(0, 1));
+        assert!(banner.is_transparent_at(5, 5));
+    }
+
+    #[test]
+    fn layers_over_other_glyphs() {
+        let under: FigletBanner = FigletBanner::new(vec!["....".chars().collect(); 2], '$');
+        let overlay: GlyphOverlay =
+            GlyphOverlay::new(Box::new(under), Box::new(banner().offset(1, 0)));
+
+        assert_eq!(overlay.as_text(&GlyphEngineAsciiImpl::new()), ".# #\n.#.#");
+    }
+
+    #[test]
+    fn filters_color_letters_but_not_blanks() {
+        let engine: GlyphEngineAnsiColorImpl<GlyphEngineAsciiImpl> =
+            GlyphEngineAnsiColorImpl::new(GlyphEngineAsciiImpl::new());
+        let banner: FigletBanner = FigletBanner::new(vec!["# #".chars().collect()], '$')
+            .with_filter(BannerFilter::Rainbow);
+
+        assert_eq!(
+            banner.as_text(&engine),
+            "\x1b[38;2;255;0;0m# \x1b[38;2;255;255;0m#\x1b[0m"
+        );
+    }
+
+    #[test]
+    fn filters_need_a_color_engine() {
+        let banner: FigletBanner = banner().with_filter(BannerFilter::Metal);
+
+        assert_eq!(banner.as_text(&GlyphEngineAsciiImpl::new()), "# #\n# #");
+    }
+}
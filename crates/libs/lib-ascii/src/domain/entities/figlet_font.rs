@@ -0,0 +1,524 @@
+use std::{collections::HashMap, fmt, fs, io, path::Path};
+
+use crate::domain::entities::figlet_banner::FigletBanner;
+
+/// Fonts shipped with glyphforge, by the names [`FigletFont::bundled`] accepts.
+pub const BUNDLED_FONTS: [&str; 3] = ["block", "segment", "shade"];
+
+/// Characters every font defines, after the printable ASCII range, in the order they appear.
+const DEUTSCH: [char; 7] = ['Ä', 'Ö', 'Ü', 'ä', 'ö', 'ü', 'ß'];
+
+/// How neighbouring letters are pushed together.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum FigletLayout {
+    /// Each letter keeps its full width.
+    FullWidth,
+    /// Letters move together until they touch.
+    Fitting,
+    /// Letters move one column further, merging the touching characters by `SmushRules`; no
+    /// rules means any two characters merge, the right one winning.
+    Smushing(SmushRules),
+}
+
+/// The FIGfont smushing rules, as the bits of the header's layout fields.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct SmushRules(pub u8);
+
+impl SmushRules {
+    /// Two equal characters merge into one.
+    pub const EQUAL: SmushRules = SmushRules(1);
+    /// An underscore is replaced by any of `|/\[]{}()<>`.
+    pub const UNDERSCORE: SmushRules = SmushRules(2);
+    /// Of `|`, `/\`, `[]`, `{}`, `()` and `<>`, the later class replaces the earlier.
+    pub const HIERARCHY: SmushRules = SmushRules(4);
+    /// Opposing brackets, braces or parentheses merge into `|`.
+    pub const OPPOSITE_PAIR: SmushRules = SmushRules(8);
+    /// `/\` merges into `|`, `\/` into `Y` and `><` into `X`.
+    pub const BIG_X: SmushRules = SmushRules(16);
+    /// Two hardblanks merge into one.
+    pub const HARDBLANK: SmushRules = SmushRules(32);
+
+    pub fn contains(&self, rule: SmushRules) -> bool {
+        self.0 & rule.0 != 0
+    }
+
+    /// Whether no rule is set, which lets any two characters merge.
+    pub fn is_universal(&self) -> bool {
+        self.0 == 0
+    }
+}
+
+/// A FIGlet `.flf` or TOIlet `.tlf` font: large letters drawn with several lines of characters.
+#[derive(PartialEq, Clone)]
+pub struct FigletFont {
+    height: usize,
+    baseline: usize,
+    /// Drawn as a space, but never merged away, so letters keep their inner gaps.
+    hardblank: char,
+    layout: FigletLayout,
+    glyphs: HashMap<char, Vec<Vec<char>>>,
+}
+
+impl FigletFont {
+    /// Parses the contents of a `.flf` or `.tlf` file.
+    pub fn parse(source: &str) -> Result<Self, FigletFontError> {
+        let mut lines = source.lines();
+        let header: &str = lines.next().ok_or(FigletFontError::NotAFont)?;
+        let signature: &str = header
+            .get(..5)
+            .filter(|s: &&str| matches!(*s, "flf2a" | "tlf2a"))
+            .ok_or(FigletFontError::NotAFont)?;
+        let mut fields = header[signature.len()..].split_whitespace();
+        let hardblank: char = fields
+            .next()
+            .and_then(|field: &str| field.chars().next())
+            .ok_or_else(|| FigletFontError::malformed("missing hardblank"))?;
+        let numbers: Vec<i64> = fields
+            .map(|field: &str| field.parse::<i64>())
+            .collect::<Result<_, _>>()
+            .map_err(|e| FigletFontError::malformed(format!("invalid header: {e}")))?;
+        // Height, baseline, max length, old layout and comment lines are required; print
+        // direction, full layout and the code tag count are not.
+        let [height, baseline, _, old_layout, comment_lines, ..] = numbers[..] else {
+            return Err(FigletFontError::malformed("header has too few fields"));
+        };
+        if height < 1 {
+            return Err(FigletFontError::malformed("height must be positive"));
+        }
+
+        let layout: FigletLayout = match numbers.get(6) {
+            Some(full) if full & 128 != 0 => FigletLayout::Smushing(SmushRules((full & 63) as u8)),
+            Some(full) if full & 64 != 0 => FigletLayout::Fitting,
+            Some(_) => FigletLayout::FullWidth,
+            None if old_layout < 0 => FigletLayout::FullWidth,
+            None if old_layout == 0 => FigletLayout::Fitting,
+            None => FigletLayout::Smushing(SmushRules((old_layout & 63) as u8)),
+        };
+
+        let mut lines = lines.skip(comment_lines.max(0) as usize).peekable();
+        let height: usize = height as usize;
+        let mut font: FigletFont = FigletFont {
+            height,
+            baseline: baseline.clamp(1, height as i64) as usize,
+            hardblank,
+            layout,
+            glyphs: HashMap::new(),
+        };
+
+        for c in (' '..='~').chain(DEUTSCH) {
+            if lines.peek().is_none() {
+                break;
+            }
+
+            let glyph: Vec<Vec<char>> = read_glyph(&mut lines, height, c)?;
+            font.glyphs.insert(c, glyph);
+        }
+
+        // Code-tagged characters follow, each after a line starting with its code.
+        while let Some(tag) = lines.next() {
+            if tag.trim().is_empty() {
+                continue;
+            }
+
+            let code: Option<char> = tag
+                .split_whitespace()
+                .next()
+                .and_then(parse_code)
+                .and_then(char::from_u32);
+            let glyph: Vec<Vec<char>> = read_glyph(&mut lines, height, code.unwrap_or(' '))?;
+
+            // Negative codes name glyphs that no input maps to.
+            if let Some(code) = code {
+                font.glyphs.insert(code, glyph);
+            }
+        }
+
+        Ok(font)
+    }
+
+    /// Reads and parses a font file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, FigletFontError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    /// One of the [`BUNDLED_FONTS`].
+    pub fn bundled(name: &str) -> Option<Self> {
+        let source: &str = match name {
+            "block" => include_str!("../../../fonts/block.flf"),
+            "segment" => include_str!("../../../fonts/segment.flf"),
+            "shade" => include_str!("../../../fonts/shade.tlf"),
+            _ => return None,
+        };
+
+        Some(Self::parse(source).expect("bundled fonts are valid"))
+    }
+
+    /// The same font, laid out as `layout` instead of the way its header asks for.
+    pub fn with_layout(mut self, layout: FigletLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Lines from the top of a letter down to the baseline of a flat-bottomed capital.
+    pub fn baseline(&self) -> usize {
+        self.baseline
+    }
+
+    pub fn layout(&self) -> FigletLayout {
+        self.layout
+    }
+
+    /// Draws `text` in this font, one block of lines per line of `text`. Characters the font
+    /// does not define are left out.
+    pub fn render(&self, text: &str) -> FigletBanner {
+        let rows: Vec<Vec<char>> = text
+            .split('\n')
+            .flat_map(|line: &str| self.render_line(line))
+            .collect();
+
+        FigletBanner::new(rows, self.hardblank)
+    }
+
+    fn render_line(&self, line: &str) -> Vec<Vec<char>> {
+        let mut rows: Vec<Vec<char>> = vec![Vec::new(); self.height];
+        let mut previous_width: usize = 0;
+
+        for glyph in line.chars().filter_map(|c: char| self.glyphs.get(&c)) {
+            let width: usize = glyph_width(glyph);
+            let amount: usize = self.overlap(&rows, glyph, previous_width, width);
+
+            for (row, glyph_row) in rows.iter_mut().zip(glyph) {
+                let start: usize = row.len() - amount.min(row.len());
+
+                for (k, right) in glyph_row.iter().take(amount).enumerate() {
+                    if let Some(left) = row.get_mut(start + k) {
+                        *left = self
+                            .smush(*left, *right, previous_width, width)
+                            .unwrap_or(*right);
+                    }
+                }
+                row.extend(glyph_row.iter().skip(amount));
+            }
+
+            previous_width = width;
+        }
+
+        rows
+    }
+
+    /// How many columns `glyph` can overlap the end of `rows` by, as figlet's `smushamt`.
+    fn overlap(
+        &self,
+        rows: &[Vec<char>],
+        glyph: &[Vec<char>],
+        previous_width: usize,
+        width: usize,
+    ) -> usize {
+        if self.layout == FigletLayout::FullWidth {
+            return 0;
+        }
+
+        rows.iter()
+            .zip(glyph)
+            .map(|(row, glyph_row)| {
+                // The last visible character of the row and the first of the glyph.
+                let left_end: usize = row.iter().rposition(|c: &char| *c != ' ').unwrap_or(0);
+                let left: Option<char> = row.get(left_end).copied();
+                let right_start: usize = glyph_row
+                    .iter()
+                    .position(|c: &char| *c != ' ')
+                    .unwrap_or(glyph_row.len());
+                let right: Option<char> = glyph_row.get(right_start).copied();
+                // Negative for the first glyph of a line, whose leading blanks go nowhere.
+                let gap: isize = right_start as isize + row.len() as isize - left_end as isize - 1;
+
+                let amount: isize = match (left, right) {
+                    (None | Some(' '), _) => gap + 1,
+                    (Some(left), Some(right))
+                        if self.smush(left, right, previous_width, width).is_some() =>
+                    {
+                        gap + 1
+                    }
+                    _ => gap,
+                };
+
+                amount.max(0) as usize
+            })
+            .fold(width, usize::min)
+    }
+
+    /// The character `left` and `right` merge into, as figlet's `smushem`, or `None` when they
+    /// must stay apart.
+    fn smush(&self, left: char, right: char, previous_width: usize, width: usize) -> Option<char> {
+        if left == ' ' {
+            return Some(right);
+        }
+        if right == ' ' {
+            return Some(left);
+        }
+        if previous_width < 2 || width < 2 {
+            return None;
+        }
+
+        let FigletLayout::Smushing(rules) = self.layout else {
+            return None;
+        };
+        let hardblank: char = self.hardblank;
+
+        if rules.is_universal() {
+            return Some(if right == hardblank { left } else { right });
+        }
+        if rules.contains(SmushRules::HARDBLANK) && left == hardblank && right == hardblank {
+            return Some(left);
+        }
+        if left == hardblank || right == hardblank {
+            return None;
+        }
+        if rules.contains(SmushRules::EQUAL) && left == right {
+            return Some(left);
+        }
+        if rules.contains(SmushRules::UNDERSCORE) {
+            if left == '_' && "|/\\[]{}()<>".contains(right) {
+                return Some(right);
+            }
+            if right == '_' && "|/\\[]{}()<>".contains(left) {
+                return Some(left);
+            }
+        }
+        if rules.contains(SmushRules::HIERARCHY) {
+            let class = |c: char| {
+                ["|", "/\\", "[]", "{}", "()", "<>"]
+                    .iter()
+                    .position(|s| s.contains(c))
+            };
+
+            if let (Some(l), Some(r)) = (class(left), class(right)) {
+                match l.cmp(&r) {
+                    std::cmp::Ordering::Less => return Some(right),
+                    std::cmp::Ordering::Greater => return Some(left),
+                    std::cmp::Ordering::Equal => {}
+                }
+            }
+        }
+        if rules.contains(SmushRules::OPPOSITE_PAIR)
+            && matches!(
+                (left, right),
+                ('[', ']') | (']', '[') | ('{', '}') | ('}', '{') | ('(', ')') | (')', '(')
+            )
+        {
+            return Some('|');
+        }
+        if rules.contains(SmushRules::BIG_X) {
+            match (left, right) {
+                ('/', '\\') => return Some('|'),
+                ('\\', '/') => return Some('Y'),
+                ('>', '<') => return Some('X'),
+                _ => {}
+            }
+        }
+
+        None
+    }
+}
+
+impl fmt::Debug for FigletFont {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FigletFont")
+            .field("height", &self.height)
+            .field("baseline", &self.baseline)
+            .field("hardblank", &self.hardblank)
+            .field("layout", &self.layout)
+            .field("glyphs", &self.glyphs.len())
+            .finish()
+    }
+}
+
+/// Reads the `height` lines drawing `c`, without their endmarks.
+fn read_glyph<'a>(
+    lines: &mut impl Iterator<Item = &'a str>,
+    height: usize,
+    c: char,
+) -> Result<Vec<Vec<char>>, FigletFontError> {
+    (0..height)
+        .map(|_| {
+            let line: &str = lines.next().ok_or_else(|| {
+                FigletFontError::malformed(format!("font ends inside character {c:?}"))
+            })?;
+            let line: &str = line.trim_end();
+            let line: &str = match line.chars().last() {
+                Some(endmark) => line.trim_end_matches(endmark),
+                None => line,
+            };
+
+            Ok(line.chars().collect())
+        })
+        .collect()
+}
+
+/// Parses a code tag, which may be decimal, hexadecimal after `0x` or octal after `0`.
+fn parse_code(code: &str) -> Option<u32> {
+    if let Some(hex) = code.strip_prefix("0x").or_else(|| code.strip_prefix("0X")) {
+        u32::from_str_radix(hex, 16).ok()
+    } else if code.len() > 1 && code.starts_with('0') {
+        u32::from_str_radix(&code[1..], 8).ok()
+    } else {
+        code.parse::<u32>().ok()
+    }
+}
+
+fn glyph_width(glyph: &[Vec<char>]) -> usize {
+    glyph.iter().map(Vec::len).max().unwrap_or(0)
+}
+
+#[derive(Debug)]
+pub enum FigletFontError {
+    /// The data does not start with a `flf2a` or `tlf2a` signature.
+    NotAFont,
+    Malformed(String),
+    Io(io::Error),
+}
+
+impl FigletFontError {
+    fn malformed(message: impl fmt::Display) -> Self {
+        FigletFontError::Malformed(message.to_string())
+    }
+}
+
+impl fmt::Display for FigletFontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FigletFontError::NotAFont => write!(f, "not a FIGlet or TOIlet font"),
+            FigletFontError::Malformed(message) => write!(f, "invalid font: {message}"),
+            FigletFontError::Io(error) => write!(f, "could not read font: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for FigletFontError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FigletFontError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for FigletFontError {
+    fn from(error: io::Error) -> Self {
+        FigletFontError::Io(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use lib_core::domain::entities::{Glyph, GlyphRenderingEngine};
+
+    use super::*;
+    use crate::domain::entities::GlyphEngineAsciiImpl;
+
+    /// A two-line font defining `/`, `\`, `_`, `|`, `[`, `]`, `h` and `j`, laid out by
+    /// `old_layout`.
+    fn font(old_layout: i64) -> FigletFont {
+        let mut source: String = format!("flf2a$ 2 2 4 {old_layout} 1\ncomment\n");
+        for c in ' '..='~' {
+            let rows: [&str; 2] = match c {
+                '/' => [" /@", "/ @@"],
+                '\\' => ["\\ @", " \\@@"],
+                '_' => ["  @", "__@@"],
+                '|' => ["||@", "||@@"],
+                '[' => ["[ @", "[ @@"],
+                ']' => [" ]@", " ]@@"],
+                'h' => ["$h@", "$h@@"],
+                'j' => ["j$@", "j$@@"],
+                _ => ["@", "@@"],
+            };
+            source.push_str(&format!("{}\n{}\n", rows[0], rows[1]));
+        }
+
+        FigletFont::parse(&source).unwrap()
+    }
+
+    fn text(font: &FigletFont, input: &str) -> String {
+        let engine: GlyphEngineAsciiImpl = GlyphEngineAsciiImpl::new();
+
+        font.render(input)
+            .as_text(&engine as &dyn GlyphRenderingEngine)
+    }
+
+    #[test]
+    fn reads_the_header_layout() {
+        assert_eq!(font(-1).layout(), FigletLayout::FullWidth);
+        assert_eq!(font(0).layout(), FigletLayout::Fitting);
+        assert_eq!(
+            font(1 | 16).layout(),
+            FigletLayout::Smushing(SmushRules(1 | 16))
+        );
+        assert_eq!(font(-1).height(), 2);
+    }
+
+    #[test]
+    fn full_layout_field_overrides_old_layout() {
+        let font: FigletFont = FigletFont::parse("flf2a$ 1 1 2 -1 0 0 128\n@\n").unwrap();
+
+        assert_eq!(font.layout(), FigletLayout::Smushing(SmushRules(0)));
+    }
+
+    #[test]
+    fn rejects_other_files() {
+        assert!(matches!(
+            FigletFont::parse("P5 1 1 255\n"),
+            Err(FigletFontError::NotAFont)
+        ));
+        assert!(matches!(
+            FigletFont::parse("flf2a$ 2 2 4 -1 0\n a@\n"),
+            Err(FigletFontError::Malformed(_))
+        ));
+    }
+
+    #[test]
+    fn full_width_keeps_every_column() {
+        assert_eq!(text(&font(-1), "/\\"), " /\\ \n/  \\");
+    }
+
+    #[test]
+    fn fitting_moves_letters_until_they_touch() {
+        assert_eq!(text(&font(0), "/\\"), " /\\ \n/  \\");
+    }
+
+    #[test]
+    fn smushing_merges_touching_characters() {
+        assert_eq!(text(&font(16), "/\\"), " | \n/ \\");
+        assert_eq!(text(&font(16), "\\/"), "\\ /\n Y ");
+        assert_eq!(text(&font(8), "]["), "| \n| ");
+        assert_eq!(text(&font(2), "_|"), " ||\n_||");
+        assert_eq!(text(&font(4), "|/"), "||/\n|/ ");
+        assert_eq!(text(&font(1), "||"), "|||\n|||");
+    }
+
+    #[test]
+    fn hardblanks_are_not_merged_without_their_rule() {
+        assert_eq!(text(&font(32), "jh"), "j h\nj h");
+        assert_eq!(text(&font(1), "jh"), "j  h\nj  h");
+    }
+
+    #[test]
+    fn lines_are_stacked() {
+        assert_eq!(text(&font(-1), "|\n|"), "||\n||\n||\n||");
+    }
+
+    #[test]
+    fn bundled_fonts_parse() {
+        for name in BUNDLED_FONTS {
+            let font: FigletFont = FigletFont::bundled(name).unwrap();
+
+            assert!(font.glyphs.contains_key(&'A'), "{name}");
+        }
+        assert!(FigletFont::bundled("missing").is_none());
+    }
+}
@@ -0,0 +1,97 @@
+use std::fmt;
+
+use lib_core::domain::entities::GlyphRenderingEngine;
+
+/// Wraps another engine and tints colored cells with 24-bit ANSI foreground escapes.
+pub struct GlyphEngineAnsiColorImpl<E: GlyphRenderingEngine> {
+    inner: E,
+}
+
+impl<E: GlyphRenderingEngine> GlyphEngineAnsiColorImpl<E> {
+    pub fn new(inner: E) -> Self {
+        Self { inner }
+    }
+}
+
+impl<E: GlyphRenderingEngine> GlyphRenderingEngine for GlyphEngineAnsiColorImpl<E> {
+    fn get_char_based_on_luma_value(&self, y: u8) -> char {
+        self.inner.get_char_based_on_luma_value(y)
+    }
+
+    fn get_text_based_on_rgb_value(&self, r: u8, g: u8, b: u8, y: u8) -> String {
+        format!(
+            "\x1b[38;2;{r};{g};{b}m{}",
+            self.inner.get_char_based_on_luma_value(y)
+        )
+    }
+
+    fn write_text_based_on_rgb_value(
+        &self,
+        out: &mut dyn fmt::Write,
+        r: u8,
+        g: u8,
+        b: u8,
+        y: u8,
+    ) -> fmt::Result {
+        write!(
+            out,
+            "\x1b[38;2;{r};{g};{b}m{}",
+            self.inner.get_char_based_on_luma_value(y)
+        )
+    }
+
+    /// An escape with three two-digit channels and a one-byte glyph; brighter colors and
+    /// wider glyphs take a few more.
+    fn colored_cell_size_hint(&self) -> usize {
+        17
+    }
+
+    fn get_text_based_on_colored_char(&self, c: char, r: u8, g: u8, b: u8) -> String {
+        format!("\x1b[38;2;{r};{g};{b}m{c}")
+    }
+
+    fn get_style_reset(&self) -> &str {
+        "\x1b[0m"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::domain::entities::GlyphEngineAsciiImpl;
+
+    #[test]
+    fn colored_cell_uses_truecolor_escape() {
+        let engine: GlyphEngineAnsiColorImpl<GlyphEngineAsciiImpl> =
+            GlyphEngineAnsiColorImpl::new(GlyphEngineAsciiImpl::new());
+
+        let result: String = engine.get_text_based_on_rgb_value(255, 0, 10, 255);
+
+        assert_eq!(result, "\x1b[38;2;255;0;10m@");
+    }
+
+    #[test]
+    fn writes_the_same_escape_as_it_returns() {
+        let engine: GlyphEngineAnsiColorImpl<GlyphEngineAsciiImpl> =
+            GlyphEngineAnsiColorImpl::new(GlyphEngineAsciiImpl::new());
+        let mut written: String = String::new();
+
+        engine
+            .write_text_based_on_rgb_value(&mut written, 255, 0, 10, 255)
+            .unwrap();
+
+        assert_eq!(written, engine.get_text_based_on_rgb_value(255, 0, 10, 255));
+    }
+
+    #[test]
+    fn luma_cell_is_left_uncolored() {
+        let engine: GlyphEngineAnsiColorImpl<GlyphEngineAsciiImpl> =
+            GlyphEngineAnsiColorImpl::new(GlyphEngineAsciiImpl::new());
+
+        let result: char = engine.get_char_based_on_luma_value(0);
+
+        assert_eq!(result, ' ');
+    }
+}
@@ -1,15 +1,28 @@
 use lib_core::domain::entities::GlyphRenderingEngine;
 
+#[derive(Debug, Clone)]
 pub struct GlyphEngineAsciiImpl {
-    charset: [char; 10],
+    charset: Vec<char>,
 }
 
 impl GlyphEngineAsciiImpl {
     pub fn new() -> Self {
         Self {
-            charset: [' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'],
+            charset: vec![' ', '.', ':', '-', '=', '+', '*', '#', '%', '@'],
         }
     }
+
+    /// Maps luma onto `charset`, ordered from darkest to brightest; `None` when it is empty.
+    pub fn with_charset(charset: impl IntoIterator<Item = char>) -> Option<Self> {
+        let charset: Vec<char> = charset.into_iter().collect();
+
+        (!charset.is_empty()).then_some(Self { charset })
+    }
+
+    /// How many glyphs luma is spread over.
+    pub fn levels(&self) -> usize {
+        self.charset.len()
+    }
 }
 
 impl GlyphRenderingEngine for GlyphEngineAsciiImpl {
@@ -52,4 +65,24 @@ mod tests {
 
         assert_eq!(result, '+');
     }
+
+    #[test]
+    fn custom_charset_spreads_luma_evenly() {
+        let engine: GlyphEngineAsciiImpl =
+            GlyphEngineAsciiImpl::with_charset(" ░▒▓█".chars()).unwrap();
+
+        let result: String = [0, 60, 128, 180, 255]
+            .map(|y: u8| engine.get_char_based_on_luma_value(y))
+            .iter()
+            .collect();
+
+        assert_eq!(result, " ░▒▓█");
+    }
+
+    #[test]
+    fn empty_charset_is_rejected() {
+        let engine: Option<GlyphEngineAsciiImpl> = GlyphEngineAsciiImpl::with_charset([]);
+
+        assert!(engine.is_none());
+    }
 }
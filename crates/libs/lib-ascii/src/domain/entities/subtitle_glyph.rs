@@ -0,0 +1,314 @@
+use std::fmt;
+
+use lib_core::domain::entities::{Glyph, GlyphRenderingEngine, HorizontalAlign, VerticalAlign};
+
+use crate::domain::entities::SubtitleCue;
+
+/// Subtitle cues laid out on a `width` by `height` area of cells, to layer over a frame or draw
+/// in rows of their own. Lines too long for the area are wrapped at spaces, keeping a cell clear
+/// on either side.
+///
+/// Cues without a position are stacked along the screen edge their alignment names, in the
+/// order given, and positioned ones are pinned where they ask. Cells without text are
+/// transparent, except that with a band every row holding text is blanked across the whole
+/// width so the text stands out from the frame beneath.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SubtitleGlyph {
+    width: usize,
+    height: usize,
+    lines: Vec<PlacedLine>,
+    band: bool,
+}
+
+/// One wrapped line of a cue and where it landed.
+#[derive(Debug, PartialEq, Clone)]
+struct PlacedLine {
+    row: usize,
+    column: usize,
+    text: Vec<char>,
+    color: Option<(u8, u8, u8)>,
+}
+
+impl SubtitleGlyph {
+    pub fn new<'a>(
+        cues: impl IntoIterator<Item = &'a SubtitleCue>,
+        width: usize,
+        height: usize,
+    ) -> Self {
+        let wrap_width: usize = width.saturating_sub(2).max(1);
+        let mut lines: Vec<PlacedLine> = Vec::new();
+        let (mut top, mut bottom): (usize, usize) = (0, height);
+
+        for cue in cues {
+            let wrapped: Vec<Vec<char>> = cue
+                .lines
+                .iter()
+                .flat_map(|line: &String| wrap(line, wrap_width))
+                .collect();
+            let count: usize = wrapped.len();
+            let first_row: isize = match cue.position {
+                Some((_, y)) => {
+                    let anchor: isize = (y * height as f32).round() as isize;
+
+                    match cue.vertical {
+                        VerticalAlign::Top => anchor,
+                        VerticalAlign::Middle => anchor - (count / 2) as isize,
+                        VerticalAlign::Bottom => anchor - count as isize,
+                    }
+                }
+                None => match cue.vertical {
+                    VerticalAlign::Top => {
+                        top += count;
+                        (top - count) as isize
+                    }
+                    VerticalAlign::Middle => (height.saturating_sub(count) / 2) as isize,
+                    VerticalAlign::Bottom => {
+                        bottom = bottom.saturating_sub(count);
+                        bottom as isize
+                    }
+                },
+            };
+
+            for (i, text) in wrapped.into_iter().enumerate() {
+                let Some(row) = usize::try_from(first_row + i as isize)
+                    .ok()
+                    .filter(|row: &usize| *row < height)
+                else {
+                    continue;
+                };
+                let column: usize = match cue.position {
+                    Some((x, _)) => {
+                        let anchor: isize = (x * width as f32).round() as isize;
+                        let start: isize = match cue.horizontal {
+                            HorizontalAlign::Left => anchor,
+                            HorizontalAlign::Center => anchor - (text.len() / 2) as isize,
+                            HorizontalAlign::Right => anchor - text.len() as isize,
+                        };
+
+                        start.clamp(0, width.saturating_sub(text.len()) as isize) as usize
+                    }
+                    None => 1 + cue.horizontal.split(wrap_width - text.len()).0,
+                };
+
+                lines.push(PlacedLine {
+                    row,
+                    column,
+                    text,
+                    color: cue.color,
+                });
+            }
+        }
+
+        Self {
+            width,
+            height,
+            lines,
+            band: false,
+        }
+    }
+
+    /// Blanks every row holding text across the whole width.
+    pub fn with_band(mut self, band: bool) -> Self {
+        self.band = band;
+        self
+    }
+
+    /// Whether any cue landed on the area.
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// The character at column `x` of line `y` and the line it belongs to; later cues cover
+    /// earlier ones.
+    fn cell(&self, x: usize, y: usize) -> Option<(char, &PlacedLine)> {
+        self.lines.iter().rev().find_map(|line: &PlacedLine| {
+            let c: char = *line.text.get(x.checked_sub(line.column)?)?;
+
+            (line.row == y).then_some((c, line))
+        })
+    }
+}
+
+impl Glyph for SubtitleGlyph {
+    fn render_into(
+        &self,
+        engine: &dyn GlyphRenderingEngine,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        let reset: &str = engine.get_style_reset();
+
+        for y in 0..self.height {
+            let mut styled: bool = false;
+
+            if y > 0 {
+                out.write_char('\n')?;
+            }
+
+            for x in 0..self.width {
+                match self.cell(x, y).map(|(c, line)| (c, line.color)) {
+                    Some((c, Some((r, g, b)))) if c != ' ' => {
+                        out.write_str(&engine.get_text_based_on_colored_char(c, r, g, b))?;
+                        styled = true;
+                    }
+                    cell => {
+                        if styled {
+                            out.write_str(reset)?;
+                            styled = false;
+                        }
+                        out.write_char(cell.map_or(' ', |(c, _)| c))?;
+                    }
+                }
+            }
+
+            if styled {
+                out.write_str(reset)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_child(&mut self, _child: Box<dyn Glyph>) {}
+
+    fn get_child_mut(&mut self, _index: usize) -> Option<&mut dyn Glyph> {
+        None
+    }
+
+    fn is_transparent_at(&self, x: usize, y: usize) -> bool {
+        if x >= self.width || y >= self.height {
+            return true;
+        }
+
+        let banded: bool = self.band && self.lines.iter().any(|line: &PlacedLine| line.row == y);
+
+        !banded && self.cell(x, y).is_none()
+    }
+}
+
+/// Breaks `line` at spaces into lines of at most `width` characters, cutting words that are
+/// longer than that.
+fn wrap(line: &str, width: usize) -> Vec<Vec<char>> {
+    let mut lines: Vec<Vec<char>> = Vec::new();
+    let mut current: Vec<char> = Vec::new();
+
+    for word in line.split_whitespace() {
+        let word: Vec<char> = word.chars().collect();
+
+        if !current.is_empty() && current.len() + 1 + word.len() > width {
+            lines.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.extend(word);
+
+        while current.len() > width {
+            let rest: Vec<char> = current.split_off(width);
+            lines.push(std::mem::replace(&mut current, rest));
+        }
+    }
+
+    if !current.is_empty() || lines.is_empty() {
+        lines.push(current);
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use lib_core::domain::entities::{Glyph, GlyphCompositor, GlyphLayer};
+
+    use super::*;
+    use crate::domain::entities::{GlyphEngineAnsiColorImpl, GlyphEngineAsciiImpl};
+
+    fn cue(text: &str) -> SubtitleCue {
+        SubtitleCue::new(Duration::ZERO, Duration::from_secs(1), text)
+    }
+
+    #[test]
+    fn wraps_and_stacks_later_cues_above_earlier_ones() {
+        let first: SubtitleCue = cue("one two three");
+        let second: SubtitleCue = cue("four");
+
+        let glyph: SubtitleGlyph = SubtitleGlyph::new([&first, &second], 10, 4);
+
+        assert_eq!(
+            glyph.as_text(&GlyphEngineAsciiImpl::new()),
+            "          \n   four   \n one two  \n  three   "
+        );
+    }
+
+    #[test]
+    fn aligns_to_edges_and_positions() {
+        let top: SubtitleCue = SubtitleCue {
+            horizontal: HorizontalAlign::Right,
+            vertical: VerticalAlign::Top,
+            ..cue("top")
+        };
+        let pinned: SubtitleCue = SubtitleCue {
+            horizontal: HorizontalAlign::Left,
+            vertical: VerticalAlign::Middle,
+            position: Some((0.25, 0.4)),
+            ..cue("pin")
+        };
+
+        let glyph: SubtitleGlyph = SubtitleGlyph::new([&top, &pinned], 8, 3);
+
+        assert_eq!(
+            glyph.as_text(&GlyphEngineAsciiImpl::new()),
+            "    top \n  pin   \n        "
+        );
+    }
+
+    #[test]
+    fn bands_rows_with_text_over_other_glyphs() {
+        let subtitle: SubtitleCue = cue("hi");
+        let frame: SubtitleGlyph = SubtitleGlyph::new([&cue("#####"), &cue("#####")], 7, 2);
+        let compositor = |band: bool| {
+            GlyphCompositor::new(7, 2)
+                .with_layer(GlyphLayer::new(Box::new(frame.clone())))
+                .with_layer(GlyphLayer::new(Box::new(
+                    SubtitleGlyph::new([&subtitle], 7, 2).with_band(band),
+                )))
+                .as_text(&GlyphEngineAsciiImpl::new())
+        };
+
+        assert_eq!(compositor(false), " ##### \n #hi## ");
+        assert_eq!(compositor(true), " ##### \n  hi   ");
+    }
+
+    #[test]
+    fn colors_text_but_not_spaces() {
+        let colored: SubtitleCue = SubtitleCue {
+            color: Some((255, 255, 0)),
+            ..cue("a b")
+        };
+        let engine: GlyphEngineAnsiColorImpl<GlyphEngineAsciiImpl> =
+            GlyphEngineAnsiColorImpl::new(GlyphEngineAsciiImpl::new());
+
+        let glyph: SubtitleGlyph = SubtitleGlyph::new([&colored], 5, 1);
+
+        assert_eq!(
+            glyph.as_text(&engine),
+            " \x1b[38;2;255;255;0ma\x1b[0m \x1b[38;2;255;255;0mb\x1b[0m "
+        );
+    }
+
+    #[test]
+    fn cuts_words_longer_than_a_line() {
+        assert_eq!(
+            wrap("abcdefg hi", 3),
+            vec![
+                "abc".chars().collect::<Vec<char>>(),
+                "def".chars().collect(),
+                "g".chars().collect(),
+                "hi".chars().collect(),
+            ]
+        );
+    }
+}
@@ -0,0 +1,697 @@
+use std::{collections::HashMap, fmt, fs, io, path::Path, time::Duration};
+
+use lib_core::domain::entities::{HorizontalAlign, VerticalAlign};
+
+/// Subtitle file formats a [`SubtitleTrack`] is read from.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum SubtitleFormat {
+    /// SubRip `.srt`.
+    Srt,
+    /// WebVTT `.vtt`.
+    WebVtt,
+    /// Advanced SubStation Alpha `.ass`, and the older SubStation Alpha `.ssa`.
+    Ass,
+}
+
+impl SubtitleFormat {
+    /// The format `path`'s extension names, if it names one.
+    pub fn from_extension(path: &Path) -> Option<Self> {
+        let extension: String = path.extension()?.to_str()?.to_ascii_lowercase();
+
+        match extension.as_str() {
+            "srt" => Some(SubtitleFormat::Srt),
+            "vtt" => Some(SubtitleFormat::WebVtt),
+            "ass" | "ssa" => Some(SubtitleFormat::Ass),
+            _ => None,
+        }
+    }
+
+    /// Recognises WebVTT by its signature and ASS by its section headers; anything else is taken
+    /// for SRT.
+    pub fn detect(source: &str) -> Self {
+        let source: &str = source.trim_start_matches('\u{feff}');
+
+        if source.starts_with("WEBVTT") {
+            SubtitleFormat::WebVtt
+        } else if source.contains("[Script Info]") || source.contains("[Events]") {
+            SubtitleFormat::Ass
+        } else {
+            SubtitleFormat::Srt
+        }
+    }
+}
+
+/// Text shown from `start` until just before `end`, measured from the start of the video.
+#[derive(Debug, PartialEq, Clone)]
+pub struct SubtitleCue {
+    pub start: Duration,
+    pub end: Duration,
+    /// Lines as authored, without markup; lines too long for the screen are wrapped when drawn.
+    pub lines: Vec<String>,
+    /// Text color; `None` leaves it to the engine.
+    pub color: Option<(u8, u8, u8)>,
+    pub horizontal: HorizontalAlign,
+    pub vertical: VerticalAlign,
+    /// Point the text is pinned to, as fractions of the screen's width and height. The alignment
+    /// says which side of the text touches it; without a position it says which edge of the
+    /// screen the text sits at.
+    pub position: Option<(f32, f32)>,
+}
+
+impl SubtitleCue {
+    /// Plain text at the bottom centre, one line per `\n`.
+    pub fn new(start: Duration, end: Duration, text: &str) -> Self {
+        Self {
+            start,
+            end,
+            lines: text.lines().map(str::to_string).collect(),
+            color: None,
+            horizontal: HorizontalAlign::Center,
+            vertical: VerticalAlign::Bottom,
+            position: None,
+        }
+    }
+
+    /// The text of an ASS dialogue event, with its `{\...}` override tags applied. Without the
+    /// script's resolution there is nothing to measure `\pos` against, so it is ignored.
+    pub fn from_ass_text(start: Duration, end: Duration, text: &str) -> Self {
+        let mut cue: SubtitleCue = SubtitleCue::new(start, end, "");
+        cue.apply_ass_text(text, None);
+        cue
+    }
+
+    pub fn is_active_at(&self, time: Duration) -> bool {
+        self.start <= time && time < self.end
+    }
+
+    /// Sets the lines from ASS event text, taking alignment, position and color from its
+    /// override tags; other tags, such as bold and italic, are dropped.
+    fn apply_ass_text(&mut self, text: &str, resolution: Option<(f32, f32)>) {
+        let mut plain: String = String::new();
+        let mut rest: &str = text;
+
+        while let Some(open) = rest.find('{') {
+            plain.push_str(&rest[..open]);
+            let Some(close) = rest[open..].find('}') else {
+                rest = &rest[open..];
+                break;
+            };
+
+            for tag in rest[open + 1..open + close].split('\\') {
+                self.apply_ass_tag(tag.trim(), resolution);
+            }
+            rest = &rest[open + close + 1..];
+        }
+        plain.push_str(rest);
+
+        let plain: String = plain
+            .replace("\\N", "\n")
+            .replace("\\n", " ")
+            .replace("\\h", " ");
+        self.lines = plain.split('\n').map(str::to_string).collect();
+    }
+
+    fn apply_ass_tag(&mut self, tag: &str, resolution: Option<(f32, f32)>) {
+        if let Some(alignment) = tag.strip_prefix("an") {
+            if let Ok(alignment) = alignment.parse::<u8>() {
+                self.align(alignment);
+            }
+        } else if let Some(alignment) = tag.strip_prefix('a') {
+            if let Ok(alignment) = alignment.parse::<u8>() {
+                self.align(legacy_alignment(alignment));
+            }
+        } else if let Some(arguments) = tag.strip_prefix("pos(") {
+            let mut coordinates = arguments
+                .trim_end_matches(')')
+                .split(',')
+                .map(|c: &str| c.trim().parse::<f32>().ok());
+
+            if let (Some((width, height)), Some(Some(x)), Some(Some(y))) =
+                (resolution, coordinates.next(), coordinates.next())
+            {
+                self.position = Some((x / width, y / height));
+            }
+        } else if let Some(color) = tag.strip_prefix("1c").or_else(|| tag.strip_prefix('c'))
+            && color.starts_with("&H")
+        {
+            self.color = parse_ass_color(color).or(self.color);
+        }
+    }
+
+    /// Applies an ASS numpad alignment: 1 to 3 along the bottom, 4 to 6 across the middle and
+    /// 7 to 9 along the top, each from left to right.
+    fn align(&mut self, alignment: u8) {
+        if !(1..=9).contains(&alignment) {
+            return;
+        }
+
+        self.horizontal = match (alignment - 1) % 3 {
+            0 => HorizontalAlign::Left,
+            1 => HorizontalAlign::Center,
+            _ => HorizontalAlign::Right,
+        };
+        self.vertical = match (alignment - 1) / 3 {
+            0 => VerticalAlign::Bottom,
+            1 => VerticalAlign::Middle,
+            _ => VerticalAlign::Top,
+        };
+    }
+}
+
+/// Cues read from a subtitle file or collected from a video's subtitle stream.
+#[derive(PartialEq, Clone, Default)]
+pub struct SubtitleTrack {
+    cues: Vec<SubtitleCue>,
+}
+
+impl SubtitleTrack {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn parse(source: &str, format: SubtitleFormat) -> Result<Self, SubtitleError> {
+        let source: String = source.trim_start_matches('\u{feff}').replace("\r\n", "\n");
+        let cues: Vec<SubtitleCue> = match format {
+            SubtitleFormat::Srt => parse_srt(&source)?,
+            SubtitleFormat::WebVtt => parse_vtt(&source)?,
+            SubtitleFormat::Ass => parse_ass(&source)?,
+        };
+
+        Ok(Self { cues })
+    }
+
+    /// Reads a subtitle file, in the format its extension names or else the one its contents
+    /// look like.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, SubtitleError> {
+        let source: String = fs::read_to_string(&path)?;
+        let format: SubtitleFormat = SubtitleFormat::from_extension(path.as_ref())
+            .unwrap_or_else(|| SubtitleFormat::detect(&source));
+
+        Self::parse(&source, format)
+    }
+
+    pub fn cues(&self) -> &[SubtitleCue] {
+        &self.cues
+    }
+
+    pub fn push(&mut self, cue: SubtitleCue) {
+        self.cues.push(cue);
+    }
+
+    /// Cues shown at `time`, in the order they were read.
+    pub fn active_at(&self, time: Duration) -> Vec<&SubtitleCue> {
+        self.cues
+            .iter()
+            .filter(|cue: &&SubtitleCue| cue.is_active_at(time))
+            .collect()
+    }
+}
+
+impl fmt::Debug for SubtitleTrack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SubtitleTrack")
+            .field("cues", &self.cues.len())
+            .finish()
+    }
+}
+
+fn parse_srt(source: &str) -> Result<Vec<SubtitleCue>, SubtitleError> {
+    let mut cues: Vec<SubtitleCue> = Vec::new();
+
+    for block in blocks(source) {
+        // The cue number before the timing line is optional in practice.
+        let Some(timing) = block.iter().position(|line: &&str| line.contains("-->")) else {
+            continue;
+        };
+        let (start, end, _) = parse_timing(block[timing])?;
+
+        cues.push(markup_cue(start, end, &block[timing + 1..].join("\n")));
+    }
+
+    Ok(cues)
+}
+
+fn parse_vtt(source: &str) -> Result<Vec<SubtitleCue>, SubtitleError> {
+    let mut blocks = blocks(source).into_iter();
+    let header: Vec<&str> = blocks.next().unwrap_or_default();
+    if !header
+        .first()
+        .is_some_and(|line: &&str| line.starts_with("WEBVTT"))
+    {
+        return Err(SubtitleError::malformed("missing WEBVTT signature"));
+    }
+
+    let mut cues: Vec<SubtitleCue> = Vec::new();
+
+    for block in blocks {
+        // Comments, style sheets and region definitions are not cues.
+        if block.first().is_some_and(|line: &&str| {
+            ["NOTE", "STYLE", "REGION"]
+                .iter()
+                .any(|keyword: &&str| line.starts_with(keyword))
+        }) {
+            continue;
+        }
+        let Some(timing) = block.iter().position(|line: &&str| line.contains("-->")) else {
+            continue;
+        };
+        let (start, end, settings) = parse_timing(block[timing])?;
+        let mut cue: SubtitleCue = markup_cue(start, end, &block[timing + 1..].join("\n"));
+
+        for setting in settings.split_whitespace() {
+            match setting.split_once(':') {
+                Some(("align", "start" | "left")) => cue.horizontal = HorizontalAlign::Left,
+                Some(("align", "center" | "middle")) => cue.horizontal = HorizontalAlign::Center,
+                Some(("align", "end" | "right")) => cue.horizontal = HorizontalAlign::Right,
+                Some(("line", line)) => {
+                    let line: &str = line.split(',').next().unwrap_or_default();
+                    let top: bool = match line.strip_suffix('%') {
+                        Some(percent) => percent.parse::<f32>().is_ok_and(|p: f32| p < 50.0),
+                        // Line numbers count down from the top, negative ones up from the bottom.
+                        None => line.parse::<i32>().is_ok_and(|n: i32| n >= 0),
+                    };
+                    if top {
+                        cue.vertical = VerticalAlign::Top;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        cues.push(cue);
+    }
+
+    Ok(cues)
+}
+
+/// What a cue takes from the `[V4+ Styles]` line it names.
+#[derive(Debug, Clone, Copy)]
+struct AssStyle {
+    color: Option<(u8, u8, u8)>,
+    alignment: u8,
+}
+
+/// `[Events]` fields assumed when a script has no `Format:` line.
+const ASS_EVENT_FIELDS: [&str; 10] = [
+    "layer", "start", "end", "style", "name", "marginl", "marginr", "marginv", "effect", "text",
+];
+
+fn parse_ass(source: &str) -> Result<Vec<SubtitleCue>, SubtitleError> {
+    let mut section: String = String::new();
+    let mut play_res: (Option<f32>, Option<f32>) = (None, None);
+    let mut style_fields: Vec<String> = Vec::new();
+    let mut styles: HashMap<String, AssStyle> = HashMap::new();
+    let mut event_fields: Vec<String> = ASS_EVENT_FIELDS.map(str::to_string).to_vec();
+    let mut cues: Vec<SubtitleCue> = Vec::new();
+
+    for line in source.lines().map(str::trim) {
+        if line.starts_with('[') && line.ends_with(']') {
+            section = line.to_ascii_lowercase();
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value: &str = value.trim_start();
+
+        match (section.as_str(), key) {
+            ("[script info]", "PlayResX") => play_res.0 = value.trim().parse().ok(),
+            ("[script info]", "PlayResY") => play_res.1 = value.trim().parse().ok(),
+            ("[v4+ styles]" | "[v4 styles]", "Format") => style_fields = format_fields(value),
+            ("[v4+ styles]" | "[v4 styles]", "Style") => {
+                let values: Vec<&str> = value.splitn(style_fields.len().max(1), ',').collect();
+                let field = |name: &str| {
+                    style_fields
+                        .iter()
+                        .position(|field: &String| field == name)
+                        .and_then(|i: usize| values.get(i))
+                        .map(|value: &&str| value.trim())
+                };
+                let alignment: u8 = field("alignment")
+                    .and_then(|alignment: &str| alignment.parse().ok())
+                    .map(|alignment: u8| match section.as_str() {
+                        "[v4 styles]" => legacy_alignment(alignment),
+                        _ => alignment,
+                    })
+                    .unwrap_or(2);
+                let style: AssStyle = AssStyle {
+                    color: field("primarycolour").and_then(parse_ass_color),
+                    alignment,
+                };
+
+                if let Some(name) = field("name") {
+                    styles.insert(name.to_string(), style);
+                }
+            }
+            ("[events]", "Format") => event_fields = format_fields(value),
+            ("[events]", "Dialogue") => {
+                let values: Vec<&str> = value.splitn(event_fields.len(), ',').collect();
+                let field = |name: &str| {
+                    event_fields
+                        .iter()
+                        .position(|field: &String| field == name)
+                        .and_then(|i: usize| values.get(i).copied())
+                };
+                let time = |name: &str| {
+                    field(name)
+                        .and_then(parse_timestamp)
+                        .ok_or_else(|| SubtitleError::malformed(format!("invalid event: {line}")))
+                };
+                let (start, end) = (time("start")?, time("end")?);
+                let style: Option<&AssStyle> =
+                    field("style").and_then(|name: &str| styles.get(name.trim()));
+                let mut cue: SubtitleCue = SubtitleCue::new(start, end, "");
+
+                if let Some(style) = style {
+                    cue.color = style.color;
+                    cue.align(style.alignment);
+                }
+                // Scripts that do not say what resolution they were written for use SSA's.
+                let resolution: (f32, f32) = (
+                    play_res.0.unwrap_or(384.0).max(1.0),
+                    play_res.1.unwrap_or(288.0).max(1.0),
+                );
+                cue.apply_ass_text(field("text").unwrap_or_default(), Some(resolution));
+
+                cues.push(cue);
+            }
+            _ => {}
+        }
+    }
+
+    Ok(cues)
+}
+
+fn format_fields(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|field: &str| field.trim().to_ascii_lowercase())
+        .collect()
+}
+
+/// Converts SSA's alignment, which counts 1 to 3 along the bottom, 5 to 7 along the top and 9
+/// to 11 across the middle, to the numpad layout ASS uses.
+fn legacy_alignment(alignment: u8) -> u8 {
+    match alignment {
+        5..=7 => alignment + 2,
+        9..=11 => alignment - 5,
+        _ => alignment,
+    }
+}
+
+/// Parses ASS `&HBBGGRR&` and `&HAABBGGRR` colors, or SSA's decimal ones. White is the default
+/// text color, so it is left to the engine.
+fn parse_ass_color(value: &str) -> Option<(u8, u8, u8)> {
+    let value: &str = value.trim().trim_end_matches('&');
+    let bgr: u32 = match value
+        .strip_prefix("&H")
+        .or_else(|| value.strip_prefix("&h"))
+    {
+        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+        None => value.parse::<i64>().ok()? as u32,
+    };
+    let color: (u8, u8, u8) = (bgr as u8, (bgr >> 8) as u8, (bgr >> 16) as u8);
+
+    (color != (255, 255, 255)).then_some(color)
+}
+
+/// Builds a cue from SRT or WebVTT text: `<font color>` and WebVTT color classes set its color,
+/// other HTML-like tags are dropped, and ASS override blocks, which SRT files often carry, are
+/// applied.
+fn markup_cue(start: Duration, end: Duration, text: &str) -> SubtitleCue {
+    let mut color: Option<(u8, u8, u8)> = None;
+    let mut plain: String = String::new();
+    let mut rest: &str = text;
+
+    while let Some(open) = rest.find('<') {
+        plain.push_str(&rest[..open]);
+        let Some(close) = rest[open..].find('>') else {
+            rest = &rest[open..];
+            break;
+        };
+        let tag: &str = &rest[open + 1..open + close];
+
+        if let Some(value) = tag.to_ascii_lowercase().split("color=").nth(1) {
+            color = color.or_else(|| parse_html_color(value.trim_matches(['"', '\'', ' '])));
+        } else if let Some(classes) = tag.strip_prefix("c.") {
+            color = color.or_else(|| classes.split('.').find_map(parse_html_color));
+        }
+        rest = &rest[open + close + 1..];
+    }
+    plain.push_str(rest);
+
+    let plain: String = plain
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&");
+    let mut cue: SubtitleCue = SubtitleCue::new(start, end, "");
+    cue.apply_ass_text(&plain.replace('\n', "\\N"), None);
+    cue.color = cue.color.or(color);
+
+    cue
+}
+
+/// `#rrggbb` or one of WebVTT's named colors.
+fn parse_html_color(value: &str) -> Option<(u8, u8, u8)> {
+    if let Some(hex) = value.strip_prefix('#').filter(|hex: &&str| hex.len() == 6) {
+        let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+
+        return Some((channel(0)?, channel(2)?, channel(4)?));
+    }
+
+    match value {
+        "black" => Some((0, 0, 0)),
+        "red" => Some((255, 0, 0)),
+        "lime" | "green" => Some((0, 255, 0)),
+        "blue" => Some((0, 0, 255)),
+        "yellow" => Some((255, 255, 0)),
+        "cyan" => Some((0, 255, 255)),
+        "magenta" => Some((255, 0, 255)),
+        _ => None,
+    }
+}
+
+/// Lines of `source` grouped into runs separated by blank lines.
+fn blocks(source: &str) -> Vec<Vec<&str>> {
+    let mut blocks: Vec<Vec<&str>> = Vec::new();
+    let mut block: Vec<&str> = Vec::new();
+
+    for line in source.lines() {
+        if line.trim().is_empty() {
+            if !block.is_empty() {
+                blocks.push(std::mem::take(&mut block));
+            }
+        } else {
+            block.push(line);
+        }
+    }
+    if !block.is_empty() {
+        blocks.push(block);
+    }
+
+    blocks
+}
+
+/// Splits `start --> end settings` into the two times and whatever follows them.
+fn parse_timing(line: &str) -> Result<(Duration, Duration, &str), SubtitleError> {
+    let invalid = || SubtitleError::malformed(format!("invalid timing: {line}"));
+    let (start, rest) = line.split_once("-->").ok_or_else(invalid)?;
+    let rest: &str = rest.trim_start();
+    let (end, settings) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+
+    Ok((
+        parse_timestamp(start).ok_or_else(invalid)?,
+        parse_timestamp(end).ok_or_else(invalid)?,
+        settings,
+    ))
+}
+
+/// Parses `[hours:]minutes:seconds[.fraction]`, with a `.` or `,` before the fraction.
+fn parse_timestamp(value: &str) -> Option<Duration> {
+    let value: &str = value.trim();
+    let (clock, fraction) = value.split_once(['.', ',']).unwrap_or((value, ""));
+    if clock.split(':').count() > 3 {
+        return None;
+    }
+
+    let mut seconds: u64 = 0;
+    for part in clock.split(':') {
+        seconds = seconds * 60 + part.trim().parse::<u64>().ok()?;
+    }
+
+    let nanos: u32 = match fraction {
+        "" => 0,
+        digits => {
+            let digits: &str = &digits[..digits.len().min(9)];
+
+            digits.parse::<u32>().ok()? * 10u32.pow(9 - digits.len() as u32)
+        }
+    };
+
+    Some(Duration::new(seconds, nanos))
+}
+
+#[derive(Debug)]
+pub enum SubtitleError {
+    Malformed(String),
+    Io(io::Error),
+}
+
+impl SubtitleError {
+    fn malformed(message: impl fmt::Display) -> Self {
+        SubtitleError::Malformed(message.to_string())
+    }
+}
+
+impl fmt::Display for SubtitleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SubtitleError::Malformed(message) => write!(f, "invalid subtitles: {message}"),
+            SubtitleError::Io(error) => write!(f, "could not read subtitles: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for SubtitleError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SubtitleError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for SubtitleError {
+    fn from(error: io::Error) -> Self {
+        SubtitleError::Io(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn seconds(seconds: f64) -> Duration {
+        Duration::from_secs_f64(seconds)
+    }
+
+    #[test]
+    fn parses_srt_with_markup() {
+        let source: &str = "1\r\n00:00:01,500 --> 00:00:03,000\r\n<i>Hello</i>\r\n\
+            <font color=\"#ff8000\">world</font>\r\n\r\n\
+            2\r\n00:01:00,000 --> 00:01:02,250\r\n{\\an8}On top\r\n";
+
+        let track: SubtitleTrack = SubtitleTrack::parse(source, SubtitleFormat::Srt).unwrap();
+
+        assert_eq!(
+            track.cues(),
+            &[
+                SubtitleCue {
+                    color: Some((255, 128, 0)),
+                    ..SubtitleCue::new(seconds(1.5), seconds(3.0), "Hello\nworld")
+                },
+                SubtitleCue {
+                    vertical: VerticalAlign::Top,
+                    ..SubtitleCue::new(seconds(60.0), seconds(62.25), "On top")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_webvtt_settings_and_skips_notes() {
+        let source: &str = "WEBVTT - title\n\nNOTE a comment\n\n\
+            intro\n00:01.000 --> 00:02.000 align:start line:0\n<c.yellow>Hi</c> &amp; bye\n";
+
+        let track: SubtitleTrack = SubtitleTrack::parse(source, SubtitleFormat::WebVtt).unwrap();
+
+        assert_eq!(
+            track.cues(),
+            &[SubtitleCue {
+                color: Some((255, 255, 0)),
+                horizontal: HorizontalAlign::Left,
+                vertical: VerticalAlign::Top,
+                ..SubtitleCue::new(seconds(1.0), seconds(2.0), "Hi & bye")
+            }]
+        );
+        assert!(SubtitleTrack::parse("1\n", SubtitleFormat::WebVtt).is_err());
+    }
+
+    #[test]
+    fn parses_ass_styles_and_overrides() {
+        let source: &str = "[Script Info]\nPlayResX: 640\nPlayResY: 480\n\n\
+            [V4+ Styles]\nFormat: Name, Fontname, PrimaryColour, Alignment\n\
+            Style: Sign,Arial,&H0000FFFF,8\n\n\
+            [Events]\nFormat: Layer, Start, End, Style, Text\n\
+            Dialogue: 0,0:00:01.00,0:00:02.50,Sign,Exit, left\n\
+            Dialogue: 0,0:00:03.00,0:00:04.00,Default,{\\an1\\pos(320,240)\\b1}A\\NB\\hC\n";
+
+        let track: SubtitleTrack = SubtitleTrack::parse(source, SubtitleFormat::Ass).unwrap();
+
+        assert_eq!(
+            track.cues(),
+            &[
+                SubtitleCue {
+                    color: Some((255, 255, 0)),
+                    vertical: VerticalAlign::Top,
+                    ..SubtitleCue::new(seconds(1.0), seconds(2.5), "Exit, left")
+                },
+                SubtitleCue {
+                    horizontal: HorizontalAlign::Left,
+                    position: Some((0.5, 0.5)),
+                    ..SubtitleCue::new(seconds(3.0), seconds(4.0), "A\nB C")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_timings() {
+        let error: SubtitleError =
+            SubtitleTrack::parse("1\n00:00:01 --> soon\nHi\n", SubtitleFormat::Srt).unwrap_err();
+
+        assert_eq!(
+            error.to_string(),
+            "invalid subtitles: invalid timing: 00:00:01 --> soon"
+        );
+    }
+
+    #[test]
+    fn finds_the_cues_shown_at_a_time() {
+        let mut track: SubtitleTrack = SubtitleTrack::new();
+        track.push(SubtitleCue::new(seconds(1.0), seconds(3.0), "a"));
+        track.push(SubtitleCue::new(seconds(2.0), seconds(4.0), "b"));
+
+        let lines = |time: f64| -> Vec<&str> {
+            track
+                .active_at(seconds(time))
+                .iter()
+                .map(|cue: &&SubtitleCue| cue.lines[0].as_str())
+                .collect()
+        };
+
+        assert_eq!(lines(0.5), Vec::<&str>::new());
+        assert_eq!(lines(2.5), vec!["a", "b"]);
+        assert_eq!(lines(3.0), vec!["b"]);
+    }
+
+    #[test]
+    fn detects_formats() {
+        assert_eq!(
+            SubtitleFormat::detect("\u{feff}WEBVTT\n"),
+            SubtitleFormat::WebVtt
+        );
+        assert_eq!(
+            SubtitleFormat::detect("[Script Info]\n"),
+            SubtitleFormat::Ass
+        );
+        assert_eq!(SubtitleFormat::detect("1\n"), SubtitleFormat::Srt);
+        assert_eq!(
+            SubtitleFormat::from_extension(Path::new("movie.en.SSA")),
+            Some(SubtitleFormat::Ass)
+        );
+    }
+}
@@ -1,10 +1,22 @@
 mod glyph;
+mod glyph_box;
 mod glyph_col;
+mod glyph_compositor;
 mod glyph_grid;
 mod glyph_none;
+mod glyph_overlay;
 mod glyph_rendering_engine;
 mod glyph_row;
+mod layout;
+mod text_block;
 
-pub use glyph::Glyph;
-pub use glyph_grid::GlyphGrid;
+pub use glyph::{Glyph, write_lines};
+pub use glyph_box::GlyphBox;
+pub use glyph_col::GlyphCol;
+pub use glyph_compositor::{GlyphCompositor, GlyphLayer};
+pub use glyph_grid::{GlyphGrid, GlyphGridView};
+pub use glyph_none::GlyphNone;
+pub use glyph_overlay::GlyphOverlay;
 pub use glyph_rendering_engine::GlyphRenderingEngine;
+pub use glyph_row::GlyphRow;
+pub use layout::{BorderStyle, HorizontalAlign, Size, Spacing, VerticalAlign};
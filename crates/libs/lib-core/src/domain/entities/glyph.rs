@@ -1,9 +1,164 @@
+use std::{fmt, io};
+
 use crate::domain::entities::glyph_rendering_engine::GlyphRenderingEngine;
 
 pub trait Glyph: std::fmt::Debug {
-    fn as_text(&self, _engine: &dyn GlyphRenderingEngine) -> String;
+    /// Writes the glyph's lines to `out`, separated by `\n` and with none after the last.
+    fn render_into(
+        &self,
+        engine: &dyn GlyphRenderingEngine,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result;
+
+    /// Bytes [`Glyph::render_into`] is expected to write with `engine`, so that buffers can be
+    /// allocated once up front. Zero when it cannot be told without rendering.
+    fn size_hint(&self, _engine: &dyn GlyphRenderingEngine) -> usize {
+        0
+    }
+
+    /// The rendered text, in a string sized by [`Glyph::size_hint`].
+    fn as_text(&self, engine: &dyn GlyphRenderingEngine) -> String {
+        let mut text: String = String::with_capacity(self.size_hint(engine));
+
+        self.render_into(engine, &mut text)
+            .expect("writing to a String does not fail");
+        text
+    }
+
+    /// Streams the rendered text to `out`, such as a socket or a file; wrap unbuffered ones in
+    /// a [`io::BufWriter`], as the text is written a few bytes at a time.
+    fn write_to(
+        &self,
+        engine: &dyn GlyphRenderingEngine,
+        out: &mut dyn io::Write,
+    ) -> io::Result<()> {
+        let mut adapter: IoAdapter<'_> = IoAdapter { out, error: None };
+
+        self.render_into(engine, &mut adapter)
+            .map_err(|_| match adapter.error.take() {
+                Some(error) => error,
+                None => io::Error::other("glyph failed to render"),
+            })
+    }
 
     fn add_child(&mut self, _child: Box<dyn Glyph>);
 
-    fn get_child_mut(&mut self, _index: usize) -> Option<&mut Box<dyn Glyph>>;
+    fn get_child_mut(&mut self, _index: usize) -> Option<&mut dyn Glyph>;
+
+    /// Whether the cell at column `x` of line `y` of [`Glyph::as_text`] lets lower layers show
+    /// through when glyphs are layered.
+    fn is_transparent_at(&self, _x: usize, _y: usize) -> bool {
+        false
+    }
+}
+
+/// Writes formatted text to an [`io::Write`], keeping the error that `fmt::Error` cannot carry.
+struct IoAdapter<'a> {
+    out: &'a mut dyn io::Write,
+    error: Option<io::Error>,
+}
+
+impl fmt::Write for IoAdapter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.out
+            .write_all(s.as_bytes())
+            .map_err(|error: io::Error| {
+                self.error = Some(error);
+                fmt::Error
+            })
+    }
+}
+
+/// Writes `lines` to `out` separated by `\n`, for glyphs that lay out whole lines at a time.
+pub fn write_lines<S: AsRef<str>>(
+    out: &mut dyn fmt::Write,
+    lines: impl IntoIterator<Item = S>,
+) -> fmt::Result {
+    for (i, line) in lines.into_iter().enumerate() {
+        if i > 0 {
+            out.write_char('\n')?;
+        }
+        out.write_str(line.as_ref())?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::{fmt, io};
+
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::entities::{
+        glyph::{Glyph, write_lines},
+        glyph_rendering_engine::GlyphRenderingEngine,
+    };
+
+    #[derive(Debug)]
+    struct LinesGlyph;
+
+    impl Glyph for LinesGlyph {
+        fn render_into(
+            &self,
+            _engine: &dyn GlyphRenderingEngine,
+            out: &mut dyn fmt::Write,
+        ) -> fmt::Result {
+            write_lines(out, ["ab", "cd"])
+        }
+
+        fn size_hint(&self, _engine: &dyn GlyphRenderingEngine) -> usize {
+            5
+        }
+
+        fn add_child(&mut self, _child: Box<dyn Glyph>) {}
+
+        fn get_child_mut(&mut self, _index: usize) -> Option<&mut dyn Glyph> {
+            None
+        }
+    }
+
+    struct DummyEngine;
+
+    impl GlyphRenderingEngine for DummyEngine {
+        fn get_char_based_on_luma_value(&self, _luma: u8) -> char {
+            panic!("Should not be called");
+        }
+    }
+
+    struct FullSink;
+
+    impl io::Write for FullSink {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::ErrorKind::StorageFull.into())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn as_text_allocates_the_size_hint_once() {
+        let text: String = LinesGlyph.as_text(&DummyEngine);
+
+        assert_eq!(text, "ab\ncd");
+        assert_eq!(text.capacity(), 5);
+    }
+
+    #[test]
+    fn writes_to_io_sinks_and_keeps_their_errors() {
+        let mut bytes: Vec<u8> = Vec::new();
+
+        LinesGlyph.write_to(&DummyEngine, &mut bytes).unwrap();
+
+        assert_eq!(bytes, b"ab\ncd");
+        assert_eq!(
+            LinesGlyph
+                .write_to(&DummyEngine, &mut FullSink)
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::StorageFull
+        );
+    }
 }
@@ -0,0 +1,306 @@
+use std::{
+    cell::{RefCell, RefMut},
+    fmt,
+};
+
+use crate::domain::entities::{
+    glyph::Glyph,
+    glyph_rendering_engine::GlyphRenderingEngine,
+    layout::{BorderStyle, HorizontalAlign, Spacing, VerticalAlign},
+    text_block::{LineWriter, TextBlock, write_fitted, write_spaces},
+};
+
+/// Wraps one child in padding, an optional titled border and a margin, in that order outwards.
+#[derive(Debug)]
+pub struct GlyphBox {
+    child: Box<dyn Glyph>,
+    padding: Spacing,
+    margin: Spacing,
+    border: Option<BorderStyle>,
+    title: Option<String>,
+    width: Option<usize>,
+    height: Option<usize>,
+    horizontal: HorizontalAlign,
+    vertical: VerticalAlign,
+    /// The child's text, kept between renders so that it is only allocated once.
+    scratch: RefCell<String>,
+}
+
+impl GlyphBox {
+    pub fn new(child: Box<dyn Glyph>) -> Self {
+        Self {
+            child,
+            padding: Spacing::default(),
+            margin: Spacing::default(),
+            border: None,
+            title: None,
+            width: None,
+            height: None,
+            horizontal: HorizontalAlign::Left,
+            vertical: VerticalAlign::Top,
+            scratch: RefCell::new(String::new()),
+        }
+    }
+
+    pub fn with_padding(mut self, padding: Spacing) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    pub fn with_margin(mut self, margin: Spacing) -> Self {
+        self.margin = margin;
+        self
+    }
+
+    pub fn with_border(mut self, border: BorderStyle) -> Self {
+        self.border = Some(border);
+        self
+    }
+
+    /// Text set into the top border, cut short when the box is too narrow. Boxes without a
+    /// border have nowhere to show it.
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = Some(title.into());
+        self
+    }
+
+    /// Width including padding and border but not margin; the child is padded or cut to fit.
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Height including padding and border but not margin; the child is padded or cut to fit.
+    pub fn with_height(mut self, height: usize) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    /// Where the child sits when it is narrower than the box.
+    pub fn with_horizontal_align(mut self, align: HorizontalAlign) -> Self {
+        self.horizontal = align;
+        self
+    }
+
+    /// Where the child sits when it is shorter than the box.
+    pub fn with_vertical_align(mut self, align: VerticalAlign) -> Self {
+        self.vertical = align;
+        self
+    }
+}
+
+impl Glyph for GlyphBox {
+    fn render_into(
+        &self,
+        engine: &dyn GlyphRenderingEngine,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        let mut text: RefMut<String> = self.scratch.borrow_mut();
+        text.clear();
+        text.reserve(self.child.size_hint(engine));
+        self.child.render_into(engine, &mut *text)?;
+
+        let block: TextBlock = TextBlock::new(&text);
+        let reset: &str = engine.get_style_reset();
+        let border: usize = if self.border.is_some() { 2 } else { 0 };
+        let padding: Spacing = self.padding;
+        let margin: Spacing = self.margin;
+        let width: usize = self.width.map_or(block.width(), |width| {
+            width.saturating_sub(border + padding.left + padding.right)
+        });
+        let height: usize = self.height.map_or(block.height(), |height| {
+            height.saturating_sub(border + padding.top + padding.bottom)
+        });
+        let inner_width: usize = padding.left + width + padding.right;
+        let outer_width: usize = margin.left + inner_width + border + margin.right;
+        let chars: Option<[char; 6]> = self.border.as_ref().map(BorderStyle::chars);
+        let mut lines: LineWriter = LineWriter::new(out);
+
+        for _ in 0..margin.top {
+            lines.line(|out| write_spaces(out, outer_width))?;
+        }
+
+        if let Some([top_left, top_right, _, _, horizontal, _]) = chars {
+            let title: String = match &self.title {
+                Some(title) => format!("{horizontal} {title} "),
+                None => String::new(),
+            };
+
+            lines.line(|out| {
+                margined(out, margin, |out| {
+                    out.write_char(top_left)?;
+                    title
+                        .chars()
+                        .chain(std::iter::repeat(horizontal))
+                        .take(inner_width)
+                        .try_for_each(|c: char| out.write_char(c))?;
+                    out.write_char(top_right)
+                })
+            })?;
+        }
+
+        // Content and padding.
+        for y in 0..padding.top + height + padding.bottom {
+            lines.line(|out| {
+                margined(out, margin, |out| {
+                    if let Some([.., vertical]) = chars {
+                        out.write_char(vertical)?;
+                    }
+                    write_spaces(out, padding.left)?;
+                    match y.checked_sub(padding.top).filter(|y: &usize| *y < height) {
+                        Some(y) => write_fitted(
+                            out,
+                            block.line(y, height, self.vertical),
+                            width,
+                            self.horizontal,
+                            reset,
+                        )?,
+                        None => write_spaces(out, width)?,
+                    }
+                    write_spaces(out, padding.right)?;
+                    if let Some([.., vertical]) = chars {
+                        out.write_char(vertical)?;
+                    }
+
+                    Ok(())
+                })
+            })?;
+        }
+
+        if let Some([_, _, bottom_left, bottom_right, horizontal, _]) = chars {
+            lines.line(|out| {
+                margined(out, margin, |out| {
+                    out.write_char(bottom_left)?;
+                    (0..inner_width).try_for_each(|_| out.write_char(horizontal))?;
+                    out.write_char(bottom_right)
+                })
+            })?;
+        }
+
+        for _ in 0..margin.bottom {
+            lines.line(|out| write_spaces(out, outer_width))?;
+        }
+
+        Ok(())
+    }
+
+    /// The child's hint, with the padding, border and margin around it counted once per side.
+    fn size_hint(&self, engine: &dyn GlyphRenderingEngine) -> usize {
+        let border: usize = if self.border.is_some() { 2 } else { 0 };
+        let spacing =
+            |spacing: Spacing| spacing.top + spacing.right + spacing.bottom + spacing.left;
+
+        self.child.size_hint(engine) + spacing(self.padding) + spacing(self.margin) + 2 * border
+    }
+
+    fn add_child(&mut self, _child: Box<dyn Glyph>) {}
+
+    fn get_child_mut(&mut self, index: usize) -> Option<&mut dyn Glyph> {
+        (index == 0).then_some(self.child.as_mut())
+    }
+}
+
+/// Writes what `write` puts on a line between the left and right `margin`.
+fn margined(
+    out: &mut dyn fmt::Write,
+    margin: Spacing,
+    write: impl FnOnce(&mut dyn fmt::Write) -> fmt::Result,
+) -> fmt::Result {
+    write_spaces(out, margin.left)?;
+    write(out)?;
+    write_spaces(out, margin.right)
+}
+
+#[cfg(test)]
+mod test {
+    use std::fmt;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::entities::{
+        glyph::Glyph,
+        glyph_box::GlyphBox,
+        glyph_rendering_engine::GlyphRenderingEngine,
+        glyph_row::GlyphRow,
+        layout::{BorderStyle, HorizontalAlign, Spacing, VerticalAlign},
+    };
+
+    #[test]
+    fn frames_with_a_title() {
+        let framed: GlyphBox = GlyphBox::new(Box::new(TextGlyph("ab\ncd")))
+            .with_border(BorderStyle::Rounded)
+            .with_title("Hi")
+            .with_padding(Spacing::symmetric(0, 2));
+
+        assert_eq!(
+            framed.as_text(&DummyEngine),
+            "╭─ Hi ─╮\n│  ab  │\n│  cd  │\n╰──────╯"
+        );
+    }
+
+    #[test]
+    fn cuts_titles_to_the_border() {
+        let framed: GlyphBox = GlyphBox::new(Box::new(TextGlyph("x")))
+            .with_border(BorderStyle::Double)
+            .with_title("Long title");
+
+        assert_eq!(framed.as_text(&DummyEngine), "╔═╗\n║x║\n╚═╝");
+    }
+
+    #[test]
+    fn aligns_within_a_fixed_size_and_adds_margin() {
+        let framed: GlyphBox = GlyphBox::new(Box::new(TextGlyph("x")))
+            .with_border(BorderStyle::Heavy)
+            .with_width(5)
+            .with_height(4)
+            .with_horizontal_align(HorizontalAlign::Right)
+            .with_vertical_align(VerticalAlign::Bottom)
+            .with_margin(Spacing {
+                top: 1,
+                left: 1,
+                ..Spacing::default()
+            });
+
+        assert_eq!(
+            framed.as_text(&DummyEngine),
+            "      \n ┏━━━┓\n ┃   ┃\n ┃  x┃\n ┗━━━┛"
+        );
+    }
+
+    #[test]
+    fn composes_side_by_side() {
+        let row: GlyphRow = GlyphRow::new(vec![
+            Box::new(GlyphBox::new(Box::new(TextGlyph("a"))).with_border(BorderStyle::Single)),
+            Box::new(GlyphBox::new(Box::new(TextGlyph("b\nb"))).with_border(BorderStyle::Single)),
+        ]);
+
+        assert_eq!(row.as_text(&DummyEngine), "┌─┐┌─┐\n│a││b│\n└─┘│b│\n   └─┘");
+    }
+
+    #[derive(Debug)]
+    struct TextGlyph(&'static str);
+
+    impl Glyph for TextGlyph {
+        fn render_into(
+            &self,
+            _engine: &dyn GlyphRenderingEngine,
+            out: &mut dyn fmt::Write,
+        ) -> fmt::Result {
+            out.write_str(self.0)
+        }
+
+        fn add_child(&mut self, _child: Box<dyn Glyph>) {}
+
+        fn get_child_mut(&mut self, _index: usize) -> Option<&mut dyn Glyph> {
+            None
+        }
+    }
+
+    struct DummyEngine;
+
+    impl GlyphRenderingEngine for DummyEngine {
+        fn get_char_based_on_luma_value(&self, _luma: u8) -> char {
+            panic!("Should not be called");
+        }
+    }
+}
@@ -1,47 +1,157 @@
-use crate::domain::entities::{glyph::Glyph, glyph_rendering_engine::GlyphRenderingEngine};
+use std::{
+    cell::{RefCell, RefMut},
+    fmt,
+};
 
+use crate::domain::entities::{
+    glyph::Glyph,
+    glyph_rendering_engine::GlyphRenderingEngine,
+    layout::{HorizontalAlign, Size, VerticalAlign, distribute},
+    text_block::{LineWriter, TextBlock, write_fitted, write_spaces},
+};
+
+/// Stacks children top to bottom, left-aligned by default, padding every line to the widest.
 #[derive(Debug)]
-pub(crate) struct GlyphCol {
-    children: Vec<Box<dyn Glyph>>,
+pub struct GlyphCol {
+    children: Vec<(Box<dyn Glyph>, Size)>,
+    height: Option<usize>,
+    gap: usize,
+    horizontal: HorizontalAlign,
+    vertical: VerticalAlign,
+    /// The children's text, kept between renders so that it is only allocated once.
+    scratch: RefCell<String>,
 }
 
 impl GlyphCol {
     pub fn empty() -> Self {
-        GlyphCol {
-            children: Vec::new(),
-        }
+        Self::new(Vec::new())
     }
 
     pub fn new(children: Vec<Box<dyn Glyph>>) -> Self {
-        Self { children }
+        Self {
+            children: children
+                .into_iter()
+                .map(|child: Box<dyn Glyph>| (child, Size::Auto))
+                .collect(),
+            height: None,
+            gap: 0,
+            horizontal: HorizontalAlign::Left,
+            vertical: VerticalAlign::Top,
+            scratch: RefCell::new(String::new()),
+        }
+    }
+
+    /// Appends `child`, taking `size` lines of the column's height.
+    pub fn with_child(mut self, child: Box<dyn Glyph>, size: Size) -> Self {
+        self.children.push((child, size));
+        self
+    }
+
+    /// Pads or cuts the column to `height` lines, which `Size::Flex` children share.
+    pub fn with_height(mut self, height: usize) -> Self {
+        self.height = Some(height);
+        self
+    }
+
+    /// Blank lines between neighbouring children.
+    pub fn with_gap(mut self, gap: usize) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Where a child sits when it is narrower than the widest one.
+    pub fn with_horizontal_align(mut self, align: HorizontalAlign) -> Self {
+        self.horizontal = align;
+        self
+    }
+
+    /// Where a child sits when it is shorter than the height it was given.
+    pub fn with_vertical_align(mut self, align: VerticalAlign) -> Self {
+        self.vertical = align;
+        self
     }
 }
 
 impl Glyph for GlyphCol {
-    fn as_text(&self, engine: &dyn GlyphRenderingEngine) -> String {
+    fn render_into(
+        &self,
+        engine: &dyn GlyphRenderingEngine,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        let reset: &str = engine.get_style_reset();
+        let mut text: RefMut<String> = self.scratch.borrow_mut();
+        let blocks: Vec<TextBlock> = TextBlock::render_all(
+            self.children.iter().map(|(child, _)| child.as_ref()),
+            engine,
+            &mut text,
+            self.children
+                .iter()
+                .map(|(child, _)| child.size_hint(engine))
+                .sum(),
+        )?;
+        let sizes: Vec<Size> = self.children.iter().map(|(_, size)| *size).collect();
+        let natural: Vec<usize> = blocks.iter().map(TextBlock::height).collect();
+        let heights: Vec<usize> = distribute(&sizes, &natural, self.gap, self.height);
+        let width: usize = blocks.iter().map(TextBlock::width).max().unwrap_or(0);
+        let mut lines: LineWriter = match self.height {
+            Some(height) => LineWriter::with_limit(out, height),
+            None => LineWriter::new(out),
+        };
+
+        for (i, (block, height)) in blocks.iter().zip(&heights).enumerate() {
+            if i > 0 {
+                for _ in 0..self.gap {
+                    lines.line(|out| write_spaces(out, width))?;
+                }
+            }
+            for y in 0..*height {
+                lines.line(|out| {
+                    let line: &str = block.line(y, *height, self.vertical);
+
+                    write_fitted(out, line, width, self.horizontal, reset)
+                })?;
+            }
+        }
+
+        if let Some(height) = self.height {
+            while lines.count() < height {
+                lines.line(|out| write_spaces(out, width))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The children's hints, the line breaks between them and a break per gap line.
+    fn size_hint(&self, engine: &dyn GlyphRenderingEngine) -> usize {
         self.children
             .iter()
-            .map(|c| c.as_text(engine))
-            .fold("".to_string(), |acc: String, e: String| {
-                format!("{acc}{e}\n")
-            })
-            .trim_end()
-            .to_string()
+            .map(|(child, _)| child.size_hint(engine))
+            .sum::<usize>()
+            + (1 + self.gap) * self.children.len().saturating_sub(1)
     }
 
     fn add_child(&mut self, child: Box<dyn Glyph>) {
-        self.children.push(child);
+        self.children.push((child, Size::Auto));
     }
 
-    fn get_child_mut(&mut self, index: usize) -> Option<&mut Box<dyn Glyph>> {
-        self.children.get_mut(index)
+    fn get_child_mut(&mut self, index: usize) -> Option<&mut dyn Glyph> {
+        let (child, _) = self.children.get_mut(index)?;
+
+        Some(child.as_mut())
     }
 }
 
 #[cfg(test)]
 mod test {
+    use std::fmt;
+
     use crate::domain::entities::{
-        glyph::Glyph, glyph_col::GlyphCol, glyph_rendering_engine::GlyphRenderingEngine,
+        glyph::Glyph,
+        glyph_col::GlyphCol,
+        glyph_rendering_engine::GlyphRenderingEngine,
+        glyph_row::GlyphRow,
+        layout::{HorizontalAlign, Size, VerticalAlign},
     };
     use pretty_assertions::assert_eq;
 
@@ -81,6 +191,52 @@ mod test {
         assert_eq!(col.as_text(&engine), "A\nB\nC");
     }
 
+    #[test]
+    fn allocates_the_size_hints_of_the_children_once() {
+        let engine: DummyEngine = DummyEngine {};
+        let col: GlyphCol = GlyphCol::new(vec![
+            Box::new(DummyGlyphA),
+            Box::new(DummyGlyphB),
+            Box::new(DummyGlyphC),
+        ]);
+
+        let text: String = col.as_text(&engine);
+
+        assert_eq!(text, "A\nB\nC");
+        assert_eq!(text.capacity(), 5);
+    }
+
+    #[test]
+    fn keeps_trailing_whitespace() {
+        let engine: DummyEngine = DummyEngine {};
+        let col: GlyphCol = GlyphCol::new(vec![Box::new(DummyGlyphA), Box::new(DummyGlyphB)])
+            .with_height(4)
+            .with_gap(1);
+
+        assert_eq!(col.as_text(&engine), "A\n \nB\n ");
+    }
+
+    #[test]
+    fn sizes_and_aligns_children() {
+        let engine: DummyEngine = DummyEngine {};
+        let col: GlyphCol = GlyphCol::empty()
+            .with_child(Box::new(DummyGlyphA), Size::Fixed(2))
+            .with_child(Box::new(DummyGlyphC), Size::Flex(1))
+            .with_child(
+                Box::new(GlyphRow::new(vec![
+                    Box::new(DummyGlyphB),
+                    Box::new(DummyGlyphB),
+                    Box::new(DummyGlyphB),
+                ])),
+                Size::Auto,
+            )
+            .with_height(6)
+            .with_horizontal_align(HorizontalAlign::Center)
+            .with_vertical_align(VerticalAlign::Bottom);
+
+        assert_eq!(col.as_text(&engine), "   \n A \n   \n   \n C \nBBB");
+    }
+
     struct DummyEngine;
 
     impl GlyphRenderingEngine for DummyEngine {
@@ -93,15 +249,23 @@ mod test {
     struct DummyGlyphA;
 
     impl Glyph for DummyGlyphA {
-        fn as_text(&self, _engine: &dyn GlyphRenderingEngine) -> String {
-            "A".to_string()
+        fn render_into(
+            &self,
+            _engine: &dyn GlyphRenderingEngine,
+            out: &mut dyn fmt::Write,
+        ) -> fmt::Result {
+            out.write_str("A")
+        }
+
+        fn size_hint(&self, _engine: &dyn GlyphRenderingEngine) -> usize {
+            1
         }
 
         fn add_child(&mut self, _child: Box<dyn Glyph>) {
             todo!()
         }
 
-        fn get_child_mut(&mut self, _index: usize) -> Option<&mut Box<dyn Glyph>> {
+        fn get_child_mut(&mut self, _index: usize) -> Option<&mut dyn Glyph> {
             todo!()
         }
     }
@@ -110,15 +274,23 @@ mod test {
     struct DummyGlyphB;
 
     impl Glyph for DummyGlyphB {
-        fn as_text(&self, _engine: &dyn GlyphRenderingEngine) -> String {
-            "B".to_string()
+        fn render_into(
+            &self,
+            _engine: &dyn GlyphRenderingEngine,
+            out: &mut dyn fmt::Write,
+        ) -> fmt::Result {
+            out.write_str("B")
+        }
+
+        fn size_hint(&self, _engine: &dyn GlyphRenderingEngine) -> usize {
+            1
         }
 
         fn add_child(&mut self, _child: Box<dyn Glyph>) {
             todo!()
         }
 
-        fn get_child_mut(&mut self, _index: usize) -> Option<&mut Box<dyn Glyph>> {
+        fn get_child_mut(&mut self, _index: usize) -> Option<&mut dyn Glyph> {
             todo!()
         }
     }
@@ -127,15 +299,23 @@ mod test {
     struct DummyGlyphC;
 
     impl Glyph for DummyGlyphC {
-        fn as_text(&self, _engine: &dyn GlyphRenderingEngine) -> String {
-            "C".to_string()
+        fn render_into(
+            &self,
+            _engine: &dyn GlyphRenderingEngine,
+            out: &mut dyn fmt::Write,
+        ) -> fmt::Result {
+            out.write_str("C")
+        }
+
+        fn size_hint(&self, _engine: &dyn GlyphRenderingEngine) -> usize {
+            1
         }
 
         fn add_child(&mut self, _child: Box<dyn Glyph>) {
             todo!()
         }
 
-        fn get_child_mut(&mut self, _index: usize) -> Option<&mut Box<dyn Glyph>> {
+        fn get_child_mut(&mut self, _index: usize) -> Option<&mut dyn Glyph> {
             todo!()
         }
     }
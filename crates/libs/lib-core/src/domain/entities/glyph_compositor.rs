@@ -0,0 +1,312 @@
+use std::{
+    borrow::Cow,
+    cell::{RefCell, RefMut},
+    fmt,
+};
+
+use crate::domain::entities::{
+    glyph::Glyph,
+    glyph_rendering_engine::GlyphRenderingEngine,
+    text_block::{TextBlock, merge_cell, split_cells, write_cells},
+};
+
+/// One glyph placed on a [`GlyphCompositor`] canvas.
+#[derive(Debug)]
+pub struct GlyphLayer {
+    glyph: Box<dyn Glyph>,
+    column: isize,
+    row: isize,
+    z: i32,
+    opacity: f32,
+}
+
+impl GlyphLayer {
+    pub fn new(glyph: Box<dyn Glyph>) -> Self {
+        Self {
+            glyph,
+            column: 0,
+            row: 0,
+            z: 0,
+            opacity: 1.0,
+        }
+    }
+
+    /// Moves the layer's top-left cell to `column`, `row` of the canvas; parts left of or above
+    /// the canvas are clipped.
+    pub fn with_offset(mut self, column: isize, row: isize) -> Self {
+        self.column = column;
+        self.row = row;
+        self
+    }
+
+    /// Layers with a higher `z` are drawn over lower ones; equal ones in the order they were
+    /// added.
+    pub fn with_z(mut self, z: i32) -> Self {
+        self.z = z;
+        self
+    }
+
+    /// From 0.0 to 1.0. Colored cells are blended with the color beneath, or with black over
+    /// uncolored cells; glyphs themselves cannot be see-through, so the layer's glyph is kept.
+    pub fn with_opacity(mut self, opacity: f32) -> Self {
+        self.opacity = opacity.clamp(0.0, 1.0);
+        self
+    }
+
+    fn is_transparent_at(&self, x: usize, y: usize) -> bool {
+        let (Ok(x), Ok(y)) = (
+            usize::try_from(x as isize - self.column),
+            usize::try_from(y as isize - self.row),
+        ) else {
+            return true;
+        };
+
+        self.opacity == 0.0 || self.glyph.is_transparent_at(x, y)
+    }
+}
+
+/// Stacks layers on a fixed-size canvas of cells. Each layer's transparent cells let the layers
+/// below show through, and cells no layer draws are blank and transparent.
+#[derive(Debug)]
+pub struct GlyphCompositor {
+    width: usize,
+    height: usize,
+    layers: Vec<GlyphLayer>,
+    /// The layers' text, kept between renders so that it is only allocated once.
+    scratch: RefCell<String>,
+}
+
+impl GlyphCompositor {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            layers: Vec::new(),
+            scratch: RefCell::new(String::new()),
+        }
+    }
+
+    pub fn with_layer(mut self, layer: GlyphLayer) -> Self {
+        self.add_layer(layer);
+        self
+    }
+
+    pub fn add_layer(&mut self, layer: GlyphLayer) {
+        self.layers.push(layer);
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Layers from the bottom up.
+    fn stacked(&self) -> Vec<&GlyphLayer> {
+        let mut layers: Vec<&GlyphLayer> = self.layers.iter().collect();
+        layers.sort_by_key(|layer: &&GlyphLayer| layer.z);
+        layers
+    }
+}
+
+impl Glyph for GlyphCompositor {
+    fn render_into(
+        &self,
+        engine: &dyn GlyphRenderingEngine,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        let reset: &str = engine.get_style_reset();
+        let layers: Vec<&GlyphLayer> = self
+            .stacked()
+            .into_iter()
+            .filter(|layer: &&GlyphLayer| layer.opacity > 0.0)
+            .collect();
+        let mut text: RefMut<String> = self.scratch.borrow_mut();
+        let blocks: Vec<TextBlock> = TextBlock::render_all(
+            layers
+                .iter()
+                .map(|layer: &&GlyphLayer| layer.glyph.as_ref()),
+            engine,
+            &mut text,
+            layers
+                .iter()
+                .map(|layer: &&GlyphLayer| layer.glyph.size_hint(engine))
+                .sum(),
+        )?;
+        // Cells borrow the layers' text; only blended ones are owned.
+        let mut canvas: Vec<Option<Cow<str>>> = vec![None; self.width * self.height];
+
+        for (layer, block) in layers.iter().zip(&blocks) {
+            for (y, line) in block.lines().iter().enumerate() {
+                let Some(row) = usize::try_from(layer.row + y as isize)
+                    .ok()
+                    .filter(|row: &usize| *row < self.height)
+                else {
+                    continue;
+                };
+
+                for (x, cell) in split_cells(line).into_iter().enumerate() {
+                    let Some(column) = usize::try_from(layer.column + x as isize)
+                        .ok()
+                        .filter(|column: &usize| *column < self.width)
+                    else {
+                        continue;
+                    };
+                    let above: Option<&str> =
+                        (!layer.glyph.is_transparent_at(x, y)).then_some(cell);
+                    let target: &mut Option<Cow<str>> = &mut canvas[row * self.width + column];
+
+                    *target = merge_cell(engine, target.take(), above, layer.opacity);
+                }
+            }
+        }
+
+        for y in 0..self.height {
+            if y > 0 {
+                out.write_char('\n')?;
+            }
+
+            let row: &[Option<Cow<str>>] = &canvas[y * self.width..(y + 1) * self.width];
+
+            write_cells(
+                out,
+                row.iter()
+                    .map(|cell: &Option<Cow<str>>| cell.as_deref().unwrap_or(" ")),
+                reset,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// A cell per byte, as most come from plain glyphs; colored layers take more.
+    fn size_hint(&self, _engine: &dyn GlyphRenderingEngine) -> usize {
+        (self.width + 1) * self.height
+    }
+
+    fn add_child(&mut self, child: Box<dyn Glyph>) {
+        self.add_layer(GlyphLayer::new(child));
+    }
+
+    fn get_child_mut(&mut self, index: usize) -> Option<&mut dyn Glyph> {
+        let layer: &mut GlyphLayer = self.layers.get_mut(index)?;
+
+        Some(layer.glyph.as_mut())
+    }
+
+    fn is_transparent_at(&self, x: usize, y: usize) -> bool {
+        x >= self.width
+            || y >= self.height
+            || self
+                .layers
+                .iter()
+                .all(|layer: &GlyphLayer| layer.is_transparent_at(x, y))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fmt;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::entities::{
+        glyph::Glyph,
+        glyph_compositor::{GlyphCompositor, GlyphLayer},
+        glyph_none::GlyphNone,
+        glyph_rendering_engine::GlyphRenderingEngine,
+    };
+
+    #[test]
+    fn stacks_layers_by_z_and_clips_to_the_canvas() {
+        let compositor: GlyphCompositor = GlyphCompositor::new(4, 3)
+            .with_layer(GlyphLayer::new(Box::new(StencilGlyph("bb\nbb"))).with_z(1))
+            .with_layer(GlyphLayer::new(Box::new(StencilGlyph("aaaa\naaaa\naaaa"))))
+            .with_layer(
+                GlyphLayer::new(Box::new(StencilGlyph("c c\nccc")))
+                    .with_offset(2, 2)
+                    .with_z(2),
+            );
+
+        assert_eq!(compositor.as_text(&DummyEngine), "bbaa\nbbaa\naaca");
+    }
+
+    #[test]
+    fn transparent_cells_show_lower_layers() {
+        let compositor: GlyphCompositor = GlyphCompositor::new(3, 1)
+            .with_layer(GlyphLayer::new(Box::new(StencilGlyph("aaa"))))
+            .with_layer(GlyphLayer::new(Box::new(StencilGlyph("b b"))))
+            .with_layer(GlyphLayer::new(Box::new(GlyphNone)));
+
+        assert_eq!(compositor.as_text(&DummyEngine), "bab");
+        assert!(!compositor.is_transparent_at(1, 0));
+        assert!(compositor.is_transparent_at(3, 0));
+    }
+
+    #[test]
+    fn negative_offsets_clip_the_top_left() {
+        let compositor: GlyphCompositor = GlyphCompositor::new(2, 2).with_layer(
+            GlyphLayer::new(Box::new(StencilGlyph("abc\ndef\nghi"))).with_offset(-1, -1),
+        );
+
+        assert_eq!(compositor.as_text(&DummyEngine), "ef\nhi");
+    }
+
+    #[test]
+    fn opacity_blends_colors() {
+        let compositor: GlyphCompositor = GlyphCompositor::new(2, 1)
+            .with_layer(GlyphLayer::new(Box::new(StencilGlyph(
+                "\x1b[38;2;0;0;200ma\x1b[38;2;0;0;200ma",
+            ))))
+            .with_layer(
+                GlyphLayer::new(Box::new(StencilGlyph("\x1b[38;2;200;0;0mb"))).with_opacity(0.25),
+            );
+
+        assert_eq!(
+            compositor.as_text(&DummyEngine),
+            "[50,0,150]b\x1b[38;2;0;0;200ma;"
+        );
+    }
+
+    /// Shows `text`, with spaces transparent.
+    #[derive(Debug)]
+    struct StencilGlyph(&'static str);
+
+    impl Glyph for StencilGlyph {
+        fn render_into(
+            &self,
+            _engine: &dyn GlyphRenderingEngine,
+            out: &mut dyn fmt::Write,
+        ) -> fmt::Result {
+            out.write_str(self.0)
+        }
+
+        fn add_child(&mut self, _child: Box<dyn Glyph>) {}
+
+        fn get_child_mut(&mut self, _index: usize) -> Option<&mut dyn Glyph> {
+            None
+        }
+
+        fn is_transparent_at(&self, x: usize, y: usize) -> bool {
+            self.0.lines().nth(y).and_then(|l| l.chars().nth(x)) == Some(' ')
+        }
+    }
+
+    struct DummyEngine;
+
+    impl GlyphRenderingEngine for DummyEngine {
+        fn get_char_based_on_luma_value(&self, _luma: u8) -> char {
+            panic!("Should not be called");
+        }
+
+        fn get_text_based_on_colored_char(&self, c: char, r: u8, g: u8, b: u8) -> String {
+            format!("[{r},{g},{b}]{c}")
+        }
+
+        fn get_style_reset(&self) -> &str {
+            ";"
+        }
+    }
+}
@@ -1,70 +1,418 @@
-use crate::domain::entities::{
-    glyph::Glyph, glyph_col::GlyphCol, glyph_rendering_engine::GlyphRenderingEngine,
-    glyph_row::GlyphRow,
-};
+use std::fmt;
 
+use crate::domain::entities::{glyph::Glyph, glyph_rendering_engine::GlyphRenderingEngine};
+
+/// A `width` by `height` grid of cells addressed by column `x` and row `y`, each holding a glyph
+/// of one character or nothing. Empty cells are drawn as spaces and are transparent.
+///
+/// Glyphs are measured as they are placed, by rendering them with an engine that draws every
+/// pixel as one character, and those that do not take exactly one cell are turned away. That
+/// costs a render per placed glyph, cut short at the second cell. Glyphs changed in place through
+/// `get_mut` or `get_child_mut` are not measured again, so they should stay one character.
+///
+/// `add_child` fills the first empty cell in reading order and ignores glyphs once every cell
+/// is taken or when they are wider; `get_child_mut` indexes cells in the same order.
 #[derive(Debug)]
 pub struct GlyphGrid {
-    main_col: GlyphCol,
-    pub elements_count: usize,
+    width: usize,
+    height: usize,
+    /// Row by row, always `width * height` long.
+    cells: Vec<Option<Box<dyn Glyph>>>,
+    /// No cell before this one is empty.
+    elements_count: usize,
+}
+
+/// A borrowed rectangle of a [`GlyphGrid`], addressed from its own top-left cell.
+#[derive(Debug, Clone, Copy)]
+pub struct GlyphGridView<'a> {
+    grid: &'a GlyphGrid,
+    x: usize,
+    y: usize,
     width: usize,
     height: usize,
 }
 
 impl GlyphGrid {
+    /// A grid of empty cells.
     pub fn new(width: usize, height: usize) -> Self {
-        let mut rows: Vec<Box<dyn Glyph>> = Vec::with_capacity(height);
-
-        for _ in 0..width {
-            rows.push(Box::new(GlyphRow::new(Vec::with_capacity(width))));
-        }
         Self {
-            main_col: GlyphCol::new(rows),
-            elements_count: 0,
             width,
             height,
+            cells: std::iter::repeat_with(|| None)
+                .take(width * height)
+                .collect(),
+            elements_count: 0,
         }
     }
 
-    fn is_finished(&self) -> bool {
-        self.elements_count >= self.width * self.height
+    /// A grid whose cells are filled by `fill(x, y)`, or left empty when it is not one character.
+    pub fn from_fn(
+        width: usize,
+        height: usize,
+        fill: impl FnMut(usize, usize) -> Box<dyn Glyph>,
+    ) -> Self {
+        let mut grid: GlyphGrid = Self::new(0, 0);
+
+        grid.resize_with(width, height, fill);
+        grid
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
     }
 
-    fn add_glyph(&mut self, new_gyph: Box<dyn Glyph>) {
-        let col_index: usize = self.elements_count / self.width;
+    /// The glyph at column `x` of row `y`; `None` for empty cells and cells outside the grid.
+    pub fn get(&self, x: usize, y: usize) -> Option<&dyn Glyph> {
+        self.cells[self.index(x, y)?].as_deref()
+    }
+
+    /// The glyph at column `x` of row `y`, to change in place; replace it with [`GlyphGrid::set`].
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut dyn Glyph> {
+        let index: usize = self.index(x, y)?;
+
+        let cell: &mut Box<dyn Glyph> = self.cells[index].as_mut()?;
 
-        if let Some(i) = self.main_col.get_child_mut(col_index) {
-            i.add_child(new_gyph)
+        Some(cell.as_mut())
+    }
+
+    /// Puts `glyph` at column `x` of row `y`, returning the glyph it replaced. A cell outside
+    /// the grid cannot be set and a glyph that is not one character cannot be placed; either
+    /// way `glyph` is handed back. `glyph` is rendered once to measure it.
+    pub fn set(
+        &mut self,
+        x: usize,
+        y: usize,
+        glyph: Box<dyn Glyph>,
+    ) -> Result<Option<Box<dyn Glyph>>, Box<dyn Glyph>> {
+        match self.index(x, y) {
+            Some(index) if is_one_character(glyph.as_ref()) => Ok(self.cells[index].replace(glyph)),
+            _ => Err(glyph),
         }
+    }
 
-        self.elements_count += 1;
+    /// Empties the cell at column `x` of row `y`, returning its glyph.
+    pub fn take(&mut self, x: usize, y: usize) -> Option<Box<dyn Glyph>> {
+        let index: usize = self.index(x, y)?;
+        self.elements_count = self.elements_count.min(index);
+
+        self.cells[index].take()
+    }
+
+    /// Row `y`, left to right; empty when the row is outside the grid.
+    pub fn row(&self, y: usize) -> impl Iterator<Item = Option<&dyn Glyph>> {
+        self.view_all().row(y)
+    }
+
+    /// Column `x`, top to bottom; empty when the column is outside the grid.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = Option<&dyn Glyph>> {
+        self.view_all().column(x)
+    }
+
+    /// Every row, top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = impl Iterator<Item = Option<&dyn Glyph>>> {
+        self.view_all().rows()
+    }
+
+    /// Every column, left to right.
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = Option<&dyn Glyph>>> {
+        self.view_all().columns()
+    }
+
+    /// Every cell with its column and row, in reading order.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize, Option<&dyn Glyph>)> {
+        self.view_all().cells()
+    }
+
+    /// The rectangle of `width` by `height` cells whose top-left cell is at column `x` of row
+    /// `y`, cut to the part inside the grid.
+    pub fn view(&self, x: usize, y: usize, width: usize, height: usize) -> GlyphGridView<'_> {
+        self.view_all().view(x, y, width, height)
+    }
+
+    /// Changes the grid to `width` by `height` cells, keeping those still inside it where they
+    /// were and leaving new ones empty.
+    pub fn resize(&mut self, width: usize, height: usize) {
+        self.resize_cells(width, height, |_, _| None);
+    }
+
+    /// Like [`GlyphGrid::resize`], but fills new cells with `fill(x, y)`, or leaves them empty
+    /// when it is not one character.
+    pub fn resize_with(
+        &mut self,
+        width: usize,
+        height: usize,
+        mut fill: impl FnMut(usize, usize) -> Box<dyn Glyph>,
+    ) {
+        self.resize_cells(width, height, |x, y| {
+            let glyph: Box<dyn Glyph> = fill(x, y);
+
+            is_one_character(glyph.as_ref()).then_some(glyph)
+        });
+    }
+
+    /// Moves the cells of `source` onto this grid with its top-left cell at column `x` of row
+    /// `y`. Empty cells of `source` leave the cells beneath as they were, and cells that land
+    /// outside this grid are dropped.
+    pub fn blit(&mut self, source: GlyphGrid, x: isize, y: isize) {
+        let source_width: usize = source.width;
+
+        for (i, cell) in source.cells.into_iter().enumerate() {
+            let (Some(glyph), Ok(column), Ok(row)) = (
+                cell,
+                usize::try_from(x + (i % source_width) as isize),
+                usize::try_from(y + (i / source_width) as isize),
+            ) else {
+                continue;
+            };
+
+            if let Some(index) = self.index(column, row) {
+                self.cells[index] = Some(glyph);
+            }
+        }
+    }
+
+    fn view_all(&self) -> GlyphGridView<'_> {
+        GlyphGridView {
+            grid: self,
+            x: 0,
+            y: 0,
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    fn index(&self, x: usize, y: usize) -> Option<usize> {
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+
+    fn resize_cells(
+        &mut self,
+        width: usize,
+        height: usize,
+        mut fill: impl FnMut(usize, usize) -> Option<Box<dyn Glyph>>,
+    ) {
+        let mut old: Vec<Option<Box<dyn Glyph>>> = std::mem::take(&mut self.cells);
+        let old_width: usize = self.width;
+        let old_height: usize = self.height;
+
+        self.cells = (0..width * height)
+            .map(|i: usize| {
+                let (x, y) = (i % width, i / width);
+
+                if x < old_width && y < old_height {
+                    old[y * old_width + x].take()
+                } else {
+                    fill(x, y)
+                }
+            })
+            .collect();
+        self.width = width;
+        self.height = height;
+        self.elements_count = 0;
+    }
+
+    /// Whether every cell holds a glyph, so that `add_child` has nowhere left to put one.
+    pub fn is_finished(&self) -> bool {
+        self.cells[self.elements_count..]
+            .iter()
+            .all(Option::is_some)
+    }
+}
+
+impl<'a> GlyphGridView<'a> {
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The glyph at column `x` of row `y` of the view; `None` for empty cells and cells
+    /// outside the view.
+    pub fn get(&self, x: usize, y: usize) -> Option<&'a dyn Glyph> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        self.grid.get(self.x + x, self.y + y)
+    }
+
+    /// Row `y` of the view, left to right.
+    pub fn row(self, y: usize) -> impl Iterator<Item = Option<&'a dyn Glyph>> {
+        let width: usize = if y < self.height { self.width } else { 0 };
+
+        (0..width).map(move |x: usize| self.get(x, y))
+    }
+
+    /// Column `x` of the view, top to bottom.
+    pub fn column(self, x: usize) -> impl Iterator<Item = Option<&'a dyn Glyph>> {
+        let height: usize = if x < self.width { self.height } else { 0 };
+
+        (0..height).map(move |y: usize| self.get(x, y))
+    }
+
+    pub fn rows(self) -> impl Iterator<Item = impl Iterator<Item = Option<&'a dyn Glyph>>> {
+        (0..self.height).map(move |y: usize| self.row(y))
+    }
+
+    pub fn columns(self) -> impl Iterator<Item = impl Iterator<Item = Option<&'a dyn Glyph>>> {
+        (0..self.width).map(move |x: usize| self.column(x))
+    }
+
+    /// Every cell with its column and row in the view, in reading order.
+    pub fn cells(self) -> impl Iterator<Item = (usize, usize, Option<&'a dyn Glyph>)> {
+        (0..self.width * self.height).map(move |i: usize| {
+            let (x, y) = (i % self.width, i / self.width);
+
+            (x, y, self.get(x, y))
+        })
+    }
+
+    /// A rectangle of this view, cut to the part inside it.
+    pub fn view(&self, x: usize, y: usize, width: usize, height: usize) -> GlyphGridView<'a> {
+        let x: usize = x.min(self.width);
+        let y: usize = y.min(self.height);
+
+        GlyphGridView {
+            grid: self.grid,
+            x: self.x + x,
+            y: self.y + y,
+            width: width.min(self.width - x),
+            height: height.min(self.height - y),
+        }
+    }
+
+    /// Writes the view's cells like [`Glyph::render_into`], a line per row.
+    pub fn render_into(
+        &self,
+        engine: &dyn GlyphRenderingEngine,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                out.write_char('\n')?;
+            }
+
+            for cell in row {
+                match cell {
+                    Some(glyph) => glyph.render_into(engine, out)?,
+                    None => out.write_char(' ')?,
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn as_text(&self, engine: &dyn GlyphRenderingEngine) -> String {
+        let mut text: String = String::with_capacity((self.width + 1) * self.height);
+
+        self.render_into(engine, &mut text)
+            .expect("writing to a String does not fail");
+        text
     }
 }
 
 impl Glyph for GlyphGrid {
-    fn as_text(&self, engine: &dyn GlyphRenderingEngine) -> String {
-        self.main_col.as_text(engine)
+    fn render_into(
+        &self,
+        engine: &dyn GlyphRenderingEngine,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        self.view_all().render_into(engine, out)
+    }
+
+    /// A byte per cell, as cells are usually plain characters.
+    fn size_hint(&self, _engine: &dyn GlyphRenderingEngine) -> usize {
+        (self.width + 1) * self.height
     }
 
     fn add_child(&mut self, child: Box<dyn Glyph>) {
-        if self.is_finished() {
+        if !is_one_character(child.as_ref()) {
             return;
         }
 
-        self.add_glyph(child);
+        let Some(offset) = self.cells[self.elements_count..]
+            .iter()
+            .position(Option::is_none)
+        else {
+            self.elements_count = self.cells.len();
+            return;
+        };
+
+        self.elements_count += offset;
+        self.cells[self.elements_count] = Some(child);
+        self.elements_count += 1;
+    }
+
+    fn get_child_mut(&mut self, index: usize) -> Option<&mut dyn Glyph> {
+        let cell: &mut Box<dyn Glyph> = self.cells.get_mut(index)?.as_mut()?;
+
+        Some(cell.as_mut())
+    }
+
+    fn is_transparent_at(&self, x: usize, y: usize) -> bool {
+        self.get(x, y)
+            .is_none_or(|glyph: &dyn Glyph| glyph.is_transparent_at(0, 0))
     }
+}
+
+/// Whether `glyph` takes exactly one cell when every pixel is drawn as one character.
+fn is_one_character(glyph: &dyn Glyph) -> bool {
+    let mut counter: CellCounter = CellCounter::default();
+
+    glyph.render_into(&ProbeEngine, &mut counter).is_ok() && counter.cells == 1
+}
+
+/// Draws every pixel as the same character, for measuring glyphs.
+struct ProbeEngine;
 
-    fn get_child_mut(&mut self, _index: usize) -> Option<&mut Box<dyn Glyph>> {
-        None
+impl GlyphRenderingEngine for ProbeEngine {
+    fn get_char_based_on_luma_value(&self, _y: u8) -> char {
+        '#'
+    }
+}
+
+/// Counts the cells written to it, line breaks included and ANSI escape sequences not, and
+/// fails as soon as there is more than one, so wide glyphs are not rendered in full.
+#[derive(Default)]
+struct CellCounter {
+    cells: usize,
+    in_escape: bool,
+}
+
+impl fmt::Write for CellCounter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for c in s.chars() {
+            match c {
+                _ if self.in_escape => self.in_escape = !(c.is_ascii_alphabetic() || c == '~'),
+                '\x1b' => self.in_escape = true,
+                _ => self.cells += 1,
+            }
+        }
+
+        match self.cells {
+            0 | 1 => Ok(()),
+            _ => Err(fmt::Error),
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::fmt;
+
     use pretty_assertions::assert_eq;
 
     use crate::domain::entities::{
-        glyph::Glyph, glyph_grid::GlyphGrid, glyph_rendering_engine::GlyphRenderingEngine,
+        glyph::Glyph,
+        glyph_grid::{GlyphGrid, GlyphGridView},
+        glyph_rendering_engine::GlyphRenderingEngine,
     };
 
     #[test]
@@ -88,19 +436,212 @@ mod tests {
         assert_eq!(expected_output, result);
     }
 
+    #[test]
+    fn fills_non_square_grids_in_reading_order() {
+        for (width, height) in [(3, 2), (2, 3), (1, 4), (4, 1)] {
+            let mut grid: GlyphGrid = GlyphGrid::new(width, height);
+
+            for c in ('a'..).take(width * height + 1) {
+                grid.add_child(Box::new(CharGlyph(c)));
+            }
+
+            let expected: Vec<String> = (0..height)
+                .map(|y| {
+                    (0..width)
+                        .map(|x| char::from(b'a' + (y * width + x) as u8))
+                        .collect()
+                })
+                .collect();
+            assert!(grid.is_finished());
+            assert_eq!(grid.as_text(&DummyEngine), expected.join("\n"));
+        }
+    }
+
+    #[test]
+    fn gets_sets_and_takes_cells() {
+        let mut grid: GlyphGrid = GlyphGrid::new(3, 2);
+
+        assert!(grid.set(2, 1, Box::new(CharGlyph('z'))).unwrap().is_none());
+        assert!(grid.set(3, 0, Box::new(CharGlyph('!'))).is_err());
+        assert!(grid.get(2, 1).is_some());
+        assert!(grid.get(0, 2).is_none());
+        assert_eq!(grid.as_text(&DummyEngine), "   \n  z");
+        assert!(grid.is_transparent_at(0, 0));
+        assert!(!grid.is_transparent_at(2, 1));
+
+        grid.add_child(Box::new(CharGlyph('a')));
+        assert!(grid.take(2, 1).is_some());
+        assert!(grid.take(0, 0).is_some());
+        grid.add_child(Box::new(CharGlyph('b')));
+
+        assert_eq!(grid.as_text(&DummyEngine), "b  \n   ");
+        assert!(grid.get_child_mut(0).is_some());
+        assert!(grid.get_child_mut(1).is_none());
+    }
+
+    #[test]
+    fn turns_away_glyphs_that_are_not_one_character() {
+        let mut grid: GlyphGrid = GlyphGrid::from_fn(3, 1, |x, _| match x {
+            0 => Box::new(TextGlyph("ab")),
+            _ => Box::new(TextGlyph("\x1b[31mx\x1b[0m")),
+        });
+
+        assert!(grid.get(0, 0).is_none());
+        assert!(grid.get(1, 0).is_some());
+        assert!(grid.set(0, 0, Box::new(TextGlyph("a\nb"))).is_err());
+        assert!(grid.set(0, 0, Box::new(TextGlyph(""))).is_err());
+
+        grid.add_child(Box::new(TextGlyph("wide")));
+        assert!(grid.get(0, 0).is_none());
+
+        grid.resize_with(4, 1, |_, _| Box::new(TextGlyph("..")));
+        assert_eq!(
+            grid.as_text(&DummyEngine),
+            " \x1b[31mx\x1b[0m\x1b[31mx\x1b[0m "
+        );
+    }
+
+    #[test]
+    fn iterates_rows_columns_and_cells() {
+        let grid: GlyphGrid = letters(3, 2);
+        let text = |cells: &mut dyn Iterator<Item = Option<&dyn Glyph>>| -> String {
+            cells
+                .map(|cell| cell.map_or(String::new(), |glyph| glyph.as_text(&DummyEngine)))
+                .collect()
+        };
+
+        assert_eq!(text(&mut grid.row(1)), "def");
+        assert_eq!(text(&mut grid.column(2)), "cf");
+        assert_eq!(text(&mut grid.row(2)), "");
+        assert_eq!(grid.rows().count(), 2);
+        assert_eq!(
+            grid.columns()
+                .map(|mut column| text(&mut column))
+                .collect::<Vec<String>>(),
+            vec!["ad", "be", "cf"]
+        );
+        assert_eq!(
+            grid.cells()
+                .map(|(x, y, _)| (x, y))
+                .collect::<Vec<(usize, usize)>>(),
+            vec![(0, 0), (1, 0), (2, 0), (0, 1), (1, 1), (2, 1)]
+        );
+    }
+
+    #[test]
+    fn resizes_keeping_cells_in_place() {
+        let mut grid: GlyphGrid = letters(3, 2);
+
+        grid.resize_with(2, 3, |_, _| Box::new(CharGlyph('.')));
+        assert_eq!(grid.as_text(&DummyEngine), "ab\nde\n..");
+
+        grid.resize(4, 1);
+        assert_eq!((grid.width(), grid.height()), (4, 1));
+        assert_eq!(grid.as_text(&DummyEngine), "ab  ");
+
+        grid.add_child(Box::new(CharGlyph('c')));
+        assert_eq!(grid.as_text(&DummyEngine), "abc ");
+
+        grid.resize(0, 0);
+        assert!(grid.is_finished());
+        assert_eq!(grid.as_text(&DummyEngine), "");
+    }
+
+    #[test]
+    fn blits_and_clips_other_grids() {
+        let mut grid: GlyphGrid = GlyphGrid::from_fn(4, 3, |_, _| Box::new(CharGlyph('.')));
+        let mut stamp: GlyphGrid = letters(2, 2);
+        stamp.take(1, 0);
+
+        grid.blit(stamp, -1, 2);
+        grid.blit(letters(3, 1), 2, 0);
+
+        assert_eq!(grid.as_text(&DummyEngine), "..ab\n....\n....");
+
+        let mut stamp: GlyphGrid = letters(2, 2);
+        stamp.take(0, 0);
+        grid.blit(stamp, 1, 1);
+
+        assert_eq!(grid.as_text(&DummyEngine), "..ab\n..b.\n.cd.");
+    }
+
+    #[test]
+    fn views_borrow_clipped_rectangles() {
+        let grid: GlyphGrid = letters(4, 3);
+
+        let view: GlyphGridView<'_> = grid.view(1, 1, 5, 5);
+
+        assert_eq!((view.width(), view.height()), (3, 2));
+        assert_eq!(view.as_text(&DummyEngine), "fgh\njkl");
+        assert!(view.get(0, 0).is_some());
+        assert!(view.get(3, 0).is_none());
+        assert_eq!(view.view(1, 0, 1, 2).as_text(&DummyEngine), "g\nk");
+        assert_eq!(view.view(9, 9, 1, 1).as_text(&DummyEngine), "");
+        assert_eq!(view.cells().count(), 6);
+    }
+
+    /// A grid of consecutive letters from `a`, in reading order.
+    fn letters(width: usize, height: usize) -> GlyphGrid {
+        GlyphGrid::from_fn(width, height, |x, y| {
+            Box::new(CharGlyph(char::from(b'a' + (y * width + x) as u8)))
+        })
+    }
+
+    #[derive(Debug)]
+    struct CharGlyph(char);
+
+    impl Glyph for CharGlyph {
+        fn render_into(
+            &self,
+            _engine: &dyn GlyphRenderingEngine,
+            out: &mut dyn fmt::Write,
+        ) -> fmt::Result {
+            out.write_char(self.0)
+        }
+
+        fn add_child(&mut self, _child: Box<dyn Glyph>) {}
+
+        fn get_child_mut(&mut self, _index: usize) -> Option<&mut dyn Glyph> {
+            None
+        }
+    }
+
+    #[derive(Debug)]
+    struct TextGlyph(&'static str);
+
+    impl Glyph for TextGlyph {
+        fn render_into(
+            &self,
+            _engine: &dyn GlyphRenderingEngine,
+            out: &mut dyn fmt::Write,
+        ) -> fmt::Result {
+            out.write_str(self.0)
+        }
+
+        fn add_child(&mut self, _child: Box<dyn Glyph>) {}
+
+        fn get_child_mut(&mut self, _index: usize) -> Option<&mut dyn Glyph> {
+            None
+        }
+    }
+
     #[derive(Debug)]
     struct DummyGlyph;
 
     impl Glyph for DummyGlyph {
-        fn as_text(&self, _engine: &dyn GlyphRenderingEngine) -> String {
-            "X".to_string()
+        fn render_into(
+            &self,
+            _engine: &dyn GlyphRenderingEngine,
+            out: &mut dyn fmt::Write,
+        ) -> fmt::Result {
+            out.write_str("X")
         }
 
         fn add_child(&mut self, _child: Box<dyn Glyph>) {
             todo!()
         }
 
-        fn get_child_mut(&mut self, _index: usize) -> Option<&mut Box<dyn Glyph>> {
+        fn get_child_mut(&mut self, _index: usize) -> Option<&mut dyn Glyph> {
             todo!()
         }
     }
@@ -1,7 +1,11 @@
+use std::fmt;
+
 use crate::domain::entities::{glyph::Glyph, glyph_rendering_engine::GlyphRenderingEngine};
 
-#[derive(Debug)]
-pub(crate) struct GlyphNone;
+/// Draws nothing and lets every cell through; a spacer in rows and columns when given a fixed
+/// size.
+#[derive(Debug, Default)]
+pub struct GlyphNone;
 
 impl GlyphNone {
     pub fn new() -> Self {
@@ -10,15 +14,23 @@ impl GlyphNone {
 }
 
 impl Glyph for GlyphNone {
-    fn as_text(&self, _: &dyn GlyphRenderingEngine) -> String {
-        String::new()
+    fn render_into(
+        &self,
+        _engine: &dyn GlyphRenderingEngine,
+        _out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        Ok(())
     }
 
     fn add_child(&mut self, _child: Box<dyn Glyph>) {}
 
-    fn get_child_mut(&mut self, _index: usize) -> Option<&mut Box<dyn Glyph>> {
+    fn get_child_mut(&mut self, _index: usize) -> Option<&mut dyn Glyph> {
         None
     }
+
+    fn is_transparent_at(&self, _x: usize, _y: usize) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,158 @@
+use std::{borrow::Cow, fmt};
+
+use crate::domain::entities::{
+    glyph::Glyph,
+    glyph_rendering_engine::GlyphRenderingEngine,
+    text_block::{merge_cell, split_cells, write_cells},
+};
+
+/// Draws `top` over `base`, letting `base` show through the transparent cells of `top`.
+#[derive(Debug)]
+pub struct GlyphOverlay {
+    base: Box<dyn Glyph>,
+    top: Box<dyn Glyph>,
+}
+
+impl GlyphOverlay {
+    pub fn new(base: Box<dyn Glyph>, top: Box<dyn Glyph>) -> Self {
+        Self { base, top }
+    }
+}
+
+impl Glyph for GlyphOverlay {
+    fn render_into(
+        &self,
+        engine: &dyn GlyphRenderingEngine,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        let base: String = self.base.as_text(engine);
+        let top: String = self.top.as_text(engine);
+        let base_lines: Vec<Vec<&str>> = base.lines().map(split_cells).collect();
+        let top_lines: Vec<Vec<&str>> = top.lines().map(split_cells).collect();
+        let reset: &str = engine.get_style_reset();
+
+        for y in 0..base_lines.len().max(top_lines.len()) {
+            let base_cells: &[&str] = base_lines.get(y).map_or(&[], |l| l.as_slice());
+            let top_cells: &[&str] = top_lines.get(y).map_or(&[], |l| l.as_slice());
+
+            if y > 0 {
+                out.write_char('\n')?;
+            }
+
+            let cells = (0..base_cells.len().max(top_cells.len())).map(|x: usize| {
+                let above: Option<&str> = top_cells
+                    .get(x)
+                    .copied()
+                    .filter(|_| !self.top.is_transparent_at(x, y));
+
+                merge_cell(
+                    engine,
+                    base_cells.get(x).copied().map(Cow::Borrowed),
+                    above,
+                    1.0,
+                )
+                .unwrap_or(Cow::Borrowed(" "))
+            });
+
+            write_cells(out, cells, reset)?;
+        }
+
+        Ok(())
+    }
+
+    fn size_hint(&self, engine: &dyn GlyphRenderingEngine) -> usize {
+        self.base.size_hint(engine).max(self.top.size_hint(engine))
+    }
+
+    fn add_child(&mut self, _child: Box<dyn Glyph>) {}
+
+    fn get_child_mut(&mut self, index: usize) -> Option<&mut dyn Glyph> {
+        match index {
+            0 => Some(self.base.as_mut()),
+            1 => Some(self.top.as_mut()),
+            _ => None,
+        }
+    }
+
+    fn is_transparent_at(&self, x: usize, y: usize) -> bool {
+        self.top.is_transparent_at(x, y) && self.base.is_transparent_at(x, y)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fmt;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::entities::{
+        glyph::Glyph, glyph_overlay::GlyphOverlay, glyph_rendering_engine::GlyphRenderingEngine,
+    };
+
+    #[test]
+    fn should_show_base_through_transparent_cells() {
+        let overlay: GlyphOverlay = GlyphOverlay::new(
+            Box::new(StencilGlyph::new("aaa\naaa", "")),
+            Box::new(StencilGlyph::new("b b\nbb", "x x\nxx")),
+        );
+
+        assert_eq!(overlay.as_text(&DummyEngine), "bab\nbba");
+    }
+
+    #[test]
+    fn should_reset_styling_before_plain_cells() {
+        let overlay: GlyphOverlay = GlyphOverlay::new(
+            Box::new(StencilGlyph::new("aa", "")),
+            Box::new(StencilGlyph::new("\x1b[31mb\x1b[0m ", "x ")),
+        );
+
+        assert_eq!(overlay.as_text(&DummyEngine), "\x1b[31mb;a");
+    }
+
+    #[derive(Debug)]
+    struct StencilGlyph {
+        text: String,
+        opaque: String,
+    }
+
+    impl StencilGlyph {
+        fn new(text: &str, opaque: &str) -> Self {
+            Self {
+                text: text.to_string(),
+                opaque: opaque.to_string(),
+            }
+        }
+    }
+
+    impl Glyph for StencilGlyph {
+        fn render_into(
+            &self,
+            _engine: &dyn GlyphRenderingEngine,
+            out: &mut dyn fmt::Write,
+        ) -> fmt::Result {
+            out.write_str(&self.text)
+        }
+
+        fn add_child(&mut self, _child: Box<dyn Glyph>) {}
+
+        fn get_child_mut(&mut self, _index: usize) -> Option<&mut dyn Glyph> {
+            None
+        }
+
+        fn is_transparent_at(&self, x: usize, y: usize) -> bool {
+            self.opaque.lines().nth(y).and_then(|l| l.chars().nth(x)) != Some('x')
+        }
+    }
+
+    struct DummyEngine;
+
+    impl GlyphRenderingEngine for DummyEngine {
+        fn get_char_based_on_luma_value(&self, _luma: u8) -> char {
+            panic!("Should not be called");
+        }
+
+        fn get_style_reset(&self) -> &str {
+            ";"
+        }
+    }
+}
@@ -1,3 +1,42 @@
+use std::fmt;
+
 pub trait GlyphRenderingEngine {
     fn get_char_based_on_luma_value(&self, y: u8) -> char;
+
+    /// Text for a colored cell whose perceived brightness is `y`.
+    ///
+    /// Engines without color support render the luma glyph.
+    fn get_text_based_on_rgb_value(&self, _r: u8, _g: u8, _b: u8, y: u8) -> String {
+        self.get_char_based_on_luma_value(y).to_string()
+    }
+
+    /// Writes [`GlyphRenderingEngine::get_text_based_on_rgb_value`] to `out`; engines that
+    /// style cells override it to skip the string built for every cell.
+    fn write_text_based_on_rgb_value(
+        &self,
+        out: &mut dyn fmt::Write,
+        r: u8,
+        g: u8,
+        b: u8,
+        y: u8,
+    ) -> fmt::Result {
+        out.write_str(&self.get_text_based_on_rgb_value(r, g, b, y))
+    }
+
+    /// Bytes a colored cell usually takes, for sizing output buffers.
+    fn colored_cell_size_hint(&self) -> usize {
+        1
+    }
+
+    /// Text for `c` drawn in a fixed color, such as a letter of a banner.
+    ///
+    /// Engines without color support render `c` as is.
+    fn get_text_based_on_colored_char(&self, c: char, _r: u8, _g: u8, _b: u8) -> String {
+        c.to_string()
+    }
+
+    /// Text that ends any styling opened by colored cells, emitted at the end of each line.
+    fn get_style_reset(&self) -> &str {
+        ""
+    }
 }
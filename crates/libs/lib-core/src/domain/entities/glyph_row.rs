@@ -1,42 +1,155 @@
-use crate::domain::entities::{glyph::Glyph, glyph_rendering_engine::GlyphRenderingEngine};
+use std::{
+    cell::{RefCell, RefMut},
+    fmt,
+};
 
+use crate::domain::entities::{
+    glyph::Glyph,
+    glyph_rendering_engine::GlyphRenderingEngine,
+    layout::{HorizontalAlign, Size, VerticalAlign, distribute},
+    text_block::{LineWriter, TextBlock, write_fitted, write_spaces},
+};
+
+/// Lays children out side by side, top-aligned by default, each as wide as its widest line.
 #[derive(Debug)]
-pub(crate) struct GlyphRow {
-    children: Vec<Box<dyn Glyph>>,
+pub struct GlyphRow {
+    children: Vec<(Box<dyn Glyph>, Size)>,
+    width: Option<usize>,
+    gap: usize,
+    horizontal: HorizontalAlign,
+    vertical: VerticalAlign,
+    /// The children's text, kept between renders so that it is only allocated once.
+    scratch: RefCell<String>,
 }
 
 impl GlyphRow {
     pub fn empty() -> Self {
-        GlyphRow {
-            children: Vec::new(),
-        }
+        Self::new(Vec::new())
     }
 
     pub fn new(children: Vec<Box<dyn Glyph>>) -> Self {
-        Self { children }
+        Self {
+            children: children
+                .into_iter()
+                .map(|child: Box<dyn Glyph>| (child, Size::Auto))
+                .collect(),
+            width: None,
+            gap: 0,
+            horizontal: HorizontalAlign::Left,
+            vertical: VerticalAlign::Top,
+            scratch: RefCell::new(String::new()),
+        }
+    }
+
+    /// Appends `child`, taking `size` cells of the row's width.
+    pub fn with_child(mut self, child: Box<dyn Glyph>, size: Size) -> Self {
+        self.children.push((child, size));
+        self
+    }
+
+    /// Pads or cuts every line to `width` cells, which `Size::Flex` children share.
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.width = Some(width);
+        self
+    }
+
+    /// Blank cells between neighbouring children.
+    pub fn with_gap(mut self, gap: usize) -> Self {
+        self.gap = gap;
+        self
+    }
+
+    /// Where a child sits when it is narrower than the width it was given.
+    pub fn with_horizontal_align(mut self, align: HorizontalAlign) -> Self {
+        self.horizontal = align;
+        self
+    }
+
+    /// Where a child sits when it is shorter than the tallest one.
+    pub fn with_vertical_align(mut self, align: VerticalAlign) -> Self {
+        self.vertical = align;
+        self
     }
 }
 
 impl Glyph for GlyphRow {
     fn add_child(&mut self, child: Box<dyn Glyph>) {
-        self.children.push(child);
+        self.children.push((child, Size::Auto));
+    }
+
+    fn get_child_mut(&mut self, index: usize) -> Option<&mut dyn Glyph> {
+        let (child, _) = self.children.get_mut(index)?;
+
+        Some(child.as_mut())
     }
 
-    fn get_child_mut(&mut self, _index: usize) -> Option<&mut Box<dyn Glyph>> {
-        self.children.get_mut(_index)
+    fn render_into(
+        &self,
+        engine: &dyn GlyphRenderingEngine,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        let reset: &str = engine.get_style_reset();
+        let mut text: RefMut<String> = self.scratch.borrow_mut();
+        let blocks: Vec<TextBlock> = TextBlock::render_all(
+            self.children.iter().map(|(child, _)| child.as_ref()),
+            engine,
+            &mut text,
+            self.children
+                .iter()
+                .map(|(child, _)| child.size_hint(engine))
+                .sum(),
+        )?;
+        let sizes: Vec<Size> = self.children.iter().map(|(_, size)| *size).collect();
+        let natural: Vec<usize> = blocks.iter().map(TextBlock::width).collect();
+        let widths: Vec<usize> = distribute(&sizes, &natural, self.gap, self.width);
+        let height: usize = blocks.iter().map(TextBlock::height).max().unwrap_or(0);
+        let write_line = |out: &mut dyn fmt::Write, y: usize| -> fmt::Result {
+            for (i, (block, width)) in blocks.iter().zip(&widths).enumerate() {
+                if i > 0 {
+                    write_spaces(out, self.gap)?;
+                }
+                write_fitted(
+                    out,
+                    block.line(y, height, self.vertical),
+                    *width,
+                    self.horizontal,
+                    reset,
+                )?;
+            }
+
+            Ok(())
+        };
+        let mut lines: LineWriter = LineWriter::new(out);
+        let mut line: String = String::new();
+
+        for y in 0..height {
+            match self.width {
+                Some(width) => {
+                    line.clear();
+                    write_line(&mut line, y)?;
+                    lines.line(|out| {
+                        write_fitted(out, &line, width, HorizontalAlign::Left, reset)
+                    })?;
+                }
+                None => lines.line(|out| write_line(out, y))?,
+            }
+        }
+
+        Ok(())
     }
 
-    fn as_text(&self, engine: &dyn GlyphRenderingEngine) -> String {
+    /// The children's hints and the gaps between them.
+    fn size_hint(&self, engine: &dyn GlyphRenderingEngine) -> usize {
         self.children
             .iter()
-            .map(|c| c.as_text(engine))
-            .fold("".to_string(), |acc: String, e: String| format!("{acc}{e}"))
+            .map(|(child, _)| child.size_hint(engine))
+            .sum::<usize>()
+            + self.gap * self.children.len().saturating_sub(1)
     }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::domain::entities::glyph_rendering_engine::GlyphRenderingEngine;
     use pretty_assertions::assert_eq;
 
     use super::*;
@@ -79,6 +192,74 @@ mod test {
         assert_eq!(row.as_text(&engine), "ABC");
     }
 
+    #[test]
+    fn allocates_the_size_hints_of_the_children_and_gaps_once() {
+        let engine: DummyEngine = DummyEngine {};
+        let row: GlyphRow = GlyphRow::new(vec![
+            Box::new(DummyGlyph {
+                text: "ab\ncd".to_string(),
+            }),
+            Box::new(DummyGlyph {
+                text: "ef\ngh".to_string(),
+            }),
+        ])
+        .with_gap(1);
+
+        let text: String = row.as_text(&engine);
+
+        assert_eq!(text, "ab ef\ncd gh");
+        assert_eq!(text.capacity(), 11);
+    }
+
+    #[test]
+    fn reuses_its_buffer_across_renders() {
+        let engine: DummyEngine = DummyEngine {};
+        let row: GlyphRow = GlyphRow::new(vec![Box::new(DummyGlyph {
+            text: "ab\ncd".to_string(),
+        })]);
+
+        assert_eq!(row.as_text(&engine), "ab\ncd");
+        let buffer: *const u8 = row.scratch.borrow().as_ptr();
+
+        assert_eq!(row.as_text(&engine), "ab\ncd");
+        assert_eq!(row.scratch.borrow().as_ptr(), buffer);
+    }
+
+    #[test]
+    fn places_blocks_side_by_side() {
+        let engine: DummyEngine = DummyEngine {};
+        let row: GlyphRow = GlyphRow::new(vec![
+            Box::new(DummyGlyph {
+                text: "AA\nAA\nAA".to_string(),
+            }),
+            Box::new(DummyGlyph {
+                text: "B".to_string(),
+            }),
+        ])
+        .with_gap(1)
+        .with_vertical_align(VerticalAlign::Middle);
+
+        assert_eq!(row.as_text(&engine), "AA  \nAA B\nAA  ");
+    }
+
+    #[test]
+    fn sizes_children() {
+        let engine: DummyEngine = DummyEngine {};
+        let glyph = |text: &str| -> Box<dyn Glyph> {
+            Box::new(DummyGlyph {
+                text: text.to_string(),
+            })
+        };
+        let row: GlyphRow = GlyphRow::empty()
+            .with_child(glyph("abcdef"), Size::Fixed(3))
+            .with_child(glyph("x"), Size::Flex(1))
+            .with_child(glyph("y"), Size::Auto)
+            .with_width(8)
+            .with_horizontal_align(HorizontalAlign::Right);
+
+        assert_eq!(row.as_text(&engine), "def   xy");
+    }
+
     struct DummyEngine;
 
     impl GlyphRenderingEngine for DummyEngine {
@@ -93,13 +274,21 @@ mod test {
     }
 
     impl Glyph for DummyGlyph {
-        fn as_text(&self, _: &dyn GlyphRenderingEngine) -> String {
-            self.text.clone()
+        fn render_into(
+            &self,
+            _engine: &dyn GlyphRenderingEngine,
+            out: &mut dyn fmt::Write,
+        ) -> fmt::Result {
+            out.write_str(&self.text)
+        }
+
+        fn size_hint(&self, _engine: &dyn GlyphRenderingEngine) -> usize {
+            self.text.len()
         }
 
         fn add_child(&mut self, _child: Box<dyn Glyph>) {}
 
-        fn get_child_mut(&mut self, _index: usize) -> Option<&mut Box<dyn Glyph>> {
+        fn get_child_mut(&mut self, _index: usize) -> Option<&mut dyn Glyph> {
             None
         }
     }
@@ -0,0 +1,182 @@
+/// How much of a row's width or a column's height a child takes.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum Size {
+    /// As much as the child's text needs.
+    #[default]
+    Auto,
+    /// Exactly this many cells or lines; the child is padded or cut to fit.
+    Fixed(usize),
+    /// A share of the space the other children leave, in proportion to this weight. Only
+    /// containers given a width or height have space to share; others treat it as `Auto`.
+    Flex(usize),
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum HorizontalAlign {
+    #[default]
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub enum VerticalAlign {
+    #[default]
+    Top,
+    Middle,
+    Bottom,
+}
+
+impl HorizontalAlign {
+    /// Splits `extra` cells into the amounts before and after aligned content.
+    pub fn split(&self, extra: usize) -> (usize, usize) {
+        match self {
+            HorizontalAlign::Left => (0, extra),
+            HorizontalAlign::Center => (extra / 2, extra - extra / 2),
+            HorizontalAlign::Right => (extra, 0),
+        }
+    }
+}
+
+impl VerticalAlign {
+    /// Splits `extra` lines into the amounts above and below aligned content.
+    pub fn split(&self, extra: usize) -> (usize, usize) {
+        match self {
+            VerticalAlign::Top => (0, extra),
+            VerticalAlign::Middle => (extra / 2, extra - extra / 2),
+            VerticalAlign::Bottom => (extra, 0),
+        }
+    }
+}
+
+/// Blank cells around each side of a glyph, as padding or margin.
+#[derive(Debug, PartialEq, Clone, Copy, Default)]
+pub struct Spacing {
+    pub top: usize,
+    pub right: usize,
+    pub bottom: usize,
+    pub left: usize,
+}
+
+impl Spacing {
+    /// The same amount on every side.
+    pub fn all(amount: usize) -> Self {
+        Self {
+            top: amount,
+            right: amount,
+            bottom: amount,
+            left: amount,
+        }
+    }
+
+    /// `vertical` lines above and below, and `horizontal` cells left and right.
+    pub fn symmetric(vertical: usize, horizontal: usize) -> Self {
+        Self {
+            top: vertical,
+            right: horizontal,
+            bottom: vertical,
+            left: horizontal,
+        }
+    }
+}
+
+/// Box-drawing characters a [`GlyphBox`](crate::domain::entities::GlyphBox) is framed with.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum BorderStyle {
+    /// `┌─┐`
+    Single,
+    /// `╔═╗`
+    Double,
+    /// `╭─╮`
+    Rounded,
+    /// `┏━┓`
+    Heavy,
+}
+
+impl BorderStyle {
+    /// Top-left, top-right, bottom-left and bottom-right corners, then the horizontal and
+    /// vertical lines.
+    pub fn chars(&self) -> [char; 6] {
+        match self {
+            BorderStyle::Single => ['┌', '┐', '└', '┘', '─', '│'],
+            BorderStyle::Double => ['╔', '╗', '╚', '╝', '═', '║'],
+            BorderStyle::Rounded => ['╭', '╮', '╰', '╯', '─', '│'],
+            BorderStyle::Heavy => ['┏', '┓', '┗', '┛', '━', '┃'],
+        }
+    }
+}
+
+/// Space each child gets along a row or column: `Auto` children take `natural`, fixed ones their
+/// size, and flex ones share what is left of `available`, the last taking any remainder.
+pub(crate) fn distribute(
+    sizes: &[Size],
+    natural: &[usize],
+    gap: usize,
+    available: Option<usize>,
+) -> Vec<usize> {
+    let gaps: usize = gap * sizes.len().saturating_sub(1);
+    let taken: usize = sizes
+        .iter()
+        .zip(natural)
+        .map(|(size, natural)| match (size, available) {
+            (Size::Fixed(n), _) => *n,
+            (Size::Flex(_), Some(_)) => 0,
+            (_, _) => *natural,
+        })
+        .sum::<usize>()
+        + gaps;
+    let weights: usize = sizes
+        .iter()
+        .map(|size: &Size| match size {
+            Size::Flex(weight) => *weight,
+            _ => 0,
+        })
+        .sum();
+    let mut left: usize = available.map_or(0, |available| available.saturating_sub(taken));
+    let mut weights_left: usize = weights;
+
+    sizes
+        .iter()
+        .zip(natural)
+        .map(|(size, natural)| match (size, available) {
+            (Size::Fixed(n), _) => *n,
+            (Size::Flex(weight), Some(_)) => {
+                let share: usize = match weights_left {
+                    0 => 0,
+                    _ if *weight == weights_left => left,
+                    _ => left * weight / weights_left,
+                };
+                left -= share;
+                weights_left -= weight;
+                share
+            }
+            (_, _) => *natural,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::entities::layout::{Size, distribute};
+
+    #[test]
+    fn flex_children_share_the_rest() {
+        let sizes: Vec<usize> = distribute(
+            &[Size::Auto, Size::Flex(1), Size::Fixed(3), Size::Flex(2)],
+            &[4, 9, 9, 9],
+            1,
+            Some(20),
+        );
+
+        assert_eq!(sizes, vec![4, 3, 3, 7]);
+    }
+
+    #[test]
+    fn flex_is_auto_without_available_space() {
+        let sizes: Vec<usize> = distribute(&[Size::Flex(1), Size::Fixed(2)], &[5, 9], 0, None);
+
+        assert_eq!(sizes, vec![5, 2]);
+    }
+}
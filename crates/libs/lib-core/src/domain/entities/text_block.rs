@@ -0,0 +1,349 @@
+use std::{borrow::Cow, fmt, ops::Range};
+
+use crate::domain::entities::{
+    glyph::Glyph,
+    glyph_rendering_engine::GlyphRenderingEngine,
+    layout::{HorizontalAlign, VerticalAlign},
+};
+
+/// Rendered glyph text as a rectangle of cells, for laying it out next to other text.
+#[derive(Debug)]
+pub(crate) struct TextBlock<'a> {
+    lines: Vec<&'a str>,
+    width: usize,
+}
+
+impl<'a> TextBlock<'a> {
+    pub fn new(text: &'a str) -> Self {
+        let lines: Vec<&str> = text.lines().collect();
+        let width: usize = lines.iter().map(|l| cell_count(l)).max().unwrap_or(0);
+
+        Self { lines, width }
+    }
+
+    /// Renders `glyphs` one after another into `text`, which is cleared and grown to `capacity`
+    /// first, so that the children of a layout share one buffer, and splits it back into a
+    /// block per glyph.
+    pub fn render_all<'g>(
+        glyphs: impl IntoIterator<Item = &'g dyn Glyph>,
+        engine: &dyn GlyphRenderingEngine,
+        text: &'a mut String,
+        capacity: usize,
+    ) -> Result<Vec<Self>, fmt::Error> {
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+
+        text.clear();
+        text.reserve(capacity);
+        for glyph in glyphs {
+            let start: usize = text.len();
+
+            glyph.render_into(engine, text)?;
+            ranges.push(start..text.len());
+        }
+
+        let text: &'a String = text;
+
+        Ok(ranges
+            .into_iter()
+            .map(|range: Range<usize>| Self::new(&text[range]))
+            .collect())
+    }
+
+    pub fn lines(&self) -> &[&'a str] {
+        &self.lines
+    }
+
+    /// Width of the widest line, in cells.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Line `y` of the block laid out in `height` lines, with blank lines added or lines cut off
+    /// on the side `vertical` points away from; fit it to a width with [`write_fitted`].
+    pub fn line(&self, y: usize, height: usize, vertical: VerticalAlign) -> &'a str {
+        let (before, _): (usize, usize) = vertical.split(height.abs_diff(self.height()));
+        let line: Option<&&str> = if self.height() > height {
+            self.lines.get(y + before)
+        } else {
+            y.checked_sub(before).and_then(|y: usize| self.lines.get(y))
+        };
+
+        line.copied().unwrap_or_default()
+    }
+}
+
+/// Writes lines to `out` separated by `\n`, like [`write_lines`](super::glyph::write_lines),
+/// but one at a time and dropping any past `limit`.
+pub(crate) struct LineWriter<'a> {
+    out: &'a mut dyn fmt::Write,
+    count: usize,
+    limit: usize,
+}
+
+impl<'a> LineWriter<'a> {
+    pub fn new(out: &'a mut dyn fmt::Write) -> Self {
+        Self::with_limit(out, usize::MAX)
+    }
+
+    pub fn with_limit(out: &'a mut dyn fmt::Write, limit: usize) -> Self {
+        Self {
+            out,
+            count: 0,
+            limit,
+        }
+    }
+
+    /// Lines written so far.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Starts a new line and fills it with `write`, unless the limit has been reached.
+    pub fn line(&mut self, write: impl FnOnce(&mut dyn fmt::Write) -> fmt::Result) -> fmt::Result {
+        if self.count >= self.limit {
+            return Ok(());
+        }
+        if self.count > 0 {
+            self.out.write_char('\n')?;
+        }
+        self.count += 1;
+
+        write(self.out)
+    }
+}
+
+/// Writes `count` spaces.
+pub(crate) fn write_spaces(out: &mut dyn fmt::Write, count: usize) -> fmt::Result {
+    (0..count).try_for_each(|_| out.write_char(' '))
+}
+
+/// Writes `line` padded or cut to exactly `width` cells. A cut line that was styled ends with
+/// `reset`.
+pub(crate) fn write_fitted(
+    out: &mut dyn fmt::Write,
+    line: &str,
+    width: usize,
+    align: HorizontalAlign,
+    reset: &str,
+) -> fmt::Result {
+    let count: usize = cell_count(line);
+
+    if count <= width {
+        let (left, right): (usize, usize) = align.split(width - count);
+
+        write_spaces(out, left)?;
+        out.write_str(line)?;
+        return write_spaces(out, right);
+    }
+
+    let cells: Vec<&str> = split_cells(line);
+    let (left, right): (usize, usize) = align.split(count - width);
+    let kept: &[&str] = &cells[left..cells.len() - right];
+
+    kept.iter()
+        .try_for_each(|cell: &&str| out.write_str(cell))?;
+    if kept.iter().any(|cell: &&str| cell.starts_with('\x1b')) {
+        out.write_str(reset)?;
+    }
+
+    Ok(())
+}
+
+/// Writes one line of cells picked from different outputs, with `reset` after each run of
+/// styled cells so that their styling does not leak into plain ones.
+pub(crate) fn write_cells<S: AsRef<str>>(
+    out: &mut dyn fmt::Write,
+    cells: impl IntoIterator<Item = S>,
+    reset: &str,
+) -> fmt::Result {
+    let mut styled: bool = false;
+
+    for cell in cells {
+        let cell: &str = cell.as_ref();
+        let cell_styled: bool = cell.starts_with('\x1b');
+
+        if styled && !cell_styled {
+            out.write_str(reset)?;
+        }
+
+        out.write_str(cell)?;
+        styled = cell_styled;
+    }
+
+    if styled {
+        out.write_str(reset)?;
+    }
+
+    Ok(())
+}
+
+/// The cell shown where `above` is drawn over `below`; `None` stands for a cell that is not
+/// drawn or is transparent. A colored cell drawn at less than full `opacity` is blended with the
+/// color beneath, or with black over an uncolored cell, but keeps its own character.
+pub(crate) fn merge_cell<'c>(
+    engine: &dyn GlyphRenderingEngine,
+    below: Option<Cow<'c, str>>,
+    above: Option<&'c str>,
+    opacity: f32,
+) -> Option<Cow<'c, str>> {
+    let Some(above) = above else {
+        return below;
+    };
+
+    Some(match cell_color(above) {
+        Some(color) if opacity < 1.0 => {
+            let under: (u8, u8, u8) = below.as_deref().and_then(cell_color).unwrap_or((0, 0, 0));
+            let (r, g, b) = blend(under, color, opacity);
+            let c: char = above.chars().last().unwrap_or(' ');
+
+            Cow::Owned(engine.get_text_based_on_colored_char(c, r, g, b))
+        }
+        _ => Cow::Borrowed(above),
+    })
+}
+
+fn blend(below: (u8, u8, u8), above: (u8, u8, u8), opacity: f32) -> (u8, u8, u8) {
+    let mix = |b: u8, a: u8| (b as f32 + (a as f32 - b as f32) * opacity).round() as u8;
+
+    (
+        mix(below.0, above.0),
+        mix(below.1, above.1),
+        mix(below.2, above.2),
+    )
+}
+
+/// Number of cells `line` covers, not counting ANSI escape sequences.
+pub(crate) fn cell_count(line: &str) -> usize {
+    let mut count: usize = 0;
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() || c == '~' {
+                    break;
+                }
+            }
+            continue;
+        }
+
+        count += 1;
+    }
+
+    count
+}
+
+/// Splits a rendered line into cells, keeping ANSI escape sequences with the character they
+/// style. Leading resets and trailing escapes that style nothing are dropped.
+pub(crate) fn split_cells(line: &str) -> Vec<&str> {
+    let mut cells: Vec<&str> = Vec::new();
+    let mut start: usize = 0;
+    let mut chars = line.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        if c == '\x1b' {
+            let mut end: usize = line.len();
+
+            // Skip to the final byte of the control sequence.
+            for (j, c) in chars.by_ref() {
+                if c.is_ascii_alphabetic() || c == '~' {
+                    end = j + 1;
+                    break;
+                }
+            }
+
+            // A leading reset only undoes the previous cell, which is tracked separately.
+            if start == i && matches!(&line[i..end], "\x1b[0m" | "\x1b[m") {
+                start = end;
+            }
+            continue;
+        }
+
+        let end: usize = i + c.len_utf8();
+        cells.push(&line[start..end]);
+        start = end;
+    }
+
+    cells
+}
+
+/// The 24-bit foreground color a cell from [`split_cells`] starts with, if any.
+pub(crate) fn cell_color(cell: &str) -> Option<(u8, u8, u8)> {
+    let params: &str = cell.strip_prefix("\x1b[38;2;")?.split('m').next()?;
+    let mut channels = params.split(';').map(|c: &str| c.parse::<u8>().ok());
+
+    Some((channels.next()??, channels.next()??, channels.next()??))
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::entities::{
+        layout::{HorizontalAlign, VerticalAlign},
+        text_block::{LineWriter, TextBlock, write_cells, write_fitted},
+    };
+
+    fn fitted(line: &str, width: usize, align: HorizontalAlign, reset: &str) -> String {
+        let mut out: String = String::new();
+
+        write_fitted(&mut out, line, width, align, reset).unwrap();
+        out
+    }
+
+    #[test]
+    fn resets_styling_before_plain_cells_and_at_the_end() {
+        let mut out: String = String::new();
+
+        write_cells(&mut out, ["\x1b[31ma", "b", "\x1b[32mc"], ";").unwrap();
+        assert_eq!(out, "\x1b[31ma;b\x1b[32mc;");
+    }
+
+    #[test]
+    fn measures_cells_without_escapes() {
+        let block: TextBlock = TextBlock::new("\x1b[31mab\x1b[0m\nabc ");
+
+        assert_eq!((block.width(), block.height()), (4, 2));
+    }
+
+    #[test]
+    fn cuts_styled_lines_on_cell_boundaries() {
+        let line: &str = "\x1b[31ma\x1b[32mb\x1b[33mc\x1b[0m";
+
+        assert_eq!(
+            fitted(line, 2, HorizontalAlign::Left, ";"),
+            "\x1b[31ma\x1b[32mb;"
+        );
+        assert_eq!(fitted(line, 1, HorizontalAlign::Right, ";"), "\x1b[33mc;");
+    }
+
+    #[test]
+    fn aligns_within_a_larger_area() {
+        let block: TextBlock = TextBlock::new("ab");
+        let mut text: String = String::new();
+        let mut lines: LineWriter = LineWriter::with_limit(&mut text, 3);
+
+        for y in 0..4 {
+            lines
+                .line(|out| {
+                    let line: &str = block.line(y, 3, VerticalAlign::Bottom);
+
+                    write_fitted(out, line, 4, HorizontalAlign::Center, "")
+                })
+                .unwrap();
+        }
+
+        assert_eq!(text, "    \n    \n ab ");
+    }
+
+    #[test]
+    fn cuts_blocks_taller_than_the_area() {
+        let block: TextBlock = TextBlock::new("a\nb\nc");
+
+        assert_eq!(block.line(0, 1, VerticalAlign::Middle), "b");
+    }
+}
@@ -1 +1,2 @@
+pub mod codecs;
 pub mod entities;
@@ -0,0 +1,37 @@
+#[cfg(feature = "bmp")]
+mod bmp_codec;
+#[cfg(any(feature = "gif", feature = "png"))]
+mod canvas;
+mod decode_error;
+mod decoded_image;
+mod frame_source;
+#[cfg(feature = "gif")]
+mod gif_codec;
+mod image_decoder;
+mod image_format;
+#[cfg(feature = "jpeg")]
+mod jpeg_codec;
+#[cfg(feature = "png")]
+mod png_codec;
+#[cfg(feature = "pnm")]
+mod pnm_codec;
+#[cfg(feature = "qoi")]
+mod qoi_codec;
+mod raw_frames;
+#[cfg(feature = "tiff")]
+mod tiff_codec;
+#[cfg(feature = "webp")]
+mod webp_codec;
+#[cfg(feature = "y4m")]
+mod y4m_codec;
+
+pub use decode_error::DecodeError;
+pub use decoded_image::DecodedImage;
+pub use frame_source::{FrameSource, LoopCount, Playback, TimedFrame};
+pub use image_decoder::{decode, decode_file, decode_frames, decode_frames_file};
+pub use image_format::ImageFormat;
+#[cfg(feature = "pnm")]
+pub use pnm_codec::{PnmFormat, encode_pnm};
+pub use raw_frames::{RawFrames, RawPixelFormat};
+#[cfg(feature = "y4m")]
+pub use y4m_codec::{Y4mChroma, Y4mHeader, Y4mReader, Y4mWriter};
@@ -0,0 +1,281 @@
+use crate::domain::{
+    codecs::{decode_error::DecodeError, decoded_image::DecodedImage, image_format::ImageFormat},
+    entities::{RgbImage, RgbaImage},
+};
+
+const FILE_HEADER_SIZE: usize = 14;
+const CORE_HEADER_SIZE: usize = 12;
+const INFO_HEADER_SIZE: usize = 40;
+
+const BI_RGB: u32 = 0;
+const BI_BITFIELDS: u32 = 3;
+const BI_ALPHABITFIELDS: u32 = 6;
+
+/// Channel masks of a 16 or 32-bit pixel, in `R, G, B, A` order.
+type Masks = [u32; 4];
+
+/// Decodes uncompressed and bitfield BMP/DIB images with 1, 4, 8, 16, 24 or 32 bits per pixel.
+pub(crate) fn decode(bytes: &[u8]) -> Result<DecodedImage, DecodeError> {
+    let malformed = |message: &str| DecodeError::malformed(ImageFormat::Bmp, message);
+    let u16_at = |offset: usize| {
+        bytes
+            .get(offset..offset + 2)
+            .map(|b: &[u8]| u16::from_le_bytes([b[0], b[1]]))
+            .ok_or_else(|| malformed("truncated header"))
+    };
+    let u32_at = |offset: usize| {
+        bytes
+            .get(offset..offset + 4)
+            .map(|b: &[u8]| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .ok_or_else(|| malformed("truncated header"))
+    };
+
+    let pixel_offset: usize = u32_at(10)? as usize;
+    let header_size: usize = u32_at(14)? as usize;
+
+    let (width, height, bits, compression): (i64, i64, u16, u32) = match header_size {
+        CORE_HEADER_SIZE => (u16_at(18)? as i64, u16_at(20)? as i64, u16_at(24)?, BI_RGB),
+        size if size >= INFO_HEADER_SIZE => (
+            u32_at(18)? as i32 as i64,
+            u32_at(22)? as i32 as i64,
+            u16_at(28)?,
+            u32_at(30)?,
+        ),
+        _ => return Err(malformed("unknown header version")),
+    };
+
+    if width <= 0 || height == 0 {
+        return Err(malformed("empty image"));
+    }
+    if !matches!(compression, BI_RGB | BI_BITFIELDS | BI_ALPHABITFIELDS) {
+        return Err(malformed("compressed BMP images are not supported"));
+    }
+
+    let top_down: bool = height < 0;
+    let (width, height): (usize, usize) = (width as usize, height.unsigned_abs() as usize);
+
+    let masks: Masks = match (compression, bits) {
+        (BI_BITFIELDS | BI_ALPHABITFIELDS, 16 | 32) => [
+            u32_at(54)?,
+            u32_at(58)?,
+            u32_at(62)?,
+            if header_size >= 56 || compression == BI_ALPHABITFIELDS {
+                u32_at(66)?
+            } else {
+                0
+            },
+        ],
+        (BI_RGB, 16) => [0x7C00, 0x03E0, 0x001F, 0],
+        (BI_RGB, 32) => [0x00FF_0000, 0x0000_FF00, 0x0000_00FF, 0],
+        (BI_RGB, 1 | 4 | 8 | 24) => [0; 4],
+        _ => return Err(malformed("unsupported bit depth")),
+    };
+
+    let palette: Vec<[u8; 3]> = if bits <= 8 {
+        let entry_size: usize = if header_size == CORE_HEADER_SIZE {
+            3
+        } else {
+            4
+        };
+        let colors_used: usize = match header_size {
+            CORE_HEADER_SIZE => 0,
+            _ => u32_at(46)? as usize,
+        };
+        let count: usize = match colors_used {
+            0 => 1 << bits,
+            n => n.min(1 << bits),
+        };
+        let start: usize = FILE_HEADER_SIZE + header_size;
+
+        bytes
+            .get(start..)
+            .unwrap_or_default()
+            .chunks_exact(entry_size)
+            .take(count)
+            .map(|bgr: &[u8]| [bgr[2], bgr[1], bgr[0]])
+            .collect()
+    } else {
+        Vec::new()
+    };
+
+    let row_size: usize = (bits as usize)
+        .checked_mul(width)
+        .and_then(|row_bits: usize| row_bits.checked_add(31))
+        .map(|row_bits: usize| row_bits / 32 * 4)
+        .ok_or_else(|| malformed("image too large"))?;
+    let pixels: &[u8] = row_size
+        .checked_mul(height)
+        .and_then(|size: usize| bytes.get(pixel_offset..pixel_offset.checked_add(size)?))
+        .ok_or_else(|| malformed("truncated pixel data"))?;
+
+    let mut data: Vec<u8> = Vec::with_capacity(width * height * 4);
+
+    for y in 0..height {
+        let source_y: usize = if top_down { y } else { height - 1 - y };
+        let row: &[u8] = &pixels[source_y * row_size..(source_y + 1) * row_size];
+
+        for x in 0..width {
+            data.extend_from_slice(&read_pixel(row, x, bits, &palette, &masks));
+        }
+    }
+
+    // Some writers declare an alpha mask but leave every alpha sample at zero.
+    if masks[3] == 0 || data.chunks_exact(4).all(|p: &[u8]| p[3] == 0) {
+        return Ok(DecodedImage::Rgb(RgbImage::from_rgb_data(
+            width,
+            height,
+            data.chunks_exact(4)
+                .flat_map(|p: &[u8]| [p[0], p[1], p[2]])
+                .collect(),
+        )));
+    }
+
+    Ok(DecodedImage::Rgba(RgbaImage::from_rgba_data(
+        width, height, data,
+    )))
+}
+
+fn read_pixel(row: &[u8], x: usize, bits: u16, palette: &[[u8; 3]], masks: &Masks) -> [u8; 4] {
+    match bits {
+        1 | 4 | 8 => {
+            let bits: usize = bits as usize;
+            let byte: u8 = row[x * bits / 8];
+            let shift: usize = 8 - bits - (x * bits % 8);
+            let index: usize = ((byte >> shift) & ((1u16 << bits) - 1) as u8) as usize;
+            let [r, g, b]: [u8; 3] = palette.get(index).copied().unwrap_or_default();
+
+            [r, g, b, u8::MAX]
+        }
+        24 => [row[x * 3 + 2], row[x * 3 + 1], row[x * 3], u8::MAX],
+        16 => unpack(
+            u16::from_le_bytes([row[x * 2], row[x * 2 + 1]]) as u32,
+            masks,
+        ),
+        _ => unpack(
+            u32::from_le_bytes([row[x * 4], row[x * 4 + 1], row[x * 4 + 2], row[x * 4 + 3]]),
+            masks,
+        ),
+    }
+}
+
+/// Extracts each masked channel and scales it to 8 bits.
+fn unpack(value: u32, masks: &Masks) -> [u8; 4] {
+    masks.map(|mask: u32| {
+        if mask == 0 {
+            return 0;
+        }
+
+        let max: u64 = (1u64 << mask.count_ones()) - 1;
+        let sample: u64 = ((value & mask) >> mask.trailing_zeros()) as u64;
+
+        (sample * 255 / max) as u8
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::{
+        codecs::{bmp_codec::decode, decode_error::DecodeError, decoded_image::DecodedImage},
+        entities::{RgbImage, RgbaImage},
+    };
+
+    /// Builds a BMP with a 40-byte info header, or a 56-byte one when `masks` are given.
+    fn bmp(
+        width: i32,
+        height: i32,
+        bits: u16,
+        palette: &[[u8; 4]],
+        masks: Option<[u32; 4]>,
+        pixels: &[u8],
+    ) -> Vec<u8> {
+        let header_size: u32 = if masks.is_some() { 56 } else { 40 };
+        let pixel_offset: u32 = 14 + header_size + palette.len() as u32 * 4;
+
+        let mut bytes: Vec<u8> = b"BM".to_vec();
+        bytes.extend((pixel_offset + pixels.len() as u32).to_le_bytes());
+        bytes.extend([0; 4]);
+        bytes.extend(pixel_offset.to_le_bytes());
+        bytes.extend(header_size.to_le_bytes());
+        bytes.extend(width.to_le_bytes());
+        bytes.extend(height.to_le_bytes());
+        bytes.extend(1u16.to_le_bytes());
+        bytes.extend(bits.to_le_bytes());
+        bytes.extend((if masks.is_some() { 3u32 } else { 0 }).to_le_bytes());
+        bytes.extend([0; 12]);
+        bytes.extend((palette.len() as u32).to_le_bytes());
+        bytes.extend([0; 4]);
+        if let Some(masks) = masks {
+            masks
+                .iter()
+                .for_each(|m: &u32| bytes.extend(m.to_le_bytes()));
+        }
+        palette
+            .iter()
+            .for_each(|entry: &[u8; 4]| bytes.extend(entry));
+        bytes.extend(pixels);
+
+        bytes
+    }
+
+    #[test]
+    fn should_decode_bottom_up_24_bit_rows_with_padding() {
+        let bytes: Vec<u8> = bmp(
+            1,
+            2,
+            24,
+            &[],
+            None,
+            &[255, 0, 0, 0, 0, 0, 255, 0], // blue on the bottom row, red on top
+        );
+
+        assert_eq!(
+            decode(&bytes).unwrap(),
+            DecodedImage::Rgb(RgbImage::from_rgb_data(1, 2, vec![255, 0, 0, 0, 0, 255]))
+        );
+    }
+
+    #[test]
+    fn should_decode_top_down_paletted_1_bit() {
+        let palette: [[u8; 4]; 2] = [[0, 0, 0, 0], [255, 255, 255, 0]];
+        let bytes: Vec<u8> = bmp(3, -1, 1, &palette, None, &[0b1010_0000, 0, 0, 0]);
+
+        assert_eq!(
+            decode(&bytes).unwrap(),
+            DecodedImage::Rgb(RgbImage::from_rgb_data(
+                3,
+                1,
+                vec![255, 255, 255, 0, 0, 0, 255, 255, 255]
+            ))
+        );
+    }
+
+    #[test]
+    fn should_decode_32_bit_bitfields_with_alpha() {
+        let masks: [u32; 4] = [0x00FF_0000, 0x0000_FF00, 0x0000_00FF, 0xFF00_0000];
+        let bytes: Vec<u8> = bmp(1, 1, 32, &[], Some(masks), &[30, 20, 10, 128]);
+
+        assert_eq!(
+            decode(&bytes).unwrap(),
+            DecodedImage::Rgba(RgbaImage::from_rgba_data(1, 1, vec![10, 20, 30, 128]))
+        );
+    }
+
+    #[test]
+    fn should_scale_16_bit_555_channels() {
+        let bytes: Vec<u8> = bmp(1, 1, 16, &[], None, &[0x1F, 0x7C, 0, 0]);
+
+        assert_eq!(
+            decode(&bytes).unwrap(),
+            DecodedImage::Rgb(RgbImage::from_rgb_data(1, 1, vec![255, 0, 255]))
+        );
+    }
+
+    #[test]
+    fn should_reject_truncated_pixel_data() {
+        let bytes: Vec<u8> = bmp(4, 4, 24, &[], None, &[0; 8]);
+
+        assert!(matches!(decode(&bytes), Err(DecodeError::Malformed { .. })));
+    }
+}
@@ -0,0 +1,249 @@
+use crate::domain::{
+    codecs::decoded_image::DecodedImage,
+    entities::{Rect, RgbImage, RgbaImage},
+};
+
+/// How a frame's pixels are combined with what is already on the canvas.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum Blend {
+    /// Frame pixels replace the canvas, alpha included.
+    #[cfg(feature = "png")]
+    Source,
+    /// Frame pixels are alpha-composited over the canvas.
+    Over,
+}
+
+/// What happens to a frame's area before the next frame is drawn.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(crate) enum Disposal {
+    Keep,
+    /// Clear the area to transparent.
+    Clear,
+    /// Put back the canvas as it was before the frame was drawn.
+    Restore,
+}
+
+#[derive(Debug, PartialEq, Clone)]
+enum Pending {
+    Keep,
+    Clear(Rect),
+    Restore(Vec<u8>),
+}
+
+/// RGBA screen that animation frames are composited onto.
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct Canvas {
+    width: usize,
+    height: usize,
+    data: Vec<u8>,
+    pending: Pending,
+}
+
+impl Canvas {
+    pub(crate) fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            data: vec![0; width * height * 4],
+            pending: Pending::Keep,
+        }
+    }
+
+    /// Clears the canvas for another pass over the frames.
+    pub(crate) fn reset(&mut self) {
+        self.data.fill(0);
+        self.pending = Pending::Keep;
+    }
+
+    /// Disposes of the previous frame, then draws an `area`-sized `rgba` frame at its position.
+    ///
+    /// `disposal` decides what happens to this frame once the next one arrives.
+    pub(crate) fn draw(&mut self, area: Rect, rgba: &[u8], blend: Blend, disposal: Disposal) {
+        match std::mem::replace(&mut self.pending, Pending::Keep) {
+            Pending::Keep => {}
+            Pending::Clear(previous) => self.clear(previous),
+            Pending::Restore(snapshot) => self.data = snapshot,
+        }
+
+        self.pending = match disposal {
+            Disposal::Keep => Pending::Keep,
+            Disposal::Clear => Pending::Clear(area),
+            Disposal::Restore => Pending::Restore(self.data.clone()),
+        };
+
+        if area.width == 0 {
+            return;
+        }
+
+        for (y, row) in rgba
+            .chunks_exact(area.width * 4)
+            .take(area.height)
+            .enumerate()
+        {
+            if area.y + y >= self.height {
+                break;
+            }
+
+            for (x, source) in row.chunks_exact(4).enumerate() {
+                if area.x + x >= self.width {
+                    break;
+                }
+
+                let offset: usize = ((area.y + y) * self.width + area.x + x) * 4;
+                let target: &mut [u8] = &mut self.data[offset..offset + 4];
+
+                match blend {
+                    #[cfg(feature = "png")]
+                    Blend::Source => target.copy_from_slice(source),
+                    Blend::Over => over(source, target),
+                }
+            }
+        }
+    }
+
+    fn clear(&mut self, area: Rect) {
+        for y in area.y..(area.y + area.height).min(self.height) {
+            let start: usize = (y * self.width + area.x.min(self.width)) * 4;
+            let end: usize = (y * self.width + (area.x + area.width).min(self.width)) * 4;
+
+            self.data[start..end].fill(0);
+        }
+    }
+
+    /// The current screen, without an alpha channel when every pixel is opaque.
+    pub(crate) fn to_image(&self) -> DecodedImage {
+        if self.data.chunks_exact(4).all(|p: &[u8]| p[3] == u8::MAX) {
+            return DecodedImage::Rgb(RgbImage::from_rgb_data(
+                self.width,
+                self.height,
+                self.data
+                    .chunks_exact(4)
+                    .flat_map(|p: &[u8]| [p[0], p[1], p[2]])
+                    .collect(),
+            ));
+        }
+
+        DecodedImage::Rgba(RgbaImage::from_rgba_data(
+            self.width,
+            self.height,
+            self.data.clone(),
+        ))
+    }
+}
+
+/// Straight-alpha "source over destination".
+fn over(source: &[u8], target: &mut [u8]) {
+    match source[3] {
+        0 => {}
+        u8::MAX => target.copy_from_slice(source),
+        alpha => {
+            let source_alpha: u32 = alpha as u32;
+            let target_alpha: u32 = target[3] as u32 * (255 - source_alpha) / 255;
+            let alpha: u32 = source_alpha + target_alpha;
+
+            for c in 0..3 {
+                target[c] = ((source[c] as u32 * source_alpha + target[c] as u32 * target_alpha)
+                    / alpha) as u8;
+            }
+            target[3] = alpha as u8;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::{
+        codecs::{
+            canvas::{Blend, Canvas, Disposal},
+            decoded_image::DecodedImage,
+        },
+        entities::{Rect, RgbImage, RgbaImage},
+    };
+
+    const RED: [u8; 4] = [255, 0, 0, 255];
+    const BLUE: [u8; 4] = [0, 0, 255, 255];
+
+    #[test]
+    fn should_dispose_previous_frame_before_drawing_next() {
+        let mut canvas: Canvas = Canvas::new(2, 1);
+
+        canvas.draw(Rect::new(0, 0, 1, 1), &RED, Blend::Over, Disposal::Keep);
+        canvas.draw(Rect::new(1, 0, 1, 1), &BLUE, Blend::Over, Disposal::Clear);
+        assert_eq!(
+            canvas.to_image(),
+            DecodedImage::Rgb(RgbImage::from_rgb_data(2, 1, vec![255, 0, 0, 0, 0, 255]))
+        );
+
+        canvas.draw(Rect::new(0, 0, 0, 0), &[], Blend::Over, Disposal::Keep);
+        assert_eq!(
+            canvas.to_image(),
+            DecodedImage::Rgba(RgbaImage::from_rgba_data(
+                2,
+                1,
+                vec![255, 0, 0, 255, 0, 0, 0, 0]
+            ))
+        );
+    }
+
+    #[test]
+    fn should_restore_canvas_from_before_frame() {
+        let mut canvas: Canvas = Canvas::new(1, 1);
+
+        canvas.draw(Rect::new(0, 0, 1, 1), &RED, Blend::Over, Disposal::Keep);
+        canvas.draw(Rect::new(0, 0, 1, 1), &BLUE, Blend::Over, Disposal::Restore);
+        canvas.draw(Rect::new(0, 0, 1, 1), &[0; 4], Blend::Over, Disposal::Keep);
+
+        assert_eq!(
+            canvas.to_image(),
+            DecodedImage::Rgb(RgbImage::from_rgb_data(1, 1, vec![255, 0, 0]))
+        );
+    }
+
+    #[test]
+    fn over_should_blend_partial_alpha() {
+        let mut canvas: Canvas = Canvas::new(1, 1);
+
+        canvas.draw(Rect::new(0, 0, 1, 1), &BLUE, Blend::Over, Disposal::Keep);
+        canvas.draw(
+            Rect::new(0, 0, 1, 1),
+            &[255, 0, 0, 51],
+            Blend::Over,
+            Disposal::Keep,
+        );
+
+        assert_eq!(
+            canvas.to_image(),
+            DecodedImage::Rgb(RgbImage::from_rgb_data(1, 1, vec![51, 0, 204]))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "png")]
+    fn source_should_replace_alpha() {
+        let mut canvas: Canvas = Canvas::new(2, 1);
+
+        canvas.draw(
+            Rect::new(0, 0, 2, 1),
+            &[RED, RED].concat(),
+            Blend::Over,
+            Disposal::Keep,
+        );
+        canvas.draw(
+            Rect::new(1, 0, 1, 1),
+            &[0, 0, 255, 0],
+            Blend::Source,
+            Disposal::Keep,
+        );
+
+        assert_eq!(
+            canvas.to_image(),
+            DecodedImage::Rgba(RgbaImage::from_rgba_data(
+                2,
+                1,
+                vec![255, 0, 0, 255, 0, 0, 255, 0]
+            ))
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use std::{fmt, io};
+
+use crate::domain::codecs::image_format::ImageFormat;
+
+#[derive(Debug)]
+pub enum DecodeError {
+    /// The data does not start with the signature of any format `lib-image` knows.
+    UnknownFormat,
+    /// The format was recognised, but support for it was not compiled in.
+    UnsupportedFormat(ImageFormat),
+    /// The data claims to be `format` but could not be decoded.
+    Malformed {
+        format: ImageFormat,
+        message: String,
+    },
+    Io(io::Error),
+}
+
+impl DecodeError {
+    #[cfg(any(
+        feature = "png",
+        feature = "jpeg",
+        feature = "gif",
+        feature = "bmp",
+        feature = "webp",
+        feature = "tiff",
+        feature = "qoi",
+        feature = "pnm",
+        feature = "y4m"
+    ))]
+    pub(crate) fn malformed(format: ImageFormat, message: impl fmt::Display) -> Self {
+        DecodeError::Malformed {
+            format,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecodeError::UnknownFormat => write!(f, "unrecognised image format"),
+            DecodeError::UnsupportedFormat(format) => write!(
+                f,
+                "{format} decoding is not enabled; build lib-image with the '{}' feature",
+                format.feature()
+            ),
+            DecodeError::Malformed { format, message } => {
+                write!(f, "invalid {format} image: {message}")
+            }
+            DecodeError::Io(error) => write!(f, "could not read image: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DecodeError::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for DecodeError {
+    fn from(error: io::Error) -> Self {
+        DecodeError::Io(error)
+    }
+}
@@ -0,0 +1,85 @@
+use crate::domain::entities::{LumaImage, RgbImage, RgbaImage};
+
+/// A decoded image in the narrowest pixel layout that holds all of its information.
+#[derive(Debug, PartialEq, Clone)]
+pub enum DecodedImage {
+    Luma(LumaImage),
+    Rgb(RgbImage),
+    Rgba(RgbaImage),
+}
+
+impl DecodedImage {
+    /// Wraps packed 8-bit samples with 1 (luma), 2 (luma, alpha), 3 (RGB) or 4 (RGBA) channels.
+    pub(crate) fn from_samples(
+        width: usize,
+        height: usize,
+        channels: usize,
+        data: Vec<u8>,
+    ) -> Option<Self> {
+        match channels {
+            1 => Some(DecodedImage::Luma(LumaImage::from_luma_data(
+                width, height, data,
+            ))),
+            2 => Some(DecodedImage::Rgba(RgbaImage::from_rgba_data(
+                width,
+                height,
+                data.chunks_exact(2)
+                    .flat_map(|p: &[u8]| [p[0], p[0], p[0], p[1]])
+                    .collect(),
+            ))),
+            3 => Some(DecodedImage::Rgb(RgbImage::from_rgb_data(
+                width, height, data,
+            ))),
+            4 => Some(DecodedImage::Rgba(RgbaImage::from_rgba_data(
+                width, height, data,
+            ))),
+            _ => None,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        match self {
+            DecodedImage::Luma(image) => image.width(),
+            DecodedImage::Rgb(image) => image.width(),
+            DecodedImage::Rgba(image) => image.width(),
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        match self {
+            DecodedImage::Luma(image) => image.height(),
+            DecodedImage::Rgb(image) => image.height(),
+            DecodedImage::Rgba(image) => image.height(),
+        }
+    }
+
+    pub fn has_alpha(&self) -> bool {
+        matches!(self, DecodedImage::Rgba(_))
+    }
+
+    /// Drops color and alpha.
+    pub fn into_luma(self) -> LumaImage {
+        match self {
+            DecodedImage::Luma(image) => image,
+            DecodedImage::Rgb(image) => LumaImage::from(&image),
+            DecodedImage::Rgba(image) => LumaImage::from(&image),
+        }
+    }
+
+    /// Drops alpha without compositing.
+    pub fn into_rgb(self) -> RgbImage {
+        match self {
+            DecodedImage::Luma(image) => RgbImage::from(&image),
+            DecodedImage::Rgb(image) => image,
+            DecodedImage::Rgba(image) => RgbImage::from(&image),
+        }
+    }
+
+    pub fn into_rgba(self) -> RgbaImage {
+        match self {
+            DecodedImage::Luma(image) => RgbaImage::from(&image),
+            DecodedImage::Rgb(image) => RgbaImage::from(&image),
+            DecodedImage::Rgba(image) => image,
+        }
+    }
+}
@@ -0,0 +1,247 @@
+use std::time::Duration;
+
+use crate::domain::codecs::{decode_error::DecodeError, decoded_image::DecodedImage};
+
+/// A fully composited frame and how long it stays on screen.
+#[derive(Debug, PartialEq, Clone)]
+pub struct TimedFrame {
+    pub image: DecodedImage,
+    pub delay: Duration,
+}
+
+/// How many times the frames of a source are played in total.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LoopCount {
+    Infinite,
+    Finite(u32),
+}
+
+/// Decodes frames one at a time, in presentation order.
+pub trait FrameSource {
+    /// The next frame, or `None` once a single pass over the frames has finished.
+    fn next_frame(&mut self) -> Option<Result<TimedFrame, DecodeError>>;
+
+    /// Starts another pass from the first frame.
+    fn rewind(&mut self) -> Result<(), DecodeError>;
+
+    fn loop_count(&self) -> LoopCount;
+}
+
+impl<S: FrameSource + ?Sized> FrameSource for Box<S> {
+    fn next_frame(&mut self) -> Option<Result<TimedFrame, DecodeError>> {
+        (**self).next_frame()
+    }
+
+    fn rewind(&mut self) -> Result<(), DecodeError> {
+        (**self).rewind()
+    }
+
+    fn loop_count(&self) -> LoopCount {
+        (**self).loop_count()
+    }
+}
+
+impl<S: FrameSource + ?Sized> FrameSource for &mut S {
+    fn next_frame(&mut self) -> Option<Result<TimedFrame, DecodeError>> {
+        (**self).next_frame()
+    }
+
+    fn rewind(&mut self) -> Result<(), DecodeError> {
+        (**self).rewind()
+    }
+
+    fn loop_count(&self) -> LoopCount {
+        (**self).loop_count()
+    }
+}
+
+/// Plays a [`FrameSource`] as many times as its [`LoopCount`] asks for.
+///
+/// Iteration stops after the first error.
+pub struct Playback<S: FrameSource> {
+    source: S,
+    passes: u32,
+    has_frames: bool,
+    ended: bool,
+}
+
+impl<S: FrameSource> Playback<S> {
+    pub fn new(source: S) -> Self {
+        Self {
+            source,
+            passes: 0,
+            has_frames: false,
+            ended: false,
+        }
+    }
+
+    fn fail(&mut self, error: DecodeError) -> Option<Result<TimedFrame, DecodeError>> {
+        self.ended = true;
+        Some(Err(error))
+    }
+}
+
+impl<S: FrameSource> Iterator for Playback<S> {
+    type Item = Result<TimedFrame, DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.ended {
+            match self.source.next_frame() {
+                Some(Ok(frame)) => {
+                    self.has_frames = true;
+                    return Some(Ok(frame));
+                }
+                Some(Err(error)) => return self.fail(error),
+                None => {
+                    self.passes += 1;
+                    // A source without frames would otherwise loop forever.
+                    self.ended = !self.has_frames
+                        || match self.source.loop_count() {
+                            LoopCount::Infinite => false,
+                            LoopCount::Finite(plays) => self.passes >= plays,
+                        };
+
+                    if let (false, Err(error)) = (self.ended, self.source.rewind()) {
+                        return self.fail(error);
+                    }
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// A still image as a source with one frame that is shown once.
+#[derive(Debug)]
+pub(crate) struct SingleFrame {
+    image: DecodedImage,
+    shown: bool,
+}
+
+impl SingleFrame {
+    pub(crate) fn new(image: DecodedImage) -> Self {
+        Self {
+            image,
+            shown: false,
+        }
+    }
+}
+
+impl FrameSource for SingleFrame {
+    fn next_frame(&mut self) -> Option<Result<TimedFrame, DecodeError>> {
+        if self.shown {
+            return None;
+        }
+
+        self.shown = true;
+        Some(Ok(TimedFrame {
+            image: self.image.clone(),
+            delay: Duration::ZERO,
+        }))
+    }
+
+    fn rewind(&mut self) -> Result<(), DecodeError> {
+        self.shown = false;
+        Ok(())
+    }
+
+    fn loop_count(&self) -> LoopCount {
+        LoopCount::Finite(1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::{
+        codecs::{
+            decode_error::DecodeError,
+            decoded_image::DecodedImage,
+            frame_source::{FrameSource, LoopCount, Playback, SingleFrame, TimedFrame},
+        },
+        entities::LumaImage,
+    };
+
+    /// Yields `frames` luma frames whose single pixel is the frame index.
+    struct Counter {
+        frames: u8,
+        next: u8,
+        loop_count: LoopCount,
+    }
+
+    impl FrameSource for Counter {
+        fn next_frame(&mut self) -> Option<Result<TimedFrame, DecodeError>> {
+            (self.next < self.frames).then(|| {
+                self.next += 1;
+                Ok(TimedFrame {
+                    image: DecodedImage::Luma(LumaImage::from_luma_data(1, 1, vec![self.next - 1])),
+                    delay: Duration::from_millis(10),
+                })
+            })
+        }
+
+        fn rewind(&mut self) -> Result<(), DecodeError> {
+            self.next = 0;
+            Ok(())
+        }
+
+        fn loop_count(&self) -> LoopCount {
+            self.loop_count
+        }
+    }
+
+    fn indices(playback: Playback<Counter>) -> Vec<u8> {
+        playback
+            .map(|frame| frame.unwrap().image.into_luma().data()[0])
+            .collect()
+    }
+
+    #[test]
+    fn should_play_frames_loop_count_times() {
+        let source: Counter = Counter {
+            frames: 2,
+            next: 0,
+            loop_count: LoopCount::Finite(2),
+        };
+
+        assert_eq!(indices(Playback::new(source)), vec![0, 1, 0, 1]);
+    }
+
+    #[test]
+    fn should_loop_infinitely_but_stop_on_empty_sources() {
+        let looping: Counter = Counter {
+            frames: 1,
+            next: 0,
+            loop_count: LoopCount::Infinite,
+        };
+        let empty: Counter = Counter {
+            frames: 0,
+            next: 0,
+            loop_count: LoopCount::Infinite,
+        };
+
+        assert_eq!(Playback::new(looping).take(5).count(), 5);
+        assert_eq!(Playback::new(empty).count(), 0);
+    }
+
+    #[test]
+    fn single_frame_should_show_once() {
+        let image: DecodedImage = DecodedImage::Luma(LumaImage::new(1, 1));
+
+        let frames: Vec<TimedFrame> = Playback::new(SingleFrame::new(image.clone()))
+            .collect::<Result<Vec<TimedFrame>, _>>()
+            .unwrap();
+
+        assert_eq!(
+            frames,
+            vec![TimedFrame {
+                image,
+                delay: Duration::ZERO
+            }]
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use std::{io::Cursor, sync::Arc, time::Duration};
+
+use gif::{ColorOutput, DecodeOptions, Decoder, DisposalMethod, Frame, Repeat};
+
+use crate::domain::{
+    codecs::{
+        canvas::{Blend, Canvas, Disposal},
+        decode_error::DecodeError,
+        decoded_image::DecodedImage,
+        frame_source::{FrameSource, LoopCount, TimedFrame},
+        image_format::ImageFormat,
+    },
+    entities::Rect,
+};
+
+/// Decodes the first frame, placed on a transparent canvas the size of the logical screen.
+pub(crate) fn decode(bytes: &[u8]) -> Result<DecodedImage, DecodeError> {
+    GifFrames::new(Arc::from(bytes))?
+        .next_frame()
+        .unwrap_or_else(|| Err(DecodeError::malformed(ImageFormat::Gif, "no image data")))
+        .map(|frame: TimedFrame| frame.image)
+}
+
+/// Frames of a GIF composited onto its logical screen.
+pub(crate) struct GifFrames {
+    bytes: Arc<[u8]>,
+    decoder: Decoder<Cursor<Arc<[u8]>>>,
+    canvas: Canvas,
+}
+
+impl GifFrames {
+    pub(crate) fn new(bytes: Arc<[u8]>) -> Result<Self, DecodeError> {
+        let decoder: Decoder<Cursor<Arc<[u8]>>> = Self::decoder(&bytes)?;
+        let canvas: Canvas = Canvas::new(decoder.width() as usize, decoder.height() as usize);
+
+        Ok(Self {
+            bytes,
+            decoder,
+            canvas,
+        })
+    }
+
+    fn decoder(bytes: &Arc<[u8]>) -> Result<Decoder<Cursor<Arc<[u8]>>>, DecodeError> {
+        let mut options: DecodeOptions = DecodeOptions::new();
+        options.set_color_output(ColorOutput::RGBA);
+
+        options
+            .read_info(Cursor::new(bytes.clone()))
+            .map_err(|error| DecodeError::malformed(ImageFormat::Gif, error))
+    }
+}
+
+impl FrameSource for GifFrames {
+    fn next_frame(&mut self) -> Option<Result<TimedFrame, DecodeError>> {
+        let frame: &Frame = match self.decoder.read_next_frame() {
+            Ok(frame) => frame?,
+            Err(error) => return Some(Err(DecodeError::malformed(ImageFormat::Gif, error))),
+        };
+        let area: Rect = Rect::new(
+            frame.left as usize,
+            frame.top as usize,
+            frame.width as usize,
+            frame.height as usize,
+        );
+        let disposal: Disposal = match frame.dispose {
+            DisposalMethod::Any | DisposalMethod::Keep => Disposal::Keep,
+            // Browsers clear to transparent rather than to the background color.
+            DisposalMethod::Background => Disposal::Clear,
+            DisposalMethod::Previous => Disposal::Restore,
+        };
+        // Like browsers, treat the common 0 and 10 ms delays as 100 ms.
+        let delay: Duration = match frame.delay {
+            0 | 1 => Duration::from_millis(100),
+            centiseconds => Duration::from_millis(centiseconds as u64 * 10),
+        };
+
+        self.canvas.draw(area, &frame.buffer, Blend::Over, disposal);
+
+        Some(Ok(TimedFrame {
+            image: self.canvas.to_image(),
+            delay,
+        }))
+    }
+
+    fn rewind(&mut self) -> Result<(), DecodeError> {
+        self.decoder = Self::decoder(&self.bytes)?;
+        self.canvas.reset();
+        Ok(())
+    }
+
+    /// The NETSCAPE extension counts repeats after the first play; without it a GIF plays once.
+    fn loop_count(&self) -> LoopCount {
+        match self.decoder.repeat() {
+            Repeat::Infinite => LoopCount::Infinite,
+            Repeat::Finite(repeats) => LoopCount::Finite(repeats as u32 + 1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{borrow::Cow, sync::Arc, time::Duration};
+
+    use gif::{DisposalMethod, Encoder, Repeat};
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::{
+        codecs::{
+            decoded_image::DecodedImage,
+            frame_source::{FrameSource, LoopCount, TimedFrame},
+            gif_codec::{GifFrames, decode},
+        },
+        entities::{RgbImage, RgbaImage},
+    };
+
+    /// A 2x1 black and white GIF with one 1x1 white frame per `(left, dispose)`, 50 ms apart.
+    fn gif(frames: &[(u16, DisposalMethod)], repeat: Option<Repeat>) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::new();
+        {
+            let mut encoder: Encoder<&mut Vec<u8>> =
+                Encoder::new(&mut bytes, 2, 1, &[0, 0, 0, 255, 255, 255]).unwrap();
+
+            if let Some(repeat) = repeat {
+                encoder.set_repeat(repeat).unwrap();
+            }
+
+            for (left, dispose) in frames {
+                encoder
+                    .write_frame(&gif::Frame {
+                        left: *left,
+                        width: 1,
+                        height: 1,
+                        delay: 5,
+                        dispose: *dispose,
+                        buffer: Cow::Borrowed(&[1]),
+                        ..gif::Frame::default()
+                    })
+                    .unwrap();
+            }
+        }
+
+        bytes
+    }
+
+    fn frames(source: &mut GifFrames) -> Vec<TimedFrame> {
+        std::iter::from_fn(|| source.next_frame())
+            .collect::<Result<Vec<TimedFrame>, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn should_place_first_frame_on_transparent_screen() {
+        let bytes: Vec<u8> = gif(
+            &[(1, DisposalMethod::Keep), (0, DisposalMethod::Keep)],
+            None,
+        );
+
+        assert_eq!(
+            decode(&bytes).unwrap(),
+            DecodedImage::Rgba(RgbaImage::from_rgba_data(
+                2,
+                1,
+                vec![0, 0, 0, 0, 255, 255, 255, 255]
+            ))
+        );
+    }
+
+    #[test]
+    fn should_composite_frames_and_honor_disposal() {
+        let bytes: Vec<u8> = gif(
+            &[
+                (0, DisposalMethod::Keep),
+                (1, DisposalMethod::Background),
+                (0, DisposalMethod::Keep),
+            ],
+            Some(Repeat::Finite(2)),
+        );
+        let mut source: GifFrames = GifFrames::new(Arc::from(bytes)).unwrap();
+
+        let frames: Vec<TimedFrame> = frames(&mut source);
+
+        assert_eq!(frames.len(), 3);
+        assert_eq!(
+            frames[1].image,
+            DecodedImage::Rgb(RgbImage::from_rgb_data(2, 1, vec![255; 6]))
+        );
+        assert_eq!(
+            frames[2].image,
+            DecodedImage::Rgba(RgbaImage::from_rgba_data(
+                2,
+                1,
+                vec![255, 255, 255, 255, 0, 0, 0, 0]
+            ))
+        );
+        assert_eq!(frames[0].delay, Duration::from_millis(50));
+        assert_eq!(source.loop_count(), LoopCount::Finite(3));
+    }
+
+    #[test]
+    fn should_rewind_to_first_frame() {
+        let bytes: Vec<u8> = gif(
+            &[(1, DisposalMethod::Keep), (0, DisposalMethod::Keep)],
+            Some(Repeat::Infinite),
+        );
+        let mut source: GifFrames = GifFrames::new(Arc::from(bytes)).unwrap();
+        let first: Vec<TimedFrame> = frames(&mut source);
+
+        source.rewind().unwrap();
+
+        assert_eq!(frames(&mut source), first);
+        assert_eq!(source.loop_count(), LoopCount::Infinite);
+    }
+}
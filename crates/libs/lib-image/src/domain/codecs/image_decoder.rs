@@ -0,0 +1,88 @@
+use std::{fs, path::Path, sync::Arc};
+
+use crate::domain::codecs::{
+    decode_error::DecodeError,
+    decoded_image::DecodedImage,
+    frame_source::{FrameSource, SingleFrame},
+    image_format::ImageFormat,
+};
+
+/// Decodes a still image, recognising its format from the leading signature.
+///
+/// Animated formats and Y4M video yield their first frame.
+pub fn decode(bytes: &[u8]) -> Result<DecodedImage, DecodeError> {
+    let format: ImageFormat = ImageFormat::detect(bytes).ok_or(DecodeError::UnknownFormat)?;
+
+    #[allow(unreachable_patterns)]
+    match format {
+        #[cfg(feature = "png")]
+        ImageFormat::Png => super::png_codec::decode(bytes),
+        #[cfg(feature = "jpeg")]
+        ImageFormat::Jpeg => super::jpeg_codec::decode(bytes),
+        #[cfg(feature = "gif")]
+        ImageFormat::Gif => super::gif_codec::decode(bytes),
+        #[cfg(feature = "bmp")]
+        ImageFormat::Bmp => super::bmp_codec::decode(bytes),
+        #[cfg(feature = "webp")]
+        ImageFormat::WebP => super::webp_codec::decode(bytes),
+        #[cfg(feature = "tiff")]
+        ImageFormat::Tiff => super::tiff_codec::decode(bytes),
+        #[cfg(feature = "qoi")]
+        ImageFormat::Qoi => super::qoi_codec::decode(bytes),
+        #[cfg(feature = "pnm")]
+        ImageFormat::Pnm => super::pnm_codec::decode(bytes),
+        #[cfg(feature = "y4m")]
+        ImageFormat::Y4m => super::y4m_codec::decode(bytes),
+        format => Err(DecodeError::UnsupportedFormat(format)),
+    }
+}
+
+pub fn decode_file(path: impl AsRef<Path>) -> Result<DecodedImage, DecodeError> {
+    decode(&fs::read(path)?)
+}
+
+/// Decodes an image as a sequence of frames: every frame of an animated GIF, APNG, WebP or Y4M
+/// stream, or a single frame for a still image.
+pub fn decode_frames(bytes: impl Into<Arc<[u8]>>) -> Result<Box<dyn FrameSource>, DecodeError> {
+    let bytes: Arc<[u8]> = bytes.into();
+
+    match ImageFormat::detect(&bytes) {
+        #[cfg(feature = "gif")]
+        Some(ImageFormat::Gif) => Ok(Box::new(super::gif_codec::GifFrames::new(bytes)?)),
+        #[cfg(feature = "png")]
+        Some(ImageFormat::Png) => Ok(Box::new(super::png_codec::ApngFrames::new(bytes)?)),
+        #[cfg(feature = "webp")]
+        Some(ImageFormat::WebP) => Ok(Box::new(super::webp_codec::WebPFrames::new(bytes)?)),
+        #[cfg(feature = "y4m")]
+        Some(ImageFormat::Y4m) => Ok(Box::new(super::y4m_codec::Y4mReader::new(
+            std::io::Cursor::new(bytes),
+        )?)),
+        _ => Ok(Box::new(SingleFrame::new(decode(&bytes)?))),
+    }
+}
+
+pub fn decode_frames_file(path: impl AsRef<Path>) -> Result<Box<dyn FrameSource>, DecodeError> {
+    decode_frames(fs::read(path)?)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::domain::codecs::{decode_error::DecodeError, image_decoder::decode};
+
+    #[test]
+    fn should_reject_unknown_formats() {
+        assert!(matches!(
+            decode(b"definitely not an image"),
+            Err(DecodeError::UnknownFormat)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "bmp")]
+    fn should_report_malformed_data() {
+        assert!(matches!(
+            decode(b"BM\0\0"),
+            Err(DecodeError::Malformed { .. })
+        ));
+    }
+}
@@ -0,0 +1,149 @@
+use std::{fmt, path::Path};
+
+/// Image and raw video container formats `lib-image` knows how to recognise.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Gif,
+    Bmp,
+    WebP,
+    Tiff,
+    Qoi,
+    Pnm,
+    Y4m,
+}
+
+impl ImageFormat {
+    /// Recognises a format from the signature at the start of `bytes`.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => Some(ImageFormat::Png),
+            [0xFF, 0xD8, 0xFF, ..] => Some(ImageFormat::Jpeg),
+            [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Some(ImageFormat::Gif),
+            [b'B', b'M', ..] => Some(ImageFormat::Bmp),
+            [
+                b'R',
+                b'I',
+                b'F',
+                b'F',
+                _,
+                _,
+                _,
+                _,
+                b'W',
+                b'E',
+                b'B',
+                b'P',
+                ..,
+            ] => Some(ImageFormat::WebP),
+            [b'I', b'I', 0x2A, 0x00, ..] | [b'M', b'M', 0x00, 0x2A, ..] => Some(ImageFormat::Tiff),
+            [b'q', b'o', b'i', b'f', ..] => Some(ImageFormat::Qoi),
+            [b'P', b'1'..=b'7', b' ' | b'\t' | b'\n' | b'\r', ..] => Some(ImageFormat::Pnm),
+            [b'Y', b'U', b'V', b'4', b'M', b'P', b'E', b'G', b'2', ..] => Some(ImageFormat::Y4m),
+            _ => None,
+        }
+    }
+
+    /// Guesses a format from a file extension, ignoring case.
+    pub fn from_path(path: impl AsRef<Path>) -> Option<Self> {
+        let extension: String = path.as_ref().extension()?.to_str()?.to_ascii_lowercase();
+
+        match extension.as_str() {
+            "png" | "apng" => Some(ImageFormat::Png),
+            "jpg" | "jpeg" | "jpe" | "jfif" => Some(ImageFormat::Jpeg),
+            "gif" => Some(ImageFormat::Gif),
+            "bmp" | "dib" => Some(ImageFormat::Bmp),
+            "webp" => Some(ImageFormat::WebP),
+            "tif" | "tiff" => Some(ImageFormat::Tiff),
+            "qoi" => Some(ImageFormat::Qoi),
+            "pbm" | "pgm" | "ppm" | "pnm" | "pam" => Some(ImageFormat::Pnm),
+            "y4m" => Some(ImageFormat::Y4m),
+            _ => None,
+        }
+    }
+
+    /// Name of the cargo feature that enables decoding this format.
+    pub fn feature(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Jpeg => "jpeg",
+            ImageFormat::Gif => "gif",
+            ImageFormat::Bmp => "bmp",
+            ImageFormat::WebP => "webp",
+            ImageFormat::Tiff => "tiff",
+            ImageFormat::Qoi => "qoi",
+            ImageFormat::Pnm => "pnm",
+            ImageFormat::Y4m => "y4m",
+        }
+    }
+
+    /// Whether this build of `lib-image` can decode the format.
+    pub fn is_enabled(&self) -> bool {
+        match self {
+            ImageFormat::Png => cfg!(feature = "png"),
+            ImageFormat::Jpeg => cfg!(feature = "jpeg"),
+            ImageFormat::Gif => cfg!(feature = "gif"),
+            ImageFormat::Bmp => cfg!(feature = "bmp"),
+            ImageFormat::WebP => cfg!(feature = "webp"),
+            ImageFormat::Tiff => cfg!(feature = "tiff"),
+            ImageFormat::Qoi => cfg!(feature = "qoi"),
+            ImageFormat::Pnm => cfg!(feature = "pnm"),
+            ImageFormat::Y4m => cfg!(feature = "y4m"),
+        }
+    }
+}
+
+impl fmt::Display for ImageFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name: &str = match self {
+            ImageFormat::Png => "PNG",
+            ImageFormat::Jpeg => "JPEG",
+            ImageFormat::Gif => "GIF",
+            ImageFormat::Bmp => "BMP",
+            ImageFormat::WebP => "WebP",
+            ImageFormat::Tiff => "TIFF",
+            ImageFormat::Qoi => "QOI",
+            ImageFormat::Pnm => "Netpbm",
+            ImageFormat::Y4m => "Y4M",
+        };
+
+        write!(f, "{name}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::codecs::image_format::ImageFormat;
+
+    #[test]
+    fn should_detect_formats_by_signature() {
+        let signatures: Vec<(&[u8], Option<ImageFormat>)> = vec![
+            (b"\x89PNG\r\n\x1a\n....", Some(ImageFormat::Png)),
+            (b"\xff\xd8\xff\xe0", Some(ImageFormat::Jpeg)),
+            (b"GIF89a", Some(ImageFormat::Gif)),
+            (b"BM....", Some(ImageFormat::Bmp)),
+            (b"RIFF\0\0\0\0WEBPVP8 ", Some(ImageFormat::WebP)),
+            (b"RIFF\0\0\0\0WAVE", None),
+            (b"MM\0*", Some(ImageFormat::Tiff)),
+            (b"qoif", Some(ImageFormat::Qoi)),
+            (b"P6\n1 1\n255\n", Some(ImageFormat::Pnm)),
+            (b"PK\x03\x04", None),
+            (b"YUV4MPEG2 W2 H2", Some(ImageFormat::Y4m)),
+            (b"\0\0\0\x18ftypmp4", None),
+        ];
+
+        for (bytes, expected) in signatures {
+            assert_eq!(ImageFormat::detect(bytes), expected, "{bytes:?}");
+        }
+    }
+
+    #[test]
+    fn should_guess_formats_from_extensions() {
+        assert_eq!(ImageFormat::from_path("logo.PNG"), Some(ImageFormat::Png));
+        assert_eq!(ImageFormat::from_path("a/b.jpeg"), Some(ImageFormat::Jpeg));
+        assert_eq!(ImageFormat::from_path("clip.mp4"), None);
+    }
+}
@@ -0,0 +1,40 @@
+use jpeg_decoder::{Decoder, ImageInfo, PixelFormat};
+
+use crate::domain::{
+    codecs::{decode_error::DecodeError, decoded_image::DecodedImage, image_format::ImageFormat},
+    entities::{LumaImage, RgbImage},
+};
+
+pub(crate) fn decode(bytes: &[u8]) -> Result<DecodedImage, DecodeError> {
+    let mut decoder: Decoder<&[u8]> = Decoder::new(bytes);
+    let data: Vec<u8> = decoder
+        .decode()
+        .map_err(|error| DecodeError::malformed(ImageFormat::Jpeg, error))?;
+    let info: ImageInfo = decoder
+        .info()
+        .ok_or_else(|| DecodeError::malformed(ImageFormat::Jpeg, "missing frame header"))?;
+    let (width, height): (usize, usize) = (info.width as usize, info.height as usize);
+
+    Ok(match info.pixel_format {
+        PixelFormat::L8 => DecodedImage::Luma(LumaImage::from_luma_data(width, height, data)),
+        PixelFormat::L16 => DecodedImage::Luma(LumaImage::from_luma_data(
+            width,
+            height,
+            data.chunks_exact(2)
+                .map(|s: &[u8]| (u16::from_ne_bytes([s[0], s[1]]) >> 8) as u8)
+                .collect(),
+        )),
+        PixelFormat::RGB24 => DecodedImage::Rgb(RgbImage::from_rgb_data(width, height, data)),
+        PixelFormat::CMYK32 => DecodedImage::Rgb(RgbImage::from_rgb_data(
+            width,
+            height,
+            data.chunks_exact(4)
+                .flat_map(|cmyk: &[u8]| {
+                    let k: u32 = 255 - cmyk[3] as u32;
+
+                    [0, 1, 2].map(|c| ((255 - cmyk[c] as u32) * k / 255) as u8)
+                })
+                .collect(),
+        )),
+    })
+}
@@ -0,0 +1,261 @@
+use std::{io::Cursor, sync::Arc, time::Duration};
+
+use png::{
+    AnimationControl, BlendOp, ColorType, Decoder, DisposeOp, FrameControl, OutputInfo, Reader,
+    Transformations,
+};
+
+use crate::domain::{
+    codecs::{
+        canvas::{Blend, Canvas, Disposal},
+        decode_error::DecodeError,
+        decoded_image::DecodedImage,
+        frame_source::{FrameSource, LoopCount, TimedFrame},
+        image_format::ImageFormat,
+    },
+    entities::Rect,
+};
+
+/// Decodes the default image of a PNG, which for an APNG may not be part of the animation.
+pub(crate) fn decode(bytes: &[u8]) -> Result<DecodedImage, DecodeError> {
+    read_image(&mut reader(Cursor::new(bytes))?)
+}
+
+fn reader<R>(source: R) -> Result<Reader<R>, DecodeError>
+where
+    R: std::io::BufRead + std::io::Seek,
+{
+    let mut decoder: Decoder<R> = Decoder::new(source);
+    decoder.set_transformations(Transformations::normalize_to_color8());
+
+    decoder.read_info().map_err(malformed)
+}
+
+/// Decodes the next image or animation frame at its own size.
+fn read_image<R>(reader: &mut Reader<R>) -> Result<DecodedImage, DecodeError>
+where
+    R: std::io::BufRead + std::io::Seek,
+{
+    let size: usize = reader
+        .output_buffer_size()
+        .ok_or_else(|| malformed(png::DecodingError::LimitsExceeded))?;
+    let mut data: Vec<u8> = vec![0; size];
+    let info: OutputInfo = reader.next_frame(&mut data).map_err(malformed)?;
+    data.truncate(info.buffer_size());
+
+    let channels: usize = match info.color_type {
+        ColorType::Grayscale => 1,
+        ColorType::GrayscaleAlpha => 2,
+        ColorType::Rgb => 3,
+        ColorType::Rgba => 4,
+        ColorType::Indexed => 0,
+    };
+
+    DecodedImage::from_samples(info.width as usize, info.height as usize, channels, data)
+        .ok_or_else(|| DecodeError::malformed(ImageFormat::Png, "unexpanded palette output"))
+}
+
+fn malformed(error: png::DecodingError) -> DecodeError {
+    DecodeError::malformed(ImageFormat::Png, error)
+}
+
+/// Frames of an APNG composited onto its canvas, or a plain PNG as a single frame.
+pub(crate) struct ApngFrames {
+    bytes: Arc<[u8]>,
+    reader: Reader<Cursor<Arc<[u8]>>>,
+    canvas: Canvas,
+    remaining: u32,
+}
+
+impl ApngFrames {
+    pub(crate) fn new(bytes: Arc<[u8]>) -> Result<Self, DecodeError> {
+        let reader: Reader<Cursor<Arc<[u8]>>> = reader(Cursor::new(bytes.clone()))?;
+        let (width, height): (u32, u32) = reader.info().size();
+        let mut frames: Self = Self {
+            bytes,
+            reader,
+            canvas: Canvas::new(width as usize, height as usize),
+            remaining: 0,
+        };
+
+        frames.start()?;
+        Ok(frames)
+    }
+
+    fn animation_control(&self) -> Option<AnimationControl> {
+        self.reader.info().animation_control
+    }
+
+    fn start(&mut self) -> Result<(), DecodeError> {
+        self.remaining = match self.animation_control() {
+            Some(animation) => {
+                // Without an `fcTL` before it, the default image is not part of the animation.
+                if self.reader.info().frame_control.is_none() {
+                    read_image(&mut self.reader)?;
+                }
+
+                animation.num_frames
+            }
+            None => 1,
+        };
+
+        Ok(())
+    }
+}
+
+impl FrameSource for ApngFrames {
+    fn next_frame(&mut self) -> Option<Result<TimedFrame, DecodeError>> {
+        if self.remaining == 0 {
+            return None;
+        }
+        self.remaining -= 1;
+
+        let image: DecodedImage = match read_image(&mut self.reader) {
+            Ok(image) => image,
+            Err(error) => return Some(Err(error)),
+        };
+        let control: FrameControl = match self.reader.info().frame_control {
+            Some(control) if self.animation_control().is_some() => control,
+            _ => {
+                return Some(Ok(TimedFrame {
+                    image,
+                    delay: Duration::ZERO,
+                }));
+            }
+        };
+
+        let area: Rect = Rect::new(
+            control.x_offset as usize,
+            control.y_offset as usize,
+            control.width as usize,
+            control.height as usize,
+        );
+        let blend: Blend = match control.blend_op {
+            BlendOp::Source => Blend::Source,
+            BlendOp::Over => Blend::Over,
+        };
+        let disposal: Disposal = match control.dispose_op {
+            DisposeOp::None => Disposal::Keep,
+            DisposeOp::Background => Disposal::Clear,
+            DisposeOp::Previous => Disposal::Restore,
+        };
+        // A zero denominator means hundredths of a second.
+        let denominator: u64 = match control.delay_den {
+            0 => 100,
+            denominator => denominator as u64,
+        };
+
+        self.canvas
+            .draw(area, &image.into_rgba().data(), blend, disposal);
+
+        Some(Ok(TimedFrame {
+            image: self.canvas.to_image(),
+            delay: Duration::from_millis(control.delay_num as u64 * 1000 / denominator),
+        }))
+    }
+
+    fn rewind(&mut self) -> Result<(), DecodeError> {
+        self.reader = reader(Cursor::new(self.bytes.clone()))?;
+        self.canvas.reset();
+        self.start()
+    }
+
+    /// `num_plays` of zero means forever.
+    fn loop_count(&self) -> LoopCount {
+        match self.animation_control() {
+            Some(AnimationControl { num_plays: 0, .. }) => LoopCount::Infinite,
+            Some(animation) => LoopCount::Finite(animation.num_plays),
+            None => LoopCount::Finite(1),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{sync::Arc, time::Duration};
+
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::{
+        codecs::{
+            decoded_image::DecodedImage,
+            frame_source::{FrameSource, LoopCount, TimedFrame},
+            png_codec::{ApngFrames, decode},
+        },
+        entities::{RgbImage, RgbaImage},
+    };
+
+    fn encode(width: u32, height: u32, color: png::ColorType, data: &[u8]) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::new();
+        let mut encoder: png::Encoder<&mut Vec<u8>> = png::Encoder::new(&mut bytes, width, height);
+        encoder.set_color(color);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .unwrap()
+            .write_image_data(data)
+            .unwrap();
+
+        bytes
+    }
+
+    #[test]
+    fn should_decode_rgb_png() {
+        let bytes: Vec<u8> = encode(2, 1, png::ColorType::Rgb, &[255, 0, 0, 0, 0, 255]);
+
+        assert_eq!(
+            decode(&bytes).unwrap(),
+            DecodedImage::Rgb(RgbImage::from_rgb_data(2, 1, vec![255, 0, 0, 0, 0, 255]))
+        );
+    }
+
+    #[test]
+    fn should_expand_gray_alpha_png_to_rgba() {
+        let bytes: Vec<u8> = encode(1, 1, png::ColorType::GrayscaleAlpha, &[100, 50]);
+
+        assert_eq!(
+            decode(&bytes).unwrap(),
+            DecodedImage::Rgba(RgbaImage::from_rgba_data(1, 1, vec![100, 100, 100, 50]))
+        );
+    }
+
+    #[test]
+    fn should_composite_apng_frames() {
+        let mut bytes: Vec<u8> = Vec::new();
+        {
+            let mut encoder: png::Encoder<&mut Vec<u8>> = png::Encoder::new(&mut bytes, 2, 1);
+            encoder.set_color(png::ColorType::Rgba);
+            encoder.set_animated(2, 3).unwrap();
+            encoder.set_frame_delay(1, 20).unwrap();
+
+            let mut writer = encoder.write_header().unwrap();
+            writer
+                .write_image_data(&[255, 0, 0, 255, 0, 0, 0, 0])
+                .unwrap();
+            writer.set_frame_dimension(1, 1).unwrap();
+            writer.set_frame_position(1, 0).unwrap();
+            writer.set_blend_op(png::BlendOp::Over).unwrap();
+            writer.write_image_data(&[0, 0, 255, 255]).unwrap();
+        }
+        let mut source: ApngFrames = ApngFrames::new(Arc::from(bytes)).unwrap();
+
+        let frames: Vec<TimedFrame> = std::iter::from_fn(|| source.next_frame())
+            .collect::<Result<Vec<TimedFrame>, _>>()
+            .unwrap();
+
+        assert_eq!(
+            frames[0].image,
+            DecodedImage::Rgba(RgbaImage::from_rgba_data(
+                2,
+                1,
+                vec![255, 0, 0, 255, 0, 0, 0, 0]
+            ))
+        );
+        assert_eq!(
+            frames[1].image,
+            DecodedImage::Rgb(RgbImage::from_rgb_data(2, 1, vec![255, 0, 0, 0, 0, 255]))
+        );
+        assert_eq!(frames[1].delay, Duration::from_millis(50));
+        assert_eq!(source.loop_count(), LoopCount::Finite(3));
+    }
+}
@@ -0,0 +1,447 @@
+use crate::domain::codecs::{
+    decode_error::DecodeError, decoded_image::DecodedImage, image_format::ImageFormat,
+};
+
+/// A Netpbm variant, named after its magic number.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum PnmFormat {
+    /// ASCII bitmap (PBM).
+    P1,
+    /// ASCII graymap (PGM).
+    P2,
+    /// ASCII pixmap (PPM).
+    P3,
+    /// Binary bitmap (PBM).
+    P4,
+    /// Binary graymap (PGM).
+    P5,
+    /// Binary pixmap (PPM).
+    P6,
+    /// Arbitrary map (PAM), which keeps alpha.
+    P7,
+}
+
+impl PnmFormat {
+    fn from_magic(magic: u8) -> Option<Self> {
+        match magic {
+            b'1' => Some(PnmFormat::P1),
+            b'2' => Some(PnmFormat::P2),
+            b'3' => Some(PnmFormat::P3),
+            b'4' => Some(PnmFormat::P4),
+            b'5' => Some(PnmFormat::P5),
+            b'6' => Some(PnmFormat::P6),
+            b'7' => Some(PnmFormat::P7),
+            _ => None,
+        }
+    }
+
+    fn is_ascii(self) -> bool {
+        matches!(self, PnmFormat::P1 | PnmFormat::P2 | PnmFormat::P3)
+    }
+}
+
+/// Decodes the first image of a PBM, PGM, PPM or PAM file, scaling samples from its maxval to
+/// 8 bits.
+pub(crate) fn decode(bytes: &[u8]) -> Result<DecodedImage, DecodeError> {
+    let format: PnmFormat = match bytes {
+        [b'P', magic, ..] => PnmFormat::from_magic(*magic),
+        _ => None,
+    }
+    .ok_or_else(|| malformed("unknown magic number"))?;
+    let mut reader: Reader = Reader { bytes, offset: 2 };
+
+    let (width, height, channels, maxval): (usize, usize, usize, u32) = match format {
+        PnmFormat::P7 => reader.pam_header()?,
+        _ => {
+            let width: usize = reader.number()? as usize;
+            let height: usize = reader.number()? as usize;
+            let (channels, maxval): (usize, u32) = match format {
+                PnmFormat::P1 | PnmFormat::P4 => (1, 1),
+                PnmFormat::P2 | PnmFormat::P5 => (1, reader.number()?),
+                _ => (3, reader.number()?),
+            };
+
+            if !format.is_ascii() {
+                reader.single_whitespace()?;
+            }
+            (width, height, channels, maxval)
+        }
+    };
+
+    if width == 0 || height == 0 {
+        return Err(malformed("empty image"));
+    }
+    if !(1..=65535).contains(&maxval) {
+        return Err(malformed(format!("invalid maxval {maxval}")));
+    }
+
+    let count: usize = width
+        .checked_mul(height)
+        .and_then(|pixels: usize| pixels.checked_mul(channels))
+        .ok_or_else(|| malformed("image too large"))?;
+    let samples: Vec<u32> = match format {
+        PnmFormat::P1 => (0..count).map(|_| reader.bit()).collect::<Result<_, _>>()?,
+        PnmFormat::P2 | PnmFormat::P3 => (0..count)
+            .map(|_| reader.number())
+            .collect::<Result<_, _>>()?,
+        PnmFormat::P4 => reader.packed_bits(width, height)?,
+        _ => reader.binary_samples(count, maxval)?,
+    };
+    let data: Vec<u8> = samples
+        .into_iter()
+        .map(|sample: u32| match format {
+            // In PBM files 1 is black.
+            PnmFormat::P1 | PnmFormat::P4 => (1 - sample.min(1)) as u8 * 255,
+            _ => ((sample.min(maxval) * 255 + maxval / 2) / maxval) as u8,
+        })
+        .collect();
+
+    DecodedImage::from_samples(width, height, channels, data)
+        .ok_or_else(|| malformed(format!("unsupported depth {channels}")))
+}
+
+/// Encodes `image` with 8-bit samples.
+///
+/// Bitmaps threshold luma at mid-gray, graymaps drop color, pixmaps drop alpha, and PAM keeps
+/// the image's own channels.
+pub fn encode_pnm(image: &DecodedImage, format: PnmFormat) -> Vec<u8> {
+    let (width, height): (usize, usize) = (image.width(), image.height());
+    let (channels, samples): (usize, Vec<u8>) = match (format, image) {
+        (PnmFormat::P1 | PnmFormat::P4, image) => (
+            1,
+            image
+                .clone()
+                .into_luma()
+                .data()
+                .iter()
+                .map(|luma: &u8| (*luma < 128) as u8)
+                .collect(),
+        ),
+        (PnmFormat::P2 | PnmFormat::P5, image) | (PnmFormat::P7, image @ DecodedImage::Luma(_)) => {
+            (1, image.clone().into_luma().data().into_owned())
+        }
+        (PnmFormat::P7, DecodedImage::Rgba(rgba)) => (4, rgba.data().into_owned()),
+        (_, image) => (3, image.clone().into_rgb().data().into_owned()),
+    };
+
+    let mut bytes: Vec<u8> = match format {
+        PnmFormat::P7 => {
+            let tuple_type: &str = match channels {
+                1 => "GRAYSCALE",
+                3 => "RGB",
+                _ => "RGB_ALPHA",
+            };
+
+            format!(
+                "P7\nWIDTH {width}\nHEIGHT {height}\nDEPTH {channels}\nMAXVAL 255\n\
+                 TUPLTYPE {tuple_type}\nENDHDR\n"
+            )
+        }
+        PnmFormat::P1 | PnmFormat::P4 => format!("{format:?}\n{width} {height}\n"),
+        _ => format!("{format:?}\n{width} {height}\n255\n"),
+    }
+    .into_bytes();
+
+    match format {
+        PnmFormat::P4 => {
+            for row in samples.chunks_exact(width.max(1)) {
+                bytes.extend(row.chunks(8).map(|bits: &[u8]| {
+                    bits.iter()
+                        .enumerate()
+                        .fold(0u8, |byte, (i, bit)| byte | bit << (7 - i))
+                }));
+            }
+        }
+        // Netpbm asks for ASCII lines of at most 70 characters.
+        _ if format.is_ascii() => {
+            let per_line: usize = if format == PnmFormat::P1 { 35 } else { 17 };
+
+            for line in samples.chunks(per_line) {
+                bytes.extend(
+                    line.iter()
+                        .map(u8::to_string)
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                        .bytes(),
+                );
+                bytes.push(b'\n');
+            }
+        }
+        _ => bytes.extend(samples),
+    }
+
+    bytes
+}
+
+fn malformed(message: impl std::fmt::Display) -> DecodeError {
+    DecodeError::malformed(ImageFormat::Pnm, message)
+}
+
+/// Reads header tokens and samples from a Netpbm file.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    /// Skips whitespace and `#` comments, which run to the end of the line.
+    fn skip_whitespace(&mut self) {
+        while let Some(byte) = self.bytes.get(self.offset) {
+            match byte {
+                b'#' => {
+                    while self
+                        .bytes
+                        .get(self.offset)
+                        .is_some_and(|b: &u8| *b != b'\n')
+                    {
+                        self.offset += 1;
+                    }
+                }
+                byte if byte.is_ascii_whitespace() => self.offset += 1,
+                _ => break,
+            }
+        }
+    }
+
+    fn token(&mut self) -> Result<&'a [u8], DecodeError> {
+        self.skip_whitespace();
+        let start: usize = self.offset;
+
+        while self
+            .bytes
+            .get(self.offset)
+            .is_some_and(|b: &u8| !b.is_ascii_whitespace())
+        {
+            self.offset += 1;
+        }
+
+        match self.offset > start {
+            true => Ok(&self.bytes[start..self.offset]),
+            false => Err(malformed("truncated data")),
+        }
+    }
+
+    fn number(&mut self) -> Result<u32, DecodeError> {
+        let token: &[u8] = self.token()?;
+
+        std::str::from_utf8(token)
+            .ok()
+            .and_then(|token: &str| token.parse().ok())
+            .ok_or_else(|| malformed(format!("invalid number '{}'", token.escape_ascii())))
+    }
+
+    /// A P1 pixel, which needs no whitespace around it.
+    fn bit(&mut self) -> Result<u32, DecodeError> {
+        self.skip_whitespace();
+        let bit: Option<u32> = match self.bytes.get(self.offset) {
+            Some(b'0') => Some(0),
+            Some(b'1') => Some(1),
+            _ => None,
+        };
+        self.offset += 1;
+
+        bit.ok_or_else(|| malformed("invalid bitmap pixel"))
+    }
+
+    /// The single whitespace character separating a binary header from the raster.
+    fn single_whitespace(&mut self) -> Result<(), DecodeError> {
+        match self.bytes.get(self.offset) {
+            Some(byte) if byte.is_ascii_whitespace() => {
+                self.offset += 1;
+                Ok(())
+            }
+            _ => Err(malformed("missing whitespace before raster")),
+        }
+    }
+
+    fn raster(&mut self, size: usize) -> Result<&'a [u8], DecodeError> {
+        let raster: &[u8] = self
+            .bytes
+            .get(self.offset..self.offset.saturating_add(size))
+            .ok_or_else(|| malformed("truncated raster"))?;
+        self.offset += size;
+
+        Ok(raster)
+    }
+
+    /// P4 rows, eight pixels per byte from the most significant bit, each row padded to a byte.
+    fn packed_bits(&mut self, width: usize, height: usize) -> Result<Vec<u32>, DecodeError> {
+        let row_size: usize = width.div_ceil(8);
+        let raster: &[u8] = self.raster(row_size * height)?;
+
+        Ok(raster
+            .chunks_exact(row_size)
+            .flat_map(|row: &[u8]| (0..width).map(|x| (row[x / 8] >> (7 - x % 8) & 1) as u32))
+            .collect())
+    }
+
+    /// Samples of one byte, or two big-endian bytes when maxval exceeds 255.
+    fn binary_samples(&mut self, count: usize, maxval: u32) -> Result<Vec<u32>, DecodeError> {
+        match maxval {
+            0..=255 => Ok(self.raster(count)?.iter().map(|b: &u8| *b as u32).collect()),
+            _ => Ok(self
+                .raster(
+                    count
+                        .checked_mul(2)
+                        .ok_or_else(|| malformed("image too large"))?,
+                )?
+                .chunks_exact(2)
+                .map(|b: &[u8]| u16::from_be_bytes([b[0], b[1]]) as u32)
+                .collect()),
+        }
+    }
+
+    /// Reads PAM header lines up to `ENDHDR`, returning width, height, depth and maxval.
+    fn pam_header(&mut self) -> Result<(usize, usize, usize, u32), DecodeError> {
+        let (mut width, mut height, mut depth, mut maxval): (u32, u32, u32, u32) = (0, 0, 0, 0);
+
+        loop {
+            match self.token()? {
+                b"WIDTH" => width = self.number()?,
+                b"HEIGHT" => height = self.number()?,
+                b"DEPTH" => depth = self.number()?,
+                b"MAXVAL" => maxval = self.number()?,
+                // The depth already says how many channels there are.
+                b"TUPLTYPE" => {
+                    self.token()?;
+                }
+                b"ENDHDR" => break,
+                token => {
+                    return Err(malformed(format!(
+                        "unknown header field '{}'",
+                        token.escape_ascii()
+                    )));
+                }
+            }
+        }
+
+        // ENDHDR ends its line; the raster starts on the next one.
+        self.single_whitespace()?;
+
+        Ok((width as usize, height as usize, depth as usize, maxval))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::{
+        codecs::{
+            decode_error::DecodeError,
+            decoded_image::DecodedImage,
+            pnm_codec::{PnmFormat, decode, encode_pnm},
+        },
+        entities::{LumaImage, RgbImage, RgbaImage},
+    };
+
+    #[test]
+    fn should_decode_ascii_bitmaps_with_comments() {
+        let bytes: &[u8] = b"P1\n# a comment\n3 2\n010\n1 0 1\n";
+
+        assert_eq!(
+            decode(bytes).unwrap(),
+            DecodedImage::Luma(LumaImage::from_luma_data(
+                3,
+                2,
+                vec![255, 0, 255, 0, 255, 0]
+            ))
+        );
+    }
+
+    #[test]
+    fn should_decode_padded_binary_bitmaps() {
+        // Ten pixels per row take two bytes: 1000000001 plus six padding bits.
+        let bytes: Vec<u8> = [&b"P4 10 1\n"[..], &[0b1000_0000, 0b0100_0000]].concat();
+
+        let luma: Vec<u8> = decode(&bytes).unwrap().into_luma().data().into_owned();
+
+        assert_eq!(luma, vec![0, 255, 255, 255, 255, 255, 255, 255, 255, 0]);
+    }
+
+    #[test]
+    fn should_scale_samples_by_maxval() {
+        let ascii: &[u8] = b"P2 3 1 15\n0 7 15\n";
+        let wide: Vec<u8> = [&b"P5 2 1 65535\n"[..], &[0x80, 0x00, 0xFF, 0xFF]].concat();
+
+        assert_eq!(
+            decode(ascii).unwrap(),
+            DecodedImage::Luma(LumaImage::from_luma_data(3, 1, vec![0, 119, 255]))
+        );
+        assert_eq!(
+            decode(&wide).unwrap(),
+            DecodedImage::Luma(LumaImage::from_luma_data(2, 1, vec![128, 255]))
+        );
+    }
+
+    #[test]
+    fn should_round_trip_every_variant() {
+        let rgb: RgbImage = RgbImage::from_rgb_data(2, 1, vec![255, 255, 255, 10, 20, 30]);
+        let rgba: RgbaImage = RgbaImage::from_rgba_data(1, 1, vec![1, 2, 3, 4]);
+        let gray: LumaImage = LumaImage::from(&rgb);
+        let bits: LumaImage = LumaImage::from_luma_data(2, 1, vec![255, 0]);
+
+        let cases: Vec<(DecodedImage, PnmFormat, DecodedImage)> = vec![
+            (
+                DecodedImage::Rgb(rgb.clone()),
+                PnmFormat::P1,
+                DecodedImage::Luma(bits.clone()),
+            ),
+            (
+                DecodedImage::Rgb(rgb.clone()),
+                PnmFormat::P4,
+                DecodedImage::Luma(bits),
+            ),
+            (
+                DecodedImage::Rgb(rgb.clone()),
+                PnmFormat::P2,
+                DecodedImage::Luma(gray.clone()),
+            ),
+            (
+                DecodedImage::Rgb(rgb.clone()),
+                PnmFormat::P5,
+                DecodedImage::Luma(gray.clone()),
+            ),
+            (
+                DecodedImage::Rgb(rgb.clone()),
+                PnmFormat::P3,
+                DecodedImage::Rgb(rgb.clone()),
+            ),
+            (
+                DecodedImage::Rgba(rgba.clone()),
+                PnmFormat::P6,
+                DecodedImage::Rgb(RgbImage::from(&rgba)),
+            ),
+            (
+                DecodedImage::Luma(gray.clone()),
+                PnmFormat::P7,
+                DecodedImage::Luma(gray),
+            ),
+            (
+                DecodedImage::Rgba(rgba.clone()),
+                PnmFormat::P7,
+                DecodedImage::Rgba(rgba),
+            ),
+        ];
+
+        for (image, format, expected) in cases {
+            assert_eq!(
+                decode(&encode_pnm(&image, format)).unwrap(),
+                expected,
+                "{format:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn should_report_truncated_rasters() {
+        assert!(matches!(
+            decode(b"P6 2 2 255\n\x01\x02\x03"),
+            Err(DecodeError::Malformed { .. })
+        ));
+        assert!(matches!(
+            decode(b"P3 1 1 255\n1 2"),
+            Err(DecodeError::Malformed { .. })
+        ));
+    }
+}
@@ -0,0 +1,39 @@
+use qoi::Header;
+
+use crate::domain::codecs::{
+    decode_error::DecodeError, decoded_image::DecodedImage, image_format::ImageFormat,
+};
+
+pub(crate) fn decode(bytes: &[u8]) -> Result<DecodedImage, DecodeError> {
+    let (header, data): (Header, Vec<u8>) = qoi::decode_to_vec(bytes)
+        .map_err(|error| DecodeError::malformed(ImageFormat::Qoi, error))?;
+
+    DecodedImage::from_samples(
+        header.width as usize,
+        header.height as usize,
+        header.channels.as_u8() as usize,
+        data,
+    )
+    .ok_or_else(|| DecodeError::malformed(ImageFormat::Qoi, "invalid channel count"))
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::{
+        codecs::{decoded_image::DecodedImage, qoi_codec::decode},
+        entities::RgbaImage,
+    };
+
+    #[test]
+    fn should_decode_rgba_qoi() {
+        let pixels: Vec<u8> = vec![10, 20, 30, 255, 10, 20, 30, 0];
+        let bytes: Vec<u8> = qoi::encode_to_vec(&pixels, 2, 1).unwrap();
+
+        assert_eq!(
+            decode(&bytes).unwrap(),
+            DecodedImage::Rgba(RgbaImage::from_rgba_data(2, 1, pixels))
+        );
+    }
+}
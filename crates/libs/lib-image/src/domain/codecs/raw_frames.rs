@@ -0,0 +1,225 @@
+use std::{
+    io::{self, Read},
+    time::Duration,
+};
+
+use crate::domain::codecs::{
+    decode_error::DecodeError,
+    decoded_image::DecodedImage,
+    frame_source::{FrameSource, LoopCount, TimedFrame},
+};
+
+/// Pixel layout of headerless frames, matching ffmpeg's `gray`, `rgb24` and `rgba`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum RawPixelFormat {
+    Gray8,
+    Rgb24,
+    Rgba,
+}
+
+impl RawPixelFormat {
+    pub fn bytes_per_pixel(self) -> usize {
+        match self {
+            RawPixelFormat::Gray8 => 1,
+            RawPixelFormat::Rgb24 => 3,
+            RawPixelFormat::Rgba => 4,
+        }
+    }
+}
+
+/// Fixed-size headerless frames read back to back from a stream such as stdin or a FIFO.
+///
+/// Each frame is returned as soon as all of its bytes have arrived, however many reads that
+/// takes. The stream ends cleanly at a frame boundary; ending inside a frame is an error. Streams
+/// play once and cannot be rewound.
+pub struct RawFrames<R: Read> {
+    reader: R,
+    format: RawPixelFormat,
+    width: usize,
+    height: usize,
+    delay: Duration,
+    ended: bool,
+}
+
+impl<R: Read> RawFrames<R> {
+    /// Frames are returned with no delay, to be shown as they arrive.
+    pub fn new(reader: R, format: RawPixelFormat, width: usize, height: usize) -> Self {
+        Self {
+            reader,
+            format,
+            width,
+            height,
+            delay: Duration::ZERO,
+            ended: false,
+        }
+    }
+
+    /// Paces frames at a fixed delay instead, for streams that arrive faster than real time.
+    pub fn with_delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    fn frame_size(&self) -> Option<usize> {
+        self.width
+            .checked_mul(self.height)?
+            .checked_mul(self.format.bytes_per_pixel())
+    }
+
+    /// Reads until `buffer` is full or the stream ends, returning how many bytes were read.
+    fn fill(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        let mut filled: usize = 0;
+
+        while filled < buffer.len() {
+            match self.reader.read(&mut buffer[filled..]) {
+                Ok(0) => break,
+                Ok(read) => filled += read,
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(filled)
+    }
+
+    fn read_frame(&mut self, size: usize) -> Option<Result<TimedFrame, DecodeError>> {
+        let mut data: Vec<u8> = vec![0; size];
+
+        match self.fill(&mut data) {
+            Ok(0) => {
+                self.ended = true;
+                None
+            }
+            Ok(filled) if filled < size => {
+                self.ended = true;
+                Some(Err(DecodeError::Io(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    format!("stream ended {filled} bytes into a {size}-byte frame"),
+                ))))
+            }
+            Ok(_) => Some(Ok(TimedFrame {
+                image: DecodedImage::from_samples(
+                    self.width,
+                    self.height,
+                    self.format.bytes_per_pixel(),
+                    data,
+                )?,
+                delay: self.delay,
+            })),
+            Err(error) => {
+                self.ended = true;
+                Some(Err(DecodeError::Io(error)))
+            }
+        }
+    }
+}
+
+impl<R: Read> FrameSource for RawFrames<R> {
+    fn next_frame(&mut self) -> Option<Result<TimedFrame, DecodeError>> {
+        if self.ended {
+            return None;
+        }
+
+        match self.frame_size() {
+            Some(0) => None,
+            Some(size) => self.read_frame(size),
+            None => {
+                self.ended = true;
+                Some(Err(DecodeError::Io(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "raw frame size overflows memory",
+                ))))
+            }
+        }
+    }
+
+    fn rewind(&mut self) -> Result<(), DecodeError> {
+        Err(DecodeError::Io(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "raw frame streams cannot be rewound",
+        )))
+    }
+
+    fn loop_count(&self) -> LoopCount {
+        LoopCount::Finite(1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::{self, Read};
+
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::{
+        codecs::{
+            decode_error::DecodeError,
+            decoded_image::DecodedImage,
+            frame_source::{FrameSource, Playback, TimedFrame},
+            raw_frames::{RawFrames, RawPixelFormat},
+        },
+        entities::{LumaImage, RgbImage},
+    };
+
+    /// Hands out at most one byte per read, like a slow pipe.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+            match (self.0.split_first(), buffer.first_mut()) {
+                (Some((byte, rest)), Some(slot)) => {
+                    *slot = *byte;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                _ => Ok(0),
+            }
+        }
+    }
+
+    #[test]
+    fn should_assemble_frames_from_short_reads() {
+        let bytes: [u8; 12] = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+        let source: RawFrames<Trickle> =
+            RawFrames::new(Trickle(&bytes), RawPixelFormat::Rgb24, 2, 1);
+
+        let images: Vec<DecodedImage> = Playback::new(source)
+            .map(|frame| frame.unwrap().image)
+            .collect();
+
+        assert_eq!(
+            images,
+            vec![
+                DecodedImage::Rgb(RgbImage::from_rgb_data(2, 1, vec![1, 2, 3, 4, 5, 6])),
+                DecodedImage::Rgb(RgbImage::from_rgb_data(2, 1, vec![7, 8, 9, 10, 11, 12])),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_end_cleanly_at_frame_boundary() {
+        let mut source: RawFrames<&[u8]> =
+            RawFrames::new(&[0, 255][..], RawPixelFormat::Gray8, 1, 2);
+
+        let frame: TimedFrame = source.next_frame().unwrap().unwrap();
+
+        assert_eq!(
+            frame.image,
+            DecodedImage::Luma(LumaImage::from_luma_data(1, 2, vec![0, 255]))
+        );
+        assert!(source.next_frame().is_none());
+    }
+
+    #[test]
+    fn should_fail_on_truncated_frame() {
+        let mut source: RawFrames<&[u8]> =
+            RawFrames::new(&[0, 0, 0, 0, 9][..], RawPixelFormat::Rgba, 1, 1);
+
+        assert!(source.next_frame().unwrap().is_ok());
+        assert!(matches!(
+            source.next_frame(),
+            Some(Err(DecodeError::Io(error))) if error.kind() == io::ErrorKind::UnexpectedEof
+        ));
+        assert!(source.next_frame().is_none());
+    }
+}
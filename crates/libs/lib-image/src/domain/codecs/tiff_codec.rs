@@ -0,0 +1,88 @@
+use std::io::Cursor;
+
+use tiff::{
+    ColorType,
+    decoder::{Decoder, DecodingResult},
+};
+
+use crate::domain::codecs::{
+    decode_error::DecodeError, decoded_image::DecodedImage, image_format::ImageFormat,
+};
+
+/// Decodes the first page of 8 or 16-bit gray, RGB and CMYK images, with or without alpha.
+pub(crate) fn decode(bytes: &[u8]) -> Result<DecodedImage, DecodeError> {
+    let malformed = |error| DecodeError::malformed(ImageFormat::Tiff, error);
+
+    let mut decoder: Decoder<Cursor<&[u8]>> =
+        Decoder::new(Cursor::new(bytes)).map_err(malformed)?;
+    let (width, height): (u32, u32) = decoder.dimensions().map_err(malformed)?;
+    let color: ColorType = decoder.colortype().map_err(malformed)?;
+
+    let channels: usize = match color {
+        ColorType::Gray(8 | 16) => 1,
+        ColorType::GrayA(8 | 16) => 2,
+        ColorType::RGB(8 | 16) => 3,
+        ColorType::RGBA(8 | 16) | ColorType::CMYK(8 | 16) => 4,
+        other => {
+            return Err(DecodeError::malformed(
+                ImageFormat::Tiff,
+                format!("unsupported color type {other:?}"),
+            ));
+        }
+    };
+
+    let mut data: Vec<u8> = match decoder.read_image().map_err(malformed)? {
+        DecodingResult::U8(data) => data,
+        DecodingResult::U16(data) => data.into_iter().map(|s: u16| (s >> 8) as u8).collect(),
+        _ => {
+            return Err(DecodeError::malformed(
+                ImageFormat::Tiff,
+                "unsupported sample format",
+            ));
+        }
+    };
+
+    let channels: usize = if matches!(color, ColorType::CMYK(_)) {
+        data = data
+            .chunks_exact(4)
+            .flat_map(|cmyk: &[u8]| {
+                let k: u32 = 255 - cmyk[3] as u32;
+
+                [0, 1, 2].map(|c| ((255 - cmyk[c] as u32) * k / 255) as u8)
+            })
+            .collect();
+        3
+    } else {
+        channels
+    };
+
+    DecodedImage::from_samples(width as usize, height as usize, channels, data)
+        .ok_or_else(|| DecodeError::malformed(ImageFormat::Tiff, "unsupported channel count"))
+}
+
+#[cfg(test)]
+mod test {
+    use std::io::Cursor;
+
+    use pretty_assertions::assert_eq;
+    use tiff::encoder::{TiffEncoder, colortype};
+
+    use crate::domain::{
+        codecs::{decoded_image::DecodedImage, tiff_codec::decode},
+        entities::LumaImage,
+    };
+
+    #[test]
+    fn should_decode_gray_tiff() {
+        let mut bytes: Cursor<Vec<u8>> = Cursor::new(Vec::new());
+        TiffEncoder::new(&mut bytes)
+            .unwrap()
+            .write_image::<colortype::Gray8>(2, 2, &[0, 64, 128, 255])
+            .unwrap();
+
+        assert_eq!(
+            decode(bytes.get_ref()).unwrap(),
+            DecodedImage::Luma(LumaImage::from_luma_data(2, 2, vec![0, 64, 128, 255]))
+        );
+    }
+}
@@ -0,0 +1,201 @@
+use std::{io::Cursor, sync::Arc, time::Duration};
+
+use image_webp::WebPDecoder;
+
+use crate::domain::codecs::{
+    decode_error::DecodeError,
+    decoded_image::DecodedImage,
+    frame_source::{FrameSource, LoopCount, TimedFrame},
+    image_format::ImageFormat,
+};
+
+/// Decodes a still WebP, or the first frame of an animated one.
+pub(crate) fn decode(bytes: &[u8]) -> Result<DecodedImage, DecodeError> {
+    let mut decoder: WebPDecoder<Cursor<&[u8]>> =
+        WebPDecoder::new(Cursor::new(bytes)).map_err(malformed)?;
+    let mut data: Vec<u8> = vec![0; buffer_size(&decoder)?];
+
+    decoder.read_image(&mut data).map_err(malformed)?;
+    image(&decoder, data)
+}
+
+/// Frames of an animated WebP, or a still one as a single frame.
+///
+/// The decoder itself composites animation frames onto the canvas.
+pub(crate) struct WebPFrames {
+    decoder: WebPDecoder<Cursor<Arc<[u8]>>>,
+    frames_read: u32,
+}
+
+impl WebPFrames {
+    pub(crate) fn new(bytes: Arc<[u8]>) -> Result<Self, DecodeError> {
+        Ok(Self {
+            decoder: WebPDecoder::new(Cursor::new(bytes)).map_err(malformed)?,
+            frames_read: 0,
+        })
+    }
+
+    fn read_frame(&mut self) -> Result<TimedFrame, DecodeError> {
+        let mut data: Vec<u8> = vec![0; buffer_size(&self.decoder)?];
+        let delay: Duration = match self.decoder.is_animated() {
+            true => {
+                Duration::from_millis(self.decoder.read_frame(&mut data).map_err(malformed)? as u64)
+            }
+            false => {
+                self.decoder.read_image(&mut data).map_err(malformed)?;
+                Duration::ZERO
+            }
+        };
+
+        Ok(TimedFrame {
+            image: image(&self.decoder, data)?,
+            delay,
+        })
+    }
+}
+
+impl FrameSource for WebPFrames {
+    fn next_frame(&mut self) -> Option<Result<TimedFrame, DecodeError>> {
+        let frames: u32 = match self.decoder.is_animated() {
+            true => self.decoder.num_frames(),
+            false => 1,
+        };
+
+        if self.frames_read >= frames {
+            return None;
+        }
+        self.frames_read += 1;
+
+        Some(self.read_frame())
+    }
+
+    fn rewind(&mut self) -> Result<(), DecodeError> {
+        if self.decoder.is_animated() {
+            self.decoder.reset_animation();
+        }
+        self.frames_read = 0;
+        Ok(())
+    }
+
+    fn loop_count(&self) -> LoopCount {
+        match self.decoder.is_animated() {
+            true => match self.decoder.loop_count() {
+                image_webp::LoopCount::Forever => LoopCount::Infinite,
+                image_webp::LoopCount::Times(plays) => LoopCount::Finite(plays.get() as u32),
+            },
+            false => LoopCount::Finite(1),
+        }
+    }
+}
+
+fn malformed(error: image_webp::DecodingError) -> DecodeError {
+    DecodeError::malformed(ImageFormat::WebP, error)
+}
+
+fn buffer_size<R>(decoder: &WebPDecoder<R>) -> Result<usize, DecodeError>
+where
+    R: std::io::BufRead + std::io::Seek,
+{
+    decoder
+        .output_buffer_size()
+        .ok_or_else(|| DecodeError::malformed(ImageFormat::WebP, "image too large"))
+}
+
+fn image<R>(decoder: &WebPDecoder<R>, data: Vec<u8>) -> Result<DecodedImage, DecodeError>
+where
+    R: std::io::BufRead + std::io::Seek,
+{
+    let (width, height): (u32, u32) = decoder.dimensions();
+    let channels: usize = if decoder.has_alpha() { 4 } else { 3 };
+
+    DecodedImage::from_samples(width as usize, height as usize, channels, data)
+        .ok_or_else(|| DecodeError::malformed(ImageFormat::WebP, "invalid channel count"))
+}
+
+#[cfg(test)]
+mod test {
+    use std::{sync::Arc, time::Duration};
+
+    use image_webp::{ColorType, WebPEncoder};
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::{
+        codecs::{
+            decoded_image::DecodedImage,
+            frame_source::{FrameSource, LoopCount, Playback, TimedFrame},
+            webp_codec::{WebPFrames, decode},
+        },
+        entities::RgbImage,
+    };
+
+    fn encode(pixels: &[u8], width: u32) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::new();
+        WebPEncoder::new(&mut bytes)
+            .encode(pixels, width, 1, ColorType::Rgb8)
+            .unwrap();
+
+        bytes
+    }
+
+    fn chunk(fourcc: &[u8], payload: &[u8]) -> Vec<u8> {
+        let mut chunk: Vec<u8> = fourcc.to_vec();
+        chunk.extend((payload.len() as u32).to_le_bytes());
+        chunk.extend(payload);
+        if payload.len() % 2 == 1 {
+            chunk.push(0);
+        }
+
+        chunk
+    }
+
+    /// An animated 1x1 WebP whose frames are the given gray levels, each shown for 30 ms.
+    fn animation(levels: &[u8], loops: u16) -> Vec<u8> {
+        let mut body: Vec<u8> = b"WEBP".to_vec();
+        body.extend(chunk(b"VP8X", &[0x02, 0, 0, 0, 0, 0, 0, 0, 0, 0]));
+        body.extend(chunk(
+            b"ANIM",
+            &[0, 0, 0, 0, loops as u8, (loops >> 8) as u8],
+        ));
+
+        for level in levels {
+            // Reuse the VP8L chunk of an encoded still: everything after `RIFF size WEBP`.
+            let still: Vec<u8> = encode(&[*level; 3], 1);
+            // Offset, size minus one and duration are 24-bit; the last byte holds the flags.
+            let mut frame: Vec<u8> = vec![0; 16];
+            frame[12] = 30;
+            frame[15] = 0b10; // overwrite rather than blend, keep afterwards
+            frame.extend(&still[12..]);
+            body.extend(chunk(b"ANMF", &frame));
+        }
+
+        chunk(b"RIFF", &body)
+    }
+
+    #[test]
+    fn should_decode_lossless_webp() {
+        let pixels: Vec<u8> = vec![255, 0, 0, 0, 255, 0, 0, 0, 255];
+
+        assert_eq!(
+            decode(&encode(&pixels, 3)).unwrap(),
+            DecodedImage::Rgb(RgbImage::from_rgb_data(3, 1, pixels))
+        );
+    }
+
+    #[test]
+    fn should_play_animated_webp_frames() {
+        let mut source: WebPFrames = WebPFrames::new(Arc::from(animation(&[10, 200], 2))).unwrap();
+
+        assert_eq!(source.loop_count(), LoopCount::Finite(2));
+
+        let frames: Vec<TimedFrame> = Playback::new(&mut source)
+            .collect::<Result<Vec<TimedFrame>, _>>()
+            .unwrap();
+        let levels: Vec<u8> = frames
+            .iter()
+            .map(|frame: &TimedFrame| frame.image.clone().into_rgb().data()[0])
+            .collect();
+
+        assert_eq!(levels, vec![10, 200, 10, 200]);
+        assert_eq!(frames[0].delay, Duration::from_millis(30));
+    }
+}
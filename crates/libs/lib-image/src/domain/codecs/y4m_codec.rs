@@ -0,0 +1,447 @@
+use std::{
+    fmt,
+    io::{self, BufRead, Cursor, Read, Write},
+    time::Duration,
+};
+
+use crate::domain::{
+    codecs::{
+        decode_error::DecodeError,
+        decoded_image::DecodedImage,
+        frame_source::{FrameSource, LoopCount, TimedFrame},
+        image_format::ImageFormat,
+    },
+    entities::LumaImage,
+};
+
+/// Longest stream or frame header line accepted, so garbage input cannot grow it unbounded.
+const MAX_HEADER_LENGTH: usize = 4096;
+
+/// Decodes the luma plane of the first frame.
+pub(crate) fn decode(bytes: &[u8]) -> Result<DecodedImage, DecodeError> {
+    Y4mReader::new(Cursor::new(bytes))?
+        .next_frame()
+        .unwrap_or_else(|| Err(malformed("no frames")))
+        .map(|frame: TimedFrame| frame.image)
+}
+
+/// Chroma subsampling of a YUV4MPEG2 stream.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Y4mChroma {
+    /// 4:2:0, whatever the chroma siting. This is the default when the header has no `C` field.
+    C420,
+    C422,
+    C444,
+    /// Luma only.
+    Mono,
+}
+
+impl Y4mChroma {
+    fn parse(value: &str) -> Result<Self, DecodeError> {
+        match value {
+            "420" | "420jpeg" | "420paldv" | "420mpeg2" => Ok(Y4mChroma::C420),
+            "422" => Ok(Y4mChroma::C422),
+            "444" => Ok(Y4mChroma::C444),
+            "mono" => Ok(Y4mChroma::Mono),
+            _ => Err(malformed(format!("unsupported colorspace '{value}'"))),
+        }
+    }
+
+    /// Bytes taken by both chroma planes of a `width` by `height` frame.
+    fn planes_size(self, width: usize, height: usize) -> Option<usize> {
+        let (width, height): (usize, usize) = match self {
+            Y4mChroma::C420 => (width.div_ceil(2), height.div_ceil(2)),
+            Y4mChroma::C422 => (width.div_ceil(2), height),
+            Y4mChroma::C444 => (width, height),
+            Y4mChroma::Mono => (0, 0),
+        };
+
+        width.checked_mul(height)?.checked_mul(2)
+    }
+}
+
+impl fmt::Display for Y4mChroma {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let tag: &str = match self {
+            Y4mChroma::C420 => "420jpeg",
+            Y4mChroma::C422 => "422",
+            Y4mChroma::C444 => "444",
+            Y4mChroma::Mono => "mono",
+        };
+
+        write!(f, "{tag}")
+    }
+}
+
+/// The stream header of a YUV4MPEG2 file. Ratios of `0:0` mean unknown.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Y4mHeader {
+    pub width: usize,
+    pub height: usize,
+    /// Frames per second, as `numerator:denominator`.
+    pub frame_rate: (u32, u32),
+    /// Width of a pixel divided by its height, as `numerator:denominator`.
+    pub pixel_aspect: (u32, u32),
+    pub chroma: Y4mChroma,
+}
+
+impl Y4mHeader {
+    /// A 4:2:0 progressive stream with square pixels.
+    pub fn new(width: usize, height: usize, frame_rate: (u32, u32)) -> Self {
+        Self {
+            width,
+            height,
+            frame_rate,
+            pixel_aspect: (1, 1),
+            chroma: Y4mChroma::C420,
+        }
+    }
+
+    /// How long each frame is shown, or zero when the frame rate is unknown.
+    pub fn frame_delay(&self) -> Duration {
+        match self.frame_rate {
+            (0, _) | (_, 0) => Duration::ZERO,
+            (numerator, denominator) => {
+                Duration::from_secs_f64(denominator as f64 / numerator as f64)
+            }
+        }
+    }
+
+    fn parse(line: &str) -> Result<Self, DecodeError> {
+        let mut fields = line.split(' ').filter(|field: &&str| !field.is_empty());
+
+        if fields.next() != Some("YUV4MPEG2") {
+            return Err(malformed("missing YUV4MPEG2 signature"));
+        }
+
+        let (mut width, mut height): (Option<usize>, Option<usize>) = (None, None);
+        let mut header: Y4mHeader = Y4mHeader {
+            frame_rate: (0, 0),
+            pixel_aspect: (0, 0),
+            ..Y4mHeader::new(0, 0, (0, 0))
+        };
+
+        for field in fields {
+            let mut chars = field.chars();
+            let tag: Option<char> = chars.next();
+            let value: &str = chars.as_str();
+
+            match tag {
+                Some('W') => width = Some(number(value)?),
+                Some('H') => height = Some(number(value)?),
+                Some('F') => header.frame_rate = ratio(value)?,
+                Some('A') => header.pixel_aspect = ratio(value)?,
+                Some('C') => header.chroma = Y4mChroma::parse(value)?,
+                // Interlacing does not change the plane layout, and `X` fields are extensions.
+                _ => {}
+            }
+        }
+
+        match (width, height) {
+            (Some(width), Some(height)) if width > 0 && height > 0 => Ok(Y4mHeader {
+                width,
+                height,
+                ..header
+            }),
+            _ => Err(malformed("missing frame size")),
+        }
+    }
+}
+
+impl fmt::Display for Y4mHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "YUV4MPEG2 W{} H{} F{}:{} Ip A{}:{} C{}",
+            self.width,
+            self.height,
+            self.frame_rate.0,
+            self.frame_rate.1,
+            self.pixel_aspect.0,
+            self.pixel_aspect.1,
+            self.chroma
+        )
+    }
+}
+
+/// Reads YUV4MPEG2 frames from a stream, keeping the Y plane as luma and skipping chroma.
+///
+/// Frames are returned as they are read, so the stream can be a pipe; wrap unbuffered readers
+/// in a `BufReader`. Streams play once and cannot be rewound.
+pub struct Y4mReader<R: BufRead> {
+    reader: R,
+    header: Y4mHeader,
+    luma_size: usize,
+    chroma_size: usize,
+    ended: bool,
+}
+
+impl<R: BufRead> Y4mReader<R> {
+    /// Reads and parses the stream header.
+    pub fn new(mut reader: R) -> Result<Self, DecodeError> {
+        let line: String = read_line(&mut reader)?.ok_or_else(|| malformed("empty stream"))?;
+        let header: Y4mHeader = Y4mHeader::parse(&line)?;
+        let luma_size: usize = header
+            .width
+            .checked_mul(header.height)
+            .ok_or_else(|| malformed("frame too large"))?;
+        let chroma_size: usize = header
+            .chroma
+            .planes_size(header.width, header.height)
+            .ok_or_else(|| malformed("frame too large"))?;
+
+        Ok(Self {
+            reader,
+            header,
+            luma_size,
+            chroma_size,
+            ended: false,
+        })
+    }
+
+    pub fn header(&self) -> &Y4mHeader {
+        &self.header
+    }
+
+    fn read_frame(&mut self) -> Result<Option<TimedFrame>, DecodeError> {
+        let Some(line) = read_line(&mut self.reader)? else {
+            return Ok(None);
+        };
+
+        if line.split(' ').next() != Some("FRAME") {
+            return Err(malformed("missing FRAME marker"));
+        }
+
+        let mut luma: Vec<u8> = vec![0; self.luma_size];
+        self.reader.read_exact(&mut luma).map_err(truncated)?;
+
+        let skipped: u64 = io::copy(
+            &mut self.reader.by_ref().take(self.chroma_size as u64),
+            &mut io::sink(),
+        )?;
+        if skipped < self.chroma_size as u64 {
+            return Err(malformed("stream ended inside a frame"));
+        }
+
+        Ok(Some(TimedFrame {
+            image: DecodedImage::Luma(LumaImage::from_luma_data(
+                self.header.width,
+                self.header.height,
+                luma,
+            )),
+            delay: self.header.frame_delay(),
+        }))
+    }
+}
+
+impl<R: BufRead> FrameSource for Y4mReader<R> {
+    fn next_frame(&mut self) -> Option<Result<TimedFrame, DecodeError>> {
+        if self.ended {
+            return None;
+        }
+
+        let frame: Result<Option<TimedFrame>, DecodeError> = self.read_frame();
+        self.ended = !matches!(frame, Ok(Some(_)));
+
+        frame.transpose()
+    }
+
+    fn rewind(&mut self) -> Result<(), DecodeError> {
+        Err(DecodeError::Io(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "Y4M streams cannot be rewound",
+        )))
+    }
+
+    fn loop_count(&self) -> LoopCount {
+        LoopCount::Finite(1)
+    }
+}
+
+/// Writes luma images as YUV4MPEG2 frames with neutral chroma, for example rasterized glyph
+/// text from [`LumaImage::from_text`].
+pub struct Y4mWriter<W: Write> {
+    writer: W,
+    header: Y4mHeader,
+}
+
+impl<W: Write> Y4mWriter<W> {
+    /// Writes the stream header.
+    pub fn new(mut writer: W, header: Y4mHeader) -> io::Result<Self> {
+        writeln!(writer, "{header}")?;
+
+        Ok(Self { writer, header })
+    }
+
+    pub fn header(&self) -> &Y4mHeader {
+        &self.header
+    }
+
+    /// Writes `image` as the Y plane of the next frame. It must match the header's frame size.
+    pub fn write_frame(&mut self, image: &LumaImage) -> io::Result<()> {
+        let (width, height): (usize, usize) = (self.header.width, self.header.height);
+
+        if (image.width(), image.height()) != (width, height) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "{}x{} frame does not match the {width}x{height} stream",
+                    image.width(),
+                    image.height()
+                ),
+            ));
+        }
+
+        let chroma_size: usize = self.header.chroma.planes_size(width, height).unwrap_or(0);
+
+        self.writer.write_all(b"FRAME\n")?;
+        self.writer.write_all(&image.data())?;
+        self.writer.write_all(&vec![128; chroma_size])
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+fn malformed(message: impl fmt::Display) -> DecodeError {
+    DecodeError::malformed(ImageFormat::Y4m, message)
+}
+
+fn truncated(error: io::Error) -> DecodeError {
+    match error.kind() {
+        io::ErrorKind::UnexpectedEof => malformed("stream ended inside a frame"),
+        _ => DecodeError::Io(error),
+    }
+}
+
+/// Reads a header line without its line feed, or `None` if the stream ends before it starts.
+fn read_line(reader: &mut impl BufRead) -> Result<Option<String>, DecodeError> {
+    let mut line: Vec<u8> = Vec::new();
+
+    reader
+        .take(MAX_HEADER_LENGTH as u64 + 1)
+        .read_until(b'\n', &mut line)?;
+
+    match line.pop() {
+        None => Ok(None),
+        Some(b'\n') => Ok(Some(String::from_utf8_lossy(&line).into_owned())),
+        Some(_) if line.len() == MAX_HEADER_LENGTH => Err(malformed("header too long")),
+        Some(_) => Err(malformed("stream ended inside a header")),
+    }
+}
+
+fn number(value: &str) -> Result<usize, DecodeError> {
+    value
+        .parse()
+        .map_err(|_| malformed(format!("invalid number '{value}'")))
+}
+
+fn ratio(value: &str) -> Result<(u32, u32), DecodeError> {
+    let parse = |v: &str| v.parse::<u32>().ok();
+
+    value
+        .split_once(':')
+        .and_then(|(numerator, denominator)| Some((parse(numerator)?, parse(denominator)?)))
+        .ok_or_else(|| malformed(format!("invalid ratio '{value}'")))
+}
+
+#[cfg(test)]
+mod test {
+    use std::{io::Cursor, time::Duration};
+
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::{
+        codecs::{
+            decode_error::DecodeError,
+            decoded_image::DecodedImage,
+            frame_source::{FrameSource, Playback, TimedFrame},
+            y4m_codec::{Y4mChroma, Y4mHeader, Y4mReader, Y4mWriter, decode},
+        },
+        entities::LumaImage,
+    };
+
+    #[test]
+    fn should_read_header_fields_and_skip_chroma() {
+        let mut bytes: Vec<u8> =
+            b"YUV4MPEG2 W3 H2 F30000:1001 It A10:11 C422 XYSCSS=422\n".to_vec();
+        for level in [10, 20] {
+            bytes.extend(b"FRAME\n");
+            bytes.extend([level; 6]);
+            // Two 2x2 chroma planes.
+            bytes.extend([99; 8]);
+        }
+
+        let reader: Y4mReader<&[u8]> = Y4mReader::new(&bytes[..]).unwrap();
+
+        assert_eq!(
+            *reader.header(),
+            Y4mHeader {
+                width: 3,
+                height: 2,
+                frame_rate: (30000, 1001),
+                pixel_aspect: (10, 11),
+                chroma: Y4mChroma::C422,
+            }
+        );
+
+        let frames: Vec<TimedFrame> = Playback::new(reader)
+            .collect::<Result<Vec<TimedFrame>, _>>()
+            .unwrap();
+
+        assert_eq!(frames.len(), 2);
+        assert_eq!(
+            frames[1].image,
+            DecodedImage::Luma(LumaImage::from_luma_data(3, 2, vec![20; 6]))
+        );
+        assert_eq!(frames[0].delay, Duration::from_secs_f64(1001.0 / 30000.0));
+    }
+
+    #[test]
+    fn should_round_trip_written_frames() {
+        let image: LumaImage = LumaImage::from_luma_data(3, 3, (0..9).collect());
+        let mut writer: Y4mWriter<Vec<u8>> =
+            Y4mWriter::new(Vec::new(), Y4mHeader::new(3, 3, (25, 1))).unwrap();
+
+        writer.write_frame(&image).unwrap();
+        writer.write_frame(&image.clone().invert()).unwrap();
+        assert!(writer.write_frame(&LumaImage::new(2, 2)).is_err());
+
+        let bytes: Vec<u8> = writer.into_inner();
+        let mut reader: Y4mReader<Cursor<&[u8]>> = Y4mReader::new(Cursor::new(&bytes[..])).unwrap();
+
+        assert_eq!(*reader.header(), Y4mHeader::new(3, 3, (25, 1)));
+        assert_eq!(
+            reader.next_frame().unwrap().unwrap().image,
+            DecodedImage::Luma(image.clone())
+        );
+        assert_eq!(
+            reader.next_frame().unwrap().unwrap().image,
+            DecodedImage::Luma(image.invert())
+        );
+        assert!(reader.next_frame().is_none());
+        assert_eq!(
+            decode(&bytes).unwrap().into_luma().data()[..],
+            (0..9).collect::<Vec<u8>>()[..]
+        );
+    }
+
+    #[test]
+    fn should_reject_truncated_frames_and_bad_headers() {
+        let mut reader: Y4mReader<&[u8]> =
+            Y4mReader::new(&b"YUV4MPEG2 W2 H2 Cmono\nFRAME\n\x01\x02"[..]).unwrap();
+
+        assert!(matches!(
+            reader.next_frame(),
+            Some(Err(DecodeError::Malformed { .. }))
+        ));
+        assert!(reader.next_frame().is_none());
+        assert!(Y4mReader::new(&b"YUV4MPEG2 W2 C420p10\n"[..]).is_err());
+        assert!(Y4mReader::new(&b"YUV4MPEG2 H2\n"[..]).is_err());
+    }
+}
@@ -1,7 +1,37 @@
+mod alpha_image;
+mod auto_exposure;
+mod dither;
+mod edge_map;
 mod glyph_image;
+mod image_buffer;
+mod line_art_image;
 mod luma_image;
 mod luma_pixel;
+mod placed_image;
+mod placement;
+mod resample_filter;
+mod rgb_image;
 mod rgb_pixel;
+mod rgba_image;
+mod rgba_pixel;
+mod text_raster;
+mod tone_adjustment;
 
+pub use alpha_image::{AlphaImage, AlphaSettings, TransparentCell, TransparentCellParseError};
+pub use auto_exposure::{AutoExposure, AutoExposureSettings};
+pub use dither::{Dither, UnknownDitherError};
+pub use edge_map::{EdgeDetector, EdgeDirection, EdgeMap, EdgeOperator};
+pub use glyph_image::GlyphImage;
+pub use image_buffer::InvalidImageDataError;
+pub use line_art_image::{LineArtImage, LineArtMode};
 pub use luma_image::LumaImage;
 pub use luma_pixel::LumaPixel;
+pub use placed_image::{PlacedImage, PlacementFill};
+pub use placement::{FitMode, InvalidRectError, Placement, PlacementLayout, Rect};
+pub use resample_filter::{ResampleFilter, UnknownResampleFilterError};
+pub use rgb_image::RgbImage;
+pub use rgb_pixel::{InvalidColorError, RgbPixel};
+pub use rgba_image::RgbaImage;
+pub use rgba_pixel::RgbaPixel;
+pub use text_raster::{TEXT_CELL_HEIGHT, TEXT_CELL_WIDTH};
+pub use tone_adjustment::{ToneAdjustment, ToneAdjustmentParseError};
@@ -0,0 +1,318 @@
+use std::{fmt, str::FromStr};
+
+use itertools::Itertools;
+use lib_core::domain::entities::{Glyph, GlyphRenderingEngine};
+
+use crate::domain::entities::{
+    glyph_image::GlyphImage, luma_pixel::LumaPixel, rgb_pixel::RgbPixel, rgba_image::RgbaImage,
+};
+
+/// What a fully transparent cell is drawn with.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum TransparentCell {
+    /// Nothing: a blank on its own, and a hole that lower layers show through when layered.
+    Clear,
+    /// A literal glyph, independent of the rendering engine.
+    Glyph(char),
+    /// A solid color rendered through the engine like any opaque pixel.
+    Color(RgbPixel),
+    /// Alternating `size x size` squares of two luma values.
+    Checkerboard { size: usize, light: u8, dark: u8 },
+}
+
+impl FromStr for TransparentCell {
+    type Err = TransparentCellParseError;
+
+    /// Parses `clear`, `glyph:<char>`, `color:<#rrggbb|r,g,b>` or
+    /// `checkerboard[=<size>:<light>:<dark>]`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || TransparentCellParseError(s.into());
+
+        match s.split_once([':', '=']) {
+            None if s == "clear" => Ok(TransparentCell::Clear),
+            None if s == "checkerboard" => Ok(TransparentCell::Checkerboard {
+                size: 2,
+                light: 204,
+                dark: 153,
+            }),
+            Some(("glyph", glyph)) => glyph
+                .chars()
+                .exactly_one()
+                .map(TransparentCell::Glyph)
+                .map_err(|_| error()),
+            Some(("color", color)) => color
+                .parse()
+                .map(TransparentCell::Color)
+                .map_err(|_| error()),
+            Some(("checkerboard", spec)) => {
+                let values: Vec<usize> = spec
+                    .split(':')
+                    .map(|v| v.trim().parse::<usize>())
+                    .collect::<Result<Vec<usize>, _>>()
+                    .map_err(|_| error())?;
+
+                match values.as_slice() {
+                    [size, light, dark] if *light <= 255 && *dark <= 255 => {
+                        Ok(TransparentCell::Checkerboard {
+                            size: *size,
+                            light: *light as u8,
+                            dark: *dark as u8,
+                        })
+                    }
+                    _ => Err(error()),
+                }
+            }
+            _ => Err(error()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct TransparentCellParseError(pub String);
+
+impl fmt::Display for TransparentCellParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "expected 'clear', 'glyph:<char>', 'color:<color>' or \
+             'checkerboard[=<size>:<light>:<dark>]', got '{}'",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for TransparentCellParseError {}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct AlphaSettings {
+    /// Color that partially transparent pixels are blended over.
+    pub background: RgbPixel,
+    /// Pixels with an alpha at or below this value count as fully transparent.
+    pub threshold: u8,
+    pub transparent: TransparentCell,
+}
+
+impl Default for AlphaSettings {
+    fn default() -> Self {
+        Self {
+            background: RgbPixel::new(0, 0, 0),
+            threshold: 0,
+            transparent: TransparentCell::Clear,
+        }
+    }
+}
+
+/// Renders an [`RgbaImage`], honouring its alpha channel.
+#[derive(Debug)]
+pub struct AlphaImage {
+    image: RgbaImage,
+    settings: AlphaSettings,
+}
+
+impl AlphaImage {
+    pub fn new(image: RgbaImage, settings: AlphaSettings) -> Self {
+        Self { image, settings }
+    }
+
+    pub fn image(&self) -> &RgbaImage {
+        &self.image
+    }
+
+    fn write_cell(
+        &self,
+        engine: &dyn GlyphRenderingEngine,
+        out: &mut dyn fmt::Write,
+        (x, y): (usize, usize),
+        p: &[u8],
+    ) -> fmt::Result {
+        let mut rgb_text = |pixel: RgbPixel| {
+            let luma: u8 = LumaPixel::from_rgb(pixel).y;
+
+            engine.write_text_based_on_rgb_value(out, pixel.r, pixel.g, pixel.b, luma)
+        };
+
+        if p[3] > self.settings.threshold {
+            let alpha: u32 = p[3] as u32;
+            let background: RgbPixel = self.settings.background;
+            let blend = |fg: u8, bg: u8| {
+                ((fg as u32 * alpha + bg as u32 * (255 - alpha) + 127) / 255) as u8
+            };
+
+            return rgb_text(RgbPixel::new(
+                blend(p[0], background.r),
+                blend(p[1], background.g),
+                blend(p[2], background.b),
+            ));
+        }
+
+        match self.settings.transparent {
+            TransparentCell::Clear => out.write_char(' '),
+            TransparentCell::Glyph(glyph) => out.write_char(glyph),
+            TransparentCell::Color(color) => rgb_text(color),
+            TransparentCell::Checkerboard { size, light, dark } => {
+                let size: usize = size.max(1);
+                let luma: u8 = if (x / size + y / size).is_multiple_of(2) {
+                    light
+                } else {
+                    dark
+                };
+
+                out.write_char(engine.get_char_based_on_luma_value(luma))
+            }
+        }
+    }
+}
+
+impl Glyph for AlphaImage {
+    fn render_into(
+        &self,
+        engine: &dyn GlyphRenderingEngine,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        for (y, row) in self.image.rows().enumerate() {
+            if y > 0 {
+                out.write_char('\n')?;
+            }
+
+            for (x, p) in row.chunks_exact(4).enumerate() {
+                self.write_cell(engine, out, (x, y), p)?;
+            }
+
+            out.write_str(engine.get_style_reset())?;
+        }
+
+        Ok(())
+    }
+
+    fn size_hint(&self, engine: &dyn GlyphRenderingEngine) -> usize {
+        self.image.size_hint(engine)
+    }
+
+    fn add_child(&mut self, _child: Box<dyn Glyph>) {}
+
+    fn get_child_mut(&mut self, _index: usize) -> Option<&mut dyn Glyph> {
+        None
+    }
+
+    fn is_transparent_at(&self, x: usize, y: usize) -> bool {
+        self.settings.transparent == TransparentCell::Clear
+            && self
+                .image
+                .pixel(x, y)
+                .is_some_and(|p| p.a <= self.settings.threshold)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use lib_core::domain::entities::{Glyph, GlyphOverlay, GlyphRenderingEngine};
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::entities::{
+        alpha_image::{AlphaImage, AlphaSettings, TransparentCell},
+        luma_image::LumaImage,
+        rgb_pixel::RgbPixel,
+        rgba_image::RgbaImage,
+    };
+
+    fn logo() -> RgbaImage {
+        RgbaImage::from_rgba_data(
+            2,
+            2,
+            vec![
+                255, 255, 255, 255, 0, 0, 0, 0, //
+                255, 255, 255, 0, 255, 255, 255, 128,
+            ],
+        )
+    }
+
+    fn settings(transparent: TransparentCell) -> AlphaSettings {
+        AlphaSettings {
+            transparent,
+            ..AlphaSettings::default()
+        }
+    }
+
+    #[test]
+    fn transparent_cells_should_be_blank_and_see_through() {
+        let image: AlphaImage = AlphaImage::new(logo(), AlphaSettings::default());
+
+        assert_eq!(image.as_text(&LevelEngine), "9 \n 5");
+        assert!(image.is_transparent_at(1, 0));
+        assert!(!image.is_transparent_at(0, 0));
+    }
+
+    #[test]
+    fn should_composite_partial_alpha_over_background() {
+        let image: AlphaImage = AlphaImage::new(
+            logo(),
+            AlphaSettings {
+                background: RgbPixel::new(255, 255, 255),
+                ..AlphaSettings::default()
+            },
+        );
+
+        assert_eq!(image.as_text(&LevelEngine), "9 \n 9");
+    }
+
+    #[test]
+    fn should_fill_transparent_cells_with_glyph_color_or_checkerboard() {
+        let glyph: AlphaImage = AlphaImage::new(logo(), settings(TransparentCell::Glyph('.')));
+        let color: AlphaImage = AlphaImage::new(
+            logo(),
+            settings(TransparentCell::Color(RgbPixel::new(128, 128, 128))),
+        );
+        let checkerboard: AlphaImage = AlphaImage::new(
+            RgbaImage::new(3, 2),
+            settings(TransparentCell::Checkerboard {
+                size: 1,
+                light: 255,
+                dark: 0,
+            }),
+        );
+
+        assert_eq!(glyph.as_text(&LevelEngine), "9.\n.5");
+        assert_eq!(color.as_text(&LevelEngine), "95\n55");
+        assert_eq!(checkerboard.as_text(&LevelEngine), "909\n090");
+        assert!(!glyph.is_transparent_at(1, 0));
+    }
+
+    #[test]
+    fn should_layer_over_other_glyphs() {
+        let overlay: GlyphOverlay = GlyphOverlay::new(
+            Box::new(LumaImage::from_luma_data(2, 2, vec![0; 4])),
+            Box::new(AlphaImage::new(logo(), AlphaSettings::default())),
+        );
+
+        assert_eq!(overlay.as_text(&LevelEngine), "90\n05");
+    }
+
+    #[test]
+    fn should_parse_transparent_cell_specs() {
+        assert_eq!("clear".parse(), Ok(TransparentCell::Clear));
+        assert_eq!("glyph:.".parse(), Ok(TransparentCell::Glyph('.')));
+        assert_eq!(
+            "color:#102030".parse(),
+            Ok(TransparentCell::Color(RgbPixel::new(16, 32, 48)))
+        );
+        assert_eq!(
+            "checkerboard=4:200:100".parse(),
+            Ok(TransparentCell::Checkerboard {
+                size: 4,
+                light: 200,
+                dark: 100
+            })
+        );
+        assert!("glyph:ab".parse::<TransparentCell>().is_err());
+        assert!("checkerboard=1:300:0".parse::<TransparentCell>().is_err());
+    }
+
+    /// Renders luma as a digit from `0` to `9`.
+    struct LevelEngine;
+
+    impl GlyphRenderingEngine for LevelEngine {
+        fn get_char_based_on_luma_value(&self, y: u8) -> char {
+            char::from_digit(y as u32 * 10 / 256, 10).unwrap()
+        }
+    }
+}
@@ -0,0 +1,325 @@
+use crate::domain::entities::{luma_image::LumaImage, rgb_image::RgbImage};
+
+/// Smallest luma range the measured levels are stretched from, which caps the gain on nearly
+/// flat frames so noise is not blown up into full-ramp flicker.
+const MIN_RANGE: f32 = 16.0;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct AutoExposureSettings {
+    /// How far the measured levels are stretched onto the full ramp, from `0.0` (off) to `1.0`.
+    pub strength: f32,
+    /// Weight of the current frame in the exponential moving average of the levels.
+    pub smoothing: f32,
+    /// Fraction of pixels ignored at each end of the histogram when measuring levels.
+    pub clip_fraction: f32,
+    /// Histogram distance (`0.0..=1.0`) above which a frame is treated as a scene cut and the
+    /// levels jump straight to the new measurement.
+    pub scene_cut_threshold: f32,
+}
+
+impl Default for AutoExposureSettings {
+    fn default() -> Self {
+        Self {
+            strength: 1.0,
+            smoothing: 0.1,
+            clip_fraction: 0.01,
+            scene_cut_threshold: 0.5,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+struct ExposureState {
+    black: f32,
+    white: f32,
+    histogram: [f32; 256],
+}
+
+/// Temporal auto-levels for frame sequences.
+#[derive(Debug, PartialEq, Clone)]
+pub struct AutoExposure {
+    settings: AutoExposureSettings,
+    enabled: bool,
+    state: Option<ExposureState>,
+}
+
+impl AutoExposure {
+    pub fn new(settings: AutoExposureSettings) -> Self {
+        Self {
+            settings,
+            enabled: true,
+            state: None,
+        }
+    }
+
+    pub fn settings(&self) -> &AutoExposureSettings {
+        &self.settings
+    }
+
+    pub fn set_strength(&mut self, strength: f32) {
+        self.settings.strength = strength.clamp(0.0, 1.0);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+    }
+
+    /// Forgets the measured levels, so the next frame is treated as a scene cut.
+    pub fn reset(&mut self) {
+        self.state = None;
+    }
+
+    /// Current smoothed `(black, white)` levels, if any frame has been measured.
+    pub fn levels(&self) -> Option<(f32, f32)> {
+        self.state.as_ref().map(|s| (s.black, s.white))
+    }
+
+    /// Measures `image` and, when enabled, stretches it using the smoothed levels.
+    ///
+    /// Levels keep being tracked while disabled so re-enabling does not cause a jump.
+    pub fn apply(&mut self, image: LumaImage) -> LumaImage {
+        match self.track(&image.data()) {
+            Some(lut) => {
+                let data: Vec<u8> = image.data().iter().map(|v| lut[*v as usize]).collect();
+
+                LumaImage::from_luma_data(image.width(), image.height(), data)
+            }
+            None => image,
+        }
+    }
+
+    /// Like [`AutoExposure::apply`], measuring the luma of `image` and stretching every channel.
+    pub fn apply_color(&mut self, image: RgbImage) -> RgbImage {
+        match self.track(&LumaImage::from(&image).data()) {
+            Some(lut) => {
+                let data: Vec<u8> = image.data().iter().map(|v| lut[*v as usize]).collect();
+
+                RgbImage::from_rgb_data(image.width(), image.height(), data)
+            }
+            None => image,
+        }
+    }
+
+    /// Updates the smoothed levels from `luma` and returns the stretch table when enabled.
+    fn track(&mut self, luma: &[u8]) -> Option<[u8; 256]> {
+        let histogram: [f32; 256] = normalized_histogram(luma);
+        let (black, white) = self.measure_levels(&histogram);
+
+        let state: ExposureState = match self.state.take() {
+            Some(previous)
+                if histogram_distance(&previous.histogram, &histogram)
+                    <= self.settings.scene_cut_threshold =>
+            {
+                let alpha: f32 = self.settings.smoothing.clamp(0.0, 1.0);
+
+                ExposureState {
+                    black: previous.black + (black - previous.black) * alpha,
+                    white: previous.white + (white - previous.white) * alpha,
+                    histogram,
+                }
+            }
+            _ => ExposureState {
+                black,
+                white,
+                histogram,
+            },
+        };
+        let lut: Option<[u8; 256]> = self.enabled.then(|| self.lut(&state));
+
+        self.state = Some(state);
+        lut
+    }
+
+    fn measure_levels(&self, histogram: &[f32; 256]) -> (f32, f32) {
+        let clip: f32 = self.settings.clip_fraction.clamp(0.0, 0.49);
+        let mut cumulative: f32 = 0.0;
+        let mut black: Option<f32> = None;
+        let mut white: f32 = 255.0;
+
+        for (value, share) in histogram.iter().enumerate() {
+            cumulative += share;
+
+            if black.is_none() && cumulative > clip {
+                black = Some(value as f32);
+            }
+
+            if cumulative >= 1.0 - clip - f32::EPSILON * 256.0 {
+                white = value as f32;
+                break;
+            }
+        }
+
+        (black.unwrap_or(0.0), white)
+    }
+
+    fn lut(&self, state: &ExposureState) -> [u8; 256] {
+        let strength: f32 = self.settings.strength.clamp(0.0, 1.0);
+        let mut black: f32 = state.black * strength;
+        let mut white: f32 = 255.0 + (state.white - 255.0) * strength;
+
+        if white - black < MIN_RANGE {
+            let centre: f32 =
+                ((black + white) / 2.0).clamp(MIN_RANGE / 2.0, 255.0 - MIN_RANGE / 2.0);
+
+            black = centre - MIN_RANGE / 2.0;
+            white = centre + MIN_RANGE / 2.0;
+        }
+
+        let mut lut: [u8; 256] = [0; 256];
+
+        for (v, out) in lut.iter_mut().enumerate() {
+            *out = ((v as f32 - black) * 255.0 / (white - black))
+                .round()
+                .clamp(0.0, 255.0) as u8;
+        }
+
+        lut
+    }
+}
+
+impl Default for AutoExposure {
+    fn default() -> Self {
+        Self::new(AutoExposureSettings::default())
+    }
+}
+
+fn normalized_histogram(data: &[u8]) -> [f32; 256] {
+    let mut histogram: [f32; 256] = [0.0; 256];
+
+    if data.is_empty() {
+        return histogram;
+    }
+
+    let share: f32 = 1.0 / data.len() as f32;
+
+    for v in data {
+        histogram[*v as usize] += share;
+    }
+
+    histogram
+}
+
+/// Half the L1 distance between two normalized histograms, in `0.0..=1.0`.
+fn histogram_distance(a: &[f32; 256], b: &[f32; 256]) -> f32 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(a, b)| (a - b).abs())
+        .sum::<f32>()
+        / 2.0
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::entities::{
+        auto_exposure::{AutoExposure, AutoExposureSettings},
+        luma_image::LumaImage,
+        rgb_image::RgbImage,
+    };
+
+    fn gradient(from: u8, to: u8) -> LumaImage {
+        LumaImage::from_luma_data(
+            2,
+            (to - from + 1) as usize,
+            (from..=to).flat_map(|v| [v, v]).collect(),
+        )
+    }
+
+    fn unclipped() -> AutoExposureSettings {
+        AutoExposureSettings {
+            clip_fraction: 0.0,
+            ..AutoExposureSettings::default()
+        }
+    }
+
+    #[test]
+    fn should_stretch_dark_frame_onto_full_ramp() {
+        let mut exposure: AutoExposure = AutoExposure::new(unclipped());
+
+        let result: LumaImage = exposure.apply(gradient(10, 50));
+
+        assert_eq!(result.data().first(), Some(&0));
+        assert_eq!(result.data().last(), Some(&255));
+    }
+
+    #[test]
+    fn should_stretch_color_frames_using_luma() {
+        let mut exposure: AutoExposure = AutoExposure::new(unclipped());
+
+        let result: RgbImage = exposure.apply_color(RgbImage::from(&gradient(10, 50)));
+
+        assert_eq!(result.data()[..3], [0, 0, 0]);
+        assert_eq!(result.data()[result.data().len() - 3..], [255, 255, 255]);
+    }
+
+    #[test]
+    fn should_blend_towards_full_range_with_partial_strength() {
+        let mut exposure: AutoExposure = AutoExposure::new(AutoExposureSettings {
+            strength: 0.5,
+            ..unclipped()
+        });
+
+        let result: LumaImage = exposure.apply(gradient(10, 50));
+
+        assert_eq!(result.data().first(), Some(&9));
+        assert_eq!(result.data().last(), Some(&78));
+    }
+
+    #[test]
+    fn should_smooth_levels_between_similar_frames() {
+        let mut exposure: AutoExposure = AutoExposure::new(AutoExposureSettings {
+            smoothing: 0.5,
+            scene_cut_threshold: 1.0,
+            ..unclipped()
+        });
+
+        exposure.apply(gradient(0, 100));
+        exposure.apply(gradient(0, 200));
+
+        assert_eq!(exposure.levels(), Some((0.0, 150.0)));
+    }
+
+    #[test]
+    fn should_snap_levels_on_scene_cut() {
+        let mut exposure: AutoExposure = AutoExposure::new(AutoExposureSettings {
+            smoothing: 0.1,
+            scene_cut_threshold: 0.5,
+            ..unclipped()
+        });
+
+        exposure.apply(gradient(0, 20));
+        exposure.apply(gradient(200, 250));
+
+        assert_eq!(exposure.levels(), Some((200.0, 250.0)));
+    }
+
+    #[test]
+    fn should_pass_frames_through_when_disabled() {
+        let mut exposure: AutoExposure = AutoExposure::default();
+        exposure.toggle();
+
+        let result: LumaImage = exposure.apply(gradient(10, 50));
+
+        assert!(!exposure.is_enabled());
+        assert_eq!(result, gradient(10, 50));
+        assert!(exposure.levels().is_some());
+    }
+
+    #[test]
+    fn should_not_amplify_flat_frames_to_full_contrast() {
+        let mut exposure: AutoExposure = AutoExposure::new(unclipped());
+
+        let result: LumaImage = exposure.apply(LumaImage::from_luma_data(2, 1, vec![100, 101]));
+
+        assert!(result.data()[1] - result.data()[0] < 32);
+    }
+}
@@ -0,0 +1,129 @@
+use std::{fmt, str::FromStr};
+
+/// How luma is spread over the few levels a glyph ramp can show, trading banding for texture.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum Dither {
+    /// Floyd-Steinberg error diffusion; the most faithful on stills.
+    FloydSteinberg,
+    /// 4x4 Bayer matrix; each pixel's level depends only on its own value, so video does not
+    /// shimmer.
+    Ordered,
+}
+
+/// Thresholds of the 4x4 Bayer matrix, in sixteenths.
+const BAYER: [[u8; 4]; 4] = [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]];
+
+impl Dither {
+    /// Quantizes row-major luma to `levels` values that a glyph engine mapping `y * levels / 256`
+    /// draws as distinct glyphs. Fewer than two levels leave the data as is.
+    pub fn apply(&self, data: &mut [u8], width: usize, height: usize, levels: usize) {
+        if levels < 2 || width == 0 {
+            return;
+        }
+
+        let steps: f32 = (levels - 1) as f32;
+        // Each level is written as the middle of the luma range the engine maps to it.
+        let level_value = |level: usize| ((level * 256 + 128) / levels).min(255) as u8;
+
+        match self {
+            Dither::FloydSteinberg => {
+                let mut values: Vec<f32> = data.iter().map(|v: &u8| *v as f32).collect();
+
+                for y in 0..height {
+                    for x in 0..width {
+                        let i: usize = y * width + x;
+                        let level: usize =
+                            (values[i].clamp(0.0, 255.0) / 255.0 * steps).round() as usize;
+                        let error: f32 = values[i] - level as f32 * 255.0 / steps;
+
+                        data[i] = level_value(level);
+                        for (dx, dy, weight) in
+                            [(1, 0, 7.0), (-1, 1, 3.0), (0, 1, 5.0), (1, 1, 1.0)]
+                        {
+                            let (nx, ny): (isize, usize) = (x as isize + dx, y + dy);
+
+                            if (0..width as isize).contains(&nx) && ny < height {
+                                values[ny * width + nx as usize] += error * weight / 16.0;
+                            }
+                        }
+                    }
+                }
+            }
+            Dither::Ordered => {
+                for (i, value) in data.iter_mut().enumerate().take(width * height) {
+                    let threshold: f32 = (BAYER[i / width % 4][i % width % 4] as f32 + 0.5) / 16.0;
+                    let level: f32 = (*value as f32 / 255.0 * steps + threshold - 0.5).round();
+
+                    *value = level_value(level.clamp(0.0, steps) as usize);
+                }
+            }
+        }
+    }
+}
+
+impl FromStr for Dither {
+    type Err = UnknownDitherError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "floyd-steinberg" | "fs" => Ok(Dither::FloydSteinberg),
+            "ordered" | "bayer" => Ok(Dither::Ordered),
+            _ => Err(UnknownDitherError(s.into())),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct UnknownDitherError(pub String);
+
+impl fmt::Display for UnknownDitherError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown dither '{}'", self.0)
+    }
+}
+
+impl std::error::Error for UnknownDitherError {}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::entities::dither::Dither;
+
+    /// The glyph an engine with `levels` glyphs would pick for each value.
+    fn glyph_levels(data: &[u8], levels: usize) -> Vec<usize> {
+        data.iter()
+            .map(|v: &u8| *v as usize * levels / 256)
+            .collect()
+    }
+
+    #[test]
+    fn mid_gray_becomes_an_even_mix_of_two_glyphs() {
+        for dither in [Dither::FloydSteinberg, Dither::Ordered] {
+            let mut data: Vec<u8> = vec![128; 16];
+
+            dither.apply(&mut data, 4, 4, 2);
+
+            let bright: usize = glyph_levels(&data, 2).iter().sum();
+            assert_eq!(bright, 8, "{dither:?}");
+        }
+    }
+
+    #[test]
+    fn extremes_keep_their_glyphs() {
+        for dither in [Dither::FloydSteinberg, Dither::Ordered] {
+            let mut data: Vec<u8> = vec![0, 255, 0, 255];
+
+            dither.apply(&mut data, 2, 2, 10);
+
+            assert_eq!(glyph_levels(&data, 10), vec![0, 9, 0, 9], "{dither:?}");
+        }
+    }
+
+    #[test]
+    fn parses_names() {
+        assert_eq!("fs".parse::<Dither>(), Ok(Dither::FloydSteinberg));
+        assert_eq!("bayer".parse::<Dither>(), Ok(Dither::Ordered));
+        assert!("noise".parse::<Dither>().is_err());
+    }
+}
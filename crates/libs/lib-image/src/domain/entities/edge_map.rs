@@ -0,0 +1,407 @@
+use std::collections::VecDeque;
+
+use crate::domain::entities::luma_image::LumaImage;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum EdgeOperator {
+    Sobel,
+    Scharr,
+}
+
+impl EdgeOperator {
+    /// Smoothing weights of the separable 3x3 kernel and their sum, used to normalize magnitudes
+    /// back into luma units.
+    fn smoothing(&self) -> ([f32; 3], f32) {
+        match self {
+            EdgeOperator::Sobel => ([1.0, 2.0, 1.0], 4.0),
+            EdgeOperator::Scharr => ([3.0, 10.0, 3.0], 16.0),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum EdgeDetector {
+    /// Keeps every pixel whose gradient magnitude reaches `threshold`.
+    Gradient {
+        operator: EdgeOperator,
+        threshold: f32,
+    },
+    /// Gaussian smoothing, non-maximum suppression and hysteresis between `low` and `high`.
+    Canny {
+        operator: EdgeOperator,
+        low: f32,
+        high: f32,
+    },
+}
+
+/// Orientation of the edge line itself, perpendicular to the luma gradient.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum EdgeDirection {
+    Horizontal,
+    Vertical,
+    /// Rising from bottom-left to top-right.
+    DiagonalUp,
+    /// Falling from top-left to bottom-right.
+    DiagonalDown,
+}
+
+impl EdgeDirection {
+    fn from_gradient(gx: f32, gy: f32) -> Self {
+        let angle: f32 = gy.atan2(gx).to_degrees().rem_euclid(180.0);
+
+        match angle {
+            a if !(22.5..157.5).contains(&a) => EdgeDirection::Vertical,
+            a if a < 67.5 => EdgeDirection::DiagonalUp,
+            a if a < 112.5 => EdgeDirection::Horizontal,
+            _ => EdgeDirection::DiagonalDown,
+        }
+    }
+
+    pub fn as_char(&self) -> char {
+        match self {
+            EdgeDirection::Horizontal => '-',
+            EdgeDirection::Vertical => '|',
+            EdgeDirection::DiagonalUp => '/',
+            EdgeDirection::DiagonalDown => '\\',
+        }
+    }
+
+    /// Neighbour offsets along the gradient, used for non-maximum suppression.
+    fn gradient_offsets(&self) -> [(isize, isize); 2] {
+        match self {
+            EdgeDirection::Vertical => [(-1, 0), (1, 0)],
+            EdgeDirection::Horizontal => [(0, -1), (0, 1)],
+            EdgeDirection::DiagonalUp => [(-1, -1), (1, 1)],
+            EdgeDirection::DiagonalDown => [(1, -1), (-1, 1)],
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct EdgeMap {
+    width: usize,
+    height: usize,
+    edges: Vec<Option<EdgeDirection>>,
+}
+
+impl EdgeMap {
+    pub fn detect(image: &LumaImage, detector: &EdgeDetector) -> Self {
+        match detector {
+            EdgeDetector::Gradient {
+                operator,
+                threshold,
+            } => Self::gradient(image, operator, *threshold),
+            EdgeDetector::Canny {
+                operator,
+                low,
+                high,
+            } => Self::canny(image, operator, *low, *high),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<EdgeDirection> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+
+        self.edges[y * self.width + x]
+    }
+
+    fn gradient(image: &LumaImage, operator: &EdgeOperator, threshold: f32) -> Self {
+        let gradients: Vec<(f32, f32)> = gradients(
+            &to_f32(&image.data()),
+            image.width(),
+            image.height(),
+            operator,
+        );
+
+        Self {
+            width: image.width(),
+            height: image.height(),
+            edges: gradients
+                .iter()
+                .map(|(gx, gy)| {
+                    (gx.hypot(*gy) >= threshold).then(|| EdgeDirection::from_gradient(*gx, *gy))
+                })
+                .collect(),
+        }
+    }
+
+    fn canny(image: &LumaImage, operator: &EdgeOperator, low: f32, high: f32) -> Self {
+        let (width, height): (usize, usize) = (image.width(), image.height());
+        let smoothed: Vec<f32> = gaussian_blur(&to_f32(&image.data()), width, height);
+        let gradients: Vec<(f32, f32)> = gradients(&smoothed, width, height, operator);
+        let magnitudes: Vec<f32> = gradients.iter().map(|(gx, gy)| gx.hypot(*gy)).collect();
+        let directions: Vec<EdgeDirection> = gradients
+            .iter()
+            .map(|(gx, gy)| EdgeDirection::from_gradient(*gx, *gy))
+            .collect();
+        let magnitude_at = |x: isize, y: isize| -> f32 {
+            if x < 0 || y < 0 || x >= width as isize || y >= height as isize {
+                0.0
+            } else {
+                magnitudes[y as usize * width + x as usize]
+            }
+        };
+
+        let mut strength: Vec<u8> = vec![0; width * height];
+
+        for y in 0..height {
+            for x in 0..width {
+                let index: usize = y * width + x;
+                let magnitude: f32 = magnitudes[index];
+                let is_local_maximum: bool = directions[index]
+                    .gradient_offsets()
+                    .iter()
+                    .all(|(dx, dy)| magnitude >= magnitude_at(x as isize + dx, y as isize + dy));
+
+                if is_local_maximum && magnitude >= high {
+                    strength[index] = 2;
+                } else if is_local_maximum && magnitude >= low {
+                    strength[index] = 1;
+                }
+            }
+        }
+
+        let mut edges: Vec<Option<EdgeDirection>> = vec![None; width * height];
+        let mut queue: VecDeque<usize> =
+            (0..width * height).filter(|i| strength[*i] == 2).collect();
+
+        while let Some(index) = queue.pop_front() {
+            if edges[index].is_some() {
+                continue;
+            }
+
+            edges[index] = Some(directions[index]);
+
+            let (x, y) = ((index % width) as isize, (index / width) as isize);
+
+            for (dx, dy) in NEIGHBOURS {
+                let (nx, ny) = (x + dx, y + dy);
+
+                if nx < 0 || ny < 0 || nx >= width as isize || ny >= height as isize {
+                    continue;
+                }
+
+                let neighbour: usize = ny as usize * width + nx as usize;
+
+                if strength[neighbour] > 0 && edges[neighbour].is_none() {
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+
+        Self {
+            width,
+            height,
+            edges,
+        }
+    }
+}
+
+const NEIGHBOURS: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+fn to_f32(data: &[u8]) -> Vec<f32> {
+    data.iter().map(|v| *v as f32).collect()
+}
+
+/// Reads `values` with edge pixels replicated outside the image.
+fn sample(values: &[f32], width: usize, height: usize, x: isize, y: isize) -> f32 {
+    let x: usize = x.clamp(0, width as isize - 1) as usize;
+    let y: usize = y.clamp(0, height as isize - 1) as usize;
+
+    values[y * width + x]
+}
+
+fn gradients(
+    values: &[f32],
+    width: usize,
+    height: usize,
+    operator: &EdgeOperator,
+) -> Vec<(f32, f32)> {
+    let (weights, weight_sum) = operator.smoothing();
+    let mut result: Vec<(f32, f32)> = Vec::with_capacity(width * height);
+
+    for y in 0..height as isize {
+        for x in 0..width as isize {
+            let (mut gx, mut gy): (f32, f32) = (0.0, 0.0);
+
+            for (i, weight) in weights.iter().enumerate() {
+                let offset: isize = i as isize - 1;
+
+                gx += weight
+                    * (sample(values, width, height, x + 1, y + offset)
+                        - sample(values, width, height, x - 1, y + offset));
+                gy += weight
+                    * (sample(values, width, height, x + offset, y + 1)
+                        - sample(values, width, height, x + offset, y - 1));
+            }
+
+            result.push((gx / weight_sum, gy / weight_sum));
+        }
+    }
+
+    result
+}
+
+/// Separable 5-tap binomial approximation of a Gaussian with sigma ~1.
+fn gaussian_blur(values: &[f32], width: usize, height: usize) -> Vec<f32> {
+    const KERNEL: [f32; 5] = [1.0 / 16.0, 4.0 / 16.0, 6.0 / 16.0, 4.0 / 16.0, 1.0 / 16.0];
+
+    let horizontal: Vec<f32> = (0..width * height)
+        .map(|i| {
+            let (x, y) = ((i % width) as isize, (i / width) as isize);
+
+            KERNEL
+                .iter()
+                .enumerate()
+                .map(|(k, w)| w * sample(values, width, height, x + k as isize - 2, y))
+                .sum()
+        })
+        .collect();
+
+    (0..width * height)
+        .map(|i| {
+            let (x, y) = ((i % width) as isize, (i / width) as isize);
+
+            KERNEL
+                .iter()
+                .enumerate()
+                .map(|(k, w)| w * sample(&horizontal, width, height, x, y + k as isize - 2))
+                .sum()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::entities::{
+        edge_map::{EdgeDetector, EdgeDirection, EdgeMap, EdgeOperator},
+        luma_image::LumaImage,
+    };
+
+    fn image_from_fn(width: usize, height: usize, f: impl Fn(usize, usize) -> u8) -> LumaImage {
+        LumaImage::from_luma_data(
+            width,
+            height,
+            (0..width * height)
+                .map(|i| f(i % width, i / width))
+                .collect(),
+        )
+    }
+
+    fn render(map: &EdgeMap) -> String {
+        (0..map.height())
+            .map(|y| {
+                (0..map.width())
+                    .map(|x| map.get(x, y).map(|d| d.as_char()).unwrap_or('.'))
+                    .collect::<String>()
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+
+    #[test]
+    fn should_map_gradients_to_edge_characters() {
+        let directions: Vec<((f32, f32), char)> = vec![
+            ((1.0, 0.0), '|'),
+            ((-1.0, 0.0), '|'),
+            ((0.0, 1.0), '-'),
+            ((1.0, 1.0), '/'),
+            ((1.0, -1.0), '\\'),
+        ];
+
+        for ((gx, gy), expected) in directions {
+            assert_eq!(EdgeDirection::from_gradient(gx, gy).as_char(), expected);
+        }
+    }
+
+    #[test]
+    fn sobel_should_detect_vertical_step() {
+        let image: LumaImage = image_from_fn(6, 3, |x, _| if x < 3 { 0 } else { 255 });
+
+        let map: EdgeMap = EdgeMap::detect(
+            &image,
+            &EdgeDetector::Gradient {
+                operator: EdgeOperator::Sobel,
+                threshold: 64.0,
+            },
+        );
+
+        assert_eq!(render(&map), "..||..\n..||..\n..||..");
+    }
+
+    #[test]
+    fn scharr_should_detect_horizontal_step() {
+        let image: LumaImage = image_from_fn(3, 6, |_, y| if y < 3 { 0 } else { 255 });
+
+        let map: EdgeMap = EdgeMap::detect(
+            &image,
+            &EdgeDetector::Gradient {
+                operator: EdgeOperator::Scharr,
+                threshold: 64.0,
+            },
+        );
+
+        assert_eq!(render(&map), "...\n...\n---\n---\n...\n...");
+    }
+
+    #[test]
+    fn canny_should_thin_edges_to_single_line() {
+        let image: LumaImage = image_from_fn(10, 4, |x, _| if x < 5 { 0 } else { 255 });
+
+        let map: EdgeMap = EdgeMap::detect(
+            &image,
+            &EdgeDetector::Canny {
+                operator: EdgeOperator::Sobel,
+                low: 20.0,
+                high: 60.0,
+            },
+        );
+
+        for y in 0..4 {
+            let edge_count: usize = (0..10).filter(|x| map.get(*x, y).is_some()).count();
+
+            assert!(edge_count <= 2, "row {y} has {edge_count} edge pixels");
+            assert_eq!(
+                map.get(4, y).or(map.get(5, y)),
+                Some(EdgeDirection::Vertical)
+            );
+        }
+    }
+
+    #[test]
+    fn canny_should_ignore_flat_images() {
+        let image: LumaImage = image_from_fn(4, 4, |_, _| 128);
+
+        let map: EdgeMap = EdgeMap::detect(
+            &image,
+            &EdgeDetector::Canny {
+                operator: EdgeOperator::Scharr,
+                low: 1.0,
+                high: 2.0,
+            },
+        );
+
+        assert_eq!(render(&map), "....\n....\n....\n....");
+    }
+}
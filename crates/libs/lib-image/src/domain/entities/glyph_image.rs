@@ -1,9 +1,65 @@
-trait GlyphImage {
+use lib_core::domain::entities::Glyph;
+
+use crate::domain::entities::image_buffer::InvalidImageDataError;
+
+/// A raster of interleaved 8-bit samples that can be rendered as glyphs.
+///
+/// Rows may be padded: row `y` starts at `y * get_stride()` in [`GlyphImage::get_image_data`].
+pub trait GlyphImage: Glyph + Sized {
+    type Pixel;
+
+    /// Samples per pixel.
+    const CHANNELS: usize;
+
+    /// Builds an image from tightly packed rows, padding or truncating `data` to fit.
+    fn from_image_data(data: Vec<u8>, width: usize, height: usize) -> Self;
+
+    /// Takes ownership of rows that start `stride` bytes apart, without repacking them.
+    fn from_strided_image_data(
+        data: Vec<u8>,
+        width: usize,
+        height: usize,
+        stride: usize,
+    ) -> Result<Self, InvalidImageDataError>;
+
+    /// The underlying buffer, including any row padding.
     fn get_image_data(&self) -> &[u8];
 
-    fn from_image_data(data: Vec<u8>, width: u32, height: u32) -> Self;
+    fn get_width(&self) -> usize;
+
+    fn get_height(&self) -> usize;
+
+    /// Distance in bytes between the starts of two consecutive rows.
+    fn get_stride(&self) -> usize;
+
+    fn pixel_from_samples(samples: &[u8]) -> Self::Pixel;
+
+    /// Samples of row `y`, without padding.
+    fn row(&self, y: usize) -> &[u8] {
+        let start: usize = y * self.get_stride();
+
+        &self.get_image_data()[start..start + self.get_width() * Self::CHANNELS]
+    }
+
+    fn rows(&self) -> impl Iterator<Item = &[u8]> {
+        (0..self.get_height()).map(move |y| self.row(y))
+    }
+
+    fn pixel(&self, x: usize, y: usize) -> Option<Self::Pixel> {
+        if x >= self.get_width() || y >= self.get_height() {
+            return None;
+        }
 
-    fn get_width(&self) -> u32;
+        Some(Self::pixel_from_samples(
+            &self.row(y)[x * Self::CHANNELS..(x + 1) * Self::CHANNELS],
+        ))
+    }
 
-    fn get_height(&self) -> u32;
+    /// Pixels in row-major order.
+    fn pixels(&self) -> impl Iterator<Item = Self::Pixel> {
+        self.rows().flat_map(|row| {
+            row.chunks_exact(Self::CHANNELS)
+                .map(Self::pixel_from_samples)
+        })
+    }
 }
@@ -0,0 +1,367 @@
+use std::{borrow::Cow, fmt};
+
+use crate::domain::entities::{placement::Rect, resample_filter::ResampleFilter};
+
+/// Interleaved 8-bit samples, `channels` per pixel, with rows `stride` bytes apart.
+#[derive(Debug, PartialEq, Clone)]
+pub(crate) struct ImageBuffer {
+    width: usize,
+    height: usize,
+    channels: usize,
+    stride: usize,
+    data: Vec<u8>,
+}
+
+impl ImageBuffer {
+    pub fn new(
+        width: usize,
+        height: usize,
+        channels: usize,
+    ) -> Result<Self, InvalidImageDataError> {
+        Self::from_packed(Vec::new(), width, height, channels)
+    }
+
+    /// Wraps tightly packed rows, padding or truncating `data` to the expected length.
+    pub fn from_packed(
+        mut data: Vec<u8>,
+        width: usize,
+        height: usize,
+        channels: usize,
+    ) -> Result<Self, InvalidImageDataError> {
+        data.resize(sample_count(width, height, channels)?, 0);
+
+        Ok(Self {
+            width,
+            height,
+            channels,
+            stride: width * channels,
+            data,
+        })
+    }
+
+    pub fn from_strided(
+        data: Vec<u8>,
+        width: usize,
+        height: usize,
+        channels: usize,
+        stride: usize,
+    ) -> Result<Self, InvalidImageDataError> {
+        if channels == 0 {
+            return Err(InvalidImageDataError::ZeroChannels);
+        }
+
+        let overflow: InvalidImageDataError = InvalidImageDataError::SizeOverflow {
+            width,
+            height,
+            channels,
+        };
+        let row_len: usize = width.checked_mul(channels).ok_or(overflow.clone())?;
+
+        if stride < row_len {
+            return Err(InvalidImageDataError::StrideTooSmall { stride, row_len });
+        }
+
+        // The last row does not need its padding.
+        let required: usize = match height {
+            0 => 0,
+            _ => stride
+                .checked_mul(height - 1)
+                .and_then(|padded: usize| padded.checked_add(row_len))
+                .ok_or(overflow)?,
+        };
+
+        if data.len() < required {
+            return Err(InvalidImageDataError::BufferTooSmall {
+                len: data.len(),
+                required,
+            });
+        }
+
+        Ok(Self {
+            width,
+            height,
+            channels,
+            stride,
+            data,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn stride(&self) -> usize {
+        self.stride
+    }
+
+    pub fn raw(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn row(&self, y: usize) -> &[u8] {
+        let start: usize = y * self.stride;
+
+        &self.data[start..start + self.width * self.channels]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [u8] {
+        let start: usize = y * self.stride;
+        let end: usize = start + self.width * self.channels;
+
+        &mut self.data[start..end]
+    }
+
+    pub fn is_packed(&self) -> bool {
+        self.stride == self.width * self.channels
+            && self.data.len() == self.width * self.height * self.channels
+    }
+
+    /// Row-major samples without padding, borrowed when the buffer is already packed.
+    pub fn packed(&self) -> Cow<'_, [u8]> {
+        if self.is_packed() {
+            return Cow::Borrowed(&self.data);
+        }
+
+        Cow::Owned(
+            (0..self.height)
+                .flat_map(|y| self.row(y).iter().copied())
+                .collect(),
+        )
+    }
+
+    /// Drops row padding so the samples can be processed as one contiguous slice.
+    pub fn packed_mut(&mut self) -> &mut [u8] {
+        if !self.is_packed() {
+            self.data = self.packed().into_owned();
+            self.stride = self.width * self.channels;
+        }
+
+        &mut self.data
+    }
+
+    pub fn crop(&self, rect: Rect) -> Self {
+        let x: usize = rect.x.min(self.width);
+        let y: usize = rect.y.min(self.height);
+        let width: usize = rect.width.min(self.width - x);
+        let height: usize = rect.height.min(self.height - y);
+        let data: Vec<u8> = (y..y + height)
+            .flat_map(|row| &self.row(row)[x * self.channels..(x + width) * self.channels])
+            .copied()
+            .collect();
+
+        Self::from_packed(data, width, height, self.channels)
+            .expect("a crop is no larger than the buffer it was cut from")
+    }
+
+    /// Resamples to `width` by `height` pixels, which fails when they cannot be addressed.
+    pub fn resize(
+        &self,
+        width: usize,
+        height: usize,
+        filter: ResampleFilter,
+    ) -> Result<Self, InvalidImageDataError> {
+        let data: Vec<u8> = filter.resize(
+            &self.packed(),
+            self.width,
+            self.height,
+            self.channels,
+            width,
+            height,
+        )?;
+
+        Self::from_packed(data, width, height, self.channels)
+    }
+
+    /// Converts every pixel with `f`, which receives and fills `channels`-sized slices.
+    ///
+    /// # Panics
+    ///
+    /// When the converted image would not fit in memory.
+    pub fn map_pixels(&self, channels: usize, f: impl Fn(&[u8], &mut [u8])) -> Self {
+        let mut result: ImageBuffer = ImageBuffer::new(self.width, self.height, channels)
+            .expect("converted image does not fit in memory");
+
+        for y in 0..self.height {
+            let source: &[u8] = self.row(y);
+
+            for (input, output) in source
+                .chunks_exact(self.channels)
+                .zip(result.row_mut(y).chunks_exact_mut(channels))
+            {
+                f(input, output);
+            }
+        }
+
+        result
+    }
+}
+
+/// Samples in `width` by `height` pixels of `channels` each, if that many can be addressed.
+pub(crate) fn sample_count(
+    width: usize,
+    height: usize,
+    channels: usize,
+) -> Result<usize, InvalidImageDataError> {
+    if channels == 0 {
+        return Err(InvalidImageDataError::ZeroChannels);
+    }
+
+    width
+        .checked_mul(height)
+        .and_then(|pixels: usize| pixels.checked_mul(channels))
+        .ok_or(InvalidImageDataError::SizeOverflow {
+            width,
+            height,
+            channels,
+        })
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum InvalidImageDataError {
+    StrideTooSmall {
+        stride: usize,
+        row_len: usize,
+    },
+    BufferTooSmall {
+        len: usize,
+        required: usize,
+    },
+    /// The dimensions describe more samples than can be addressed.
+    SizeOverflow {
+        width: usize,
+        height: usize,
+        channels: usize,
+    },
+    /// Pixels were given no samples at all.
+    ZeroChannels,
+}
+
+impl fmt::Display for InvalidImageDataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidImageDataError::StrideTooSmall { stride, row_len } => {
+                write!(
+                    f,
+                    "stride {stride} is smaller than a row of {row_len} bytes"
+                )
+            }
+            InvalidImageDataError::BufferTooSmall { len, required } => {
+                write!(
+                    f,
+                    "buffer of {len} bytes is smaller than the required {required}"
+                )
+            }
+            InvalidImageDataError::SizeOverflow {
+                width,
+                height,
+                channels,
+            } => {
+                write!(
+                    f,
+                    "{width}x{height} pixels of {channels} channels do not fit in memory"
+                )
+            }
+            InvalidImageDataError::ZeroChannels => write!(f, "pixels must have a channel"),
+        }
+    }
+}
+
+impl std::error::Error for InvalidImageDataError {}
+
+#[cfg(test)]
+mod test {
+    use std::borrow::Cow;
+
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::entities::{
+        image_buffer::{ImageBuffer, InvalidImageDataError},
+        resample_filter::ResampleFilter,
+    };
+
+    #[test]
+    fn should_read_rows_through_stride() {
+        let buffer: ImageBuffer =
+            ImageBuffer::from_strided(vec![1, 2, 0, 0, 3, 4], 2, 2, 1, 4).unwrap();
+
+        assert_eq!(buffer.row(0), &[1, 2]);
+        assert_eq!(buffer.row(1), &[3, 4]);
+        assert_eq!(buffer.packed(), Cow::<[u8]>::Owned(vec![1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn should_borrow_packed_data() {
+        let buffer: ImageBuffer = ImageBuffer::from_packed(vec![1, 2, 3, 4], 2, 2, 1).unwrap();
+
+        assert!(matches!(buffer.packed(), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn should_drop_padding_when_packing_in_place() {
+        let mut buffer: ImageBuffer =
+            ImageBuffer::from_strided(vec![1, 2, 9, 3, 4, 9], 2, 2, 1, 3).unwrap();
+
+        assert_eq!(buffer.packed_mut(), &[1, 2, 3, 4]);
+        assert_eq!(buffer.stride(), 2);
+    }
+
+    #[test]
+    fn should_reject_sizes_that_overflow() {
+        let overflow = |width: usize, height: usize, channels: usize| {
+            Err(InvalidImageDataError::SizeOverflow {
+                width,
+                height,
+                channels,
+            })
+        };
+
+        assert_eq!(
+            ImageBuffer::from_strided(vec![0; 16], usize::MAX / 2, 1, 3, usize::MAX),
+            overflow(usize::MAX / 2, 1, 3)
+        );
+        assert_eq!(
+            ImageBuffer::from_strided(vec![0; 16], 1, usize::MAX, 1, 2),
+            overflow(1, usize::MAX, 1)
+        );
+        assert_eq!(
+            ImageBuffer::from_strided(vec![0; 16], usize::MAX / 4, 3, 2, usize::MAX / 2),
+            overflow(usize::MAX / 4, 3, 2)
+        );
+        assert_eq!(
+            ImageBuffer::new(usize::MAX / 2, 3, 1),
+            overflow(usize::MAX / 2, 3, 1)
+        );
+        assert_eq!(
+            ImageBuffer::from_packed(vec![1, 2, 3, 4], 2, 2, 1)
+                .unwrap()
+                .resize(usize::MAX, 2, ResampleFilter::Box),
+            overflow(usize::MAX, 2, 1)
+        );
+        assert_eq!(
+            ImageBuffer::from_packed(Vec::new(), 2, 2, 0),
+            Err(InvalidImageDataError::ZeroChannels)
+        );
+    }
+
+    #[test]
+    fn should_reject_invalid_strides() {
+        assert_eq!(
+            ImageBuffer::from_strided(vec![0; 12], 2, 2, 3, 4),
+            Err(InvalidImageDataError::StrideTooSmall {
+                stride: 4,
+                row_len: 6
+            })
+        );
+        assert_eq!(
+            ImageBuffer::from_strided(vec![0; 8], 2, 2, 3, 8),
+            Err(InvalidImageDataError::BufferTooSmall {
+                len: 8,
+                required: 14
+            })
+        );
+    }
+}
@@ -0,0 +1,116 @@
+use std::fmt;
+
+use lib_core::domain::entities::{Glyph, GlyphRenderingEngine};
+
+use crate::domain::entities::{edge_map::EdgeMap, luma_image::LumaImage};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum LineArtMode {
+    /// Edges only, everything else is blank.
+    Outline,
+    /// Edges drawn over the regular luma fill.
+    Hybrid,
+}
+
+/// Renders detected edges with direction characters (`-`, `|`, `/`, `\`).
+#[derive(Debug)]
+pub struct LineArtImage {
+    edges: EdgeMap,
+    fill: LumaImage,
+    mode: LineArtMode,
+}
+
+impl LineArtImage {
+    pub fn new(edges: EdgeMap, fill: LumaImage, mode: LineArtMode) -> Self {
+        Self { edges, fill, mode }
+    }
+}
+
+impl Glyph for LineArtImage {
+    fn render_into(
+        &self,
+        engine: &dyn GlyphRenderingEngine,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        let (width, height): (usize, usize) = (self.fill.width(), self.fill.height());
+        let fill = self.fill.data();
+
+        for y in 0..height {
+            if y > 0 {
+                out.write_char('\n')?;
+            }
+
+            for x in 0..width {
+                out.write_char(match (self.edges.get(x, y), self.mode) {
+                    (Some(direction), _) => direction.as_char(),
+                    (None, LineArtMode::Outline) => ' ',
+                    (None, LineArtMode::Hybrid) => {
+                        engine.get_char_based_on_luma_value(fill[y * width + x])
+                    }
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn size_hint(&self, engine: &dyn GlyphRenderingEngine) -> usize {
+        self.fill.size_hint(engine)
+    }
+
+    fn add_child(&mut self, _child: Box<dyn Glyph>) {}
+
+    fn get_child_mut(&mut self, _index: usize) -> Option<&mut dyn Glyph> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use lib_core::domain::entities::{Glyph, GlyphRenderingEngine};
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::entities::{
+        edge_map::{EdgeDetector, EdgeMap, EdgeOperator},
+        line_art_image::{LineArtImage, LineArtMode},
+        luma_image::LumaImage,
+    };
+
+    fn step_image() -> LumaImage {
+        LumaImage::from_luma_data(4, 2, vec![0, 0, 255, 255, 0, 0, 255, 255])
+    }
+
+    fn step_edges(image: &LumaImage) -> EdgeMap {
+        EdgeMap::detect(
+            image,
+            &EdgeDetector::Gradient {
+                operator: EdgeOperator::Sobel,
+                threshold: 64.0,
+            },
+        )
+    }
+
+    #[test]
+    fn outline_mode_should_only_draw_edges() {
+        let image: LumaImage = step_image();
+        let art: LineArtImage = LineArtImage::new(step_edges(&image), image, LineArtMode::Outline);
+
+        assert_eq!(art.as_text(&FillEngine), " || \n || ");
+    }
+
+    #[test]
+    fn hybrid_mode_should_draw_edges_over_fill() {
+        let image: LumaImage = step_image();
+        let art: LineArtImage = LineArtImage::new(step_edges(&image), image, LineArtMode::Hybrid);
+
+        assert_eq!(art.as_text(&FillEngine), "a||b\na||b");
+    }
+
+    struct FillEngine;
+
+    impl GlyphRenderingEngine for FillEngine {
+        fn get_char_based_on_luma_value(&self, y: u8) -> char {
+            if y < 128 { 'a' } else { 'b' }
+        }
+    }
+}
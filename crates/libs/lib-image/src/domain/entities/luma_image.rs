@@ -1,37 +1,246 @@
-use lib_core::domain::entities::{Glyph, GlyphGrid, GlyphRenderingEngine};
+use std::{borrow::Cow, fmt};
 
-#[derive(Debug)]
+use lib_core::domain::entities::{Glyph, GlyphRenderingEngine};
+
+use crate::domain::entities::{
+    dither::Dither,
+    edge_map::{EdgeDetector, EdgeMap},
+    glyph_image::GlyphImage,
+    image_buffer::{ImageBuffer, InvalidImageDataError},
+    luma_pixel::LumaPixel,
+    placement::Rect,
+    resample_filter::ResampleFilter,
+    rgb_image::RgbImage,
+    rgb_pixel::RgbPixel,
+    rgba_image::RgbaImage,
+    text_raster,
+    tone_adjustment::ToneAdjustment,
+};
+
+#[derive(Debug, PartialEq, Clone)]
 pub struct LumaImage {
-    width: usize,
-    height: usize,
-    pixels: GlyphGrid,
+    buffer: ImageBuffer,
+    filled: usize,
 }
 
 impl LumaImage {
+    /// A black image; panics when `width * height` pixels do not fit in memory.
     pub fn new(width: usize, height: usize) -> Self {
         Self {
-            width,
-            height,
-            pixels: GlyphGrid::new(width, height),
+            buffer: ImageBuffer::new(width, height, 1).expect("image does not fit in memory"),
+            filled: 0,
+        }
+    }
+
+    /// Builds an image from row-major luma values, padding or truncating to `width * height`.
+    /// Panics when `width * height` pixels do not fit in memory.
+    pub fn from_luma_data(width: usize, height: usize, data: Vec<u8>) -> Self {
+        Self::from_buffer(
+            ImageBuffer::from_packed(data, width, height, 1).expect("image does not fit in memory"),
+        )
+    }
+
+    /// Draws rendered glyph text, one [`TEXT_CELL_WIDTH`] by [`TEXT_CELL_HEIGHT`] cell per
+    /// character, in a grid of `columns` by `rows` cells.
+    ///
+    /// [`TEXT_CELL_WIDTH`]: text_raster::TEXT_CELL_WIDTH
+    /// [`TEXT_CELL_HEIGHT`]: text_raster::TEXT_CELL_HEIGHT
+    pub fn from_text(text: &str, columns: usize, rows: usize) -> Self {
+        text_raster::rasterize(text, columns, rows)
+    }
+
+    pub(crate) fn from_buffer(buffer: ImageBuffer) -> Self {
+        Self {
+            filled: buffer.width() * buffer.height(),
+            buffer,
         }
     }
 
+    pub fn width(&self) -> usize {
+        self.buffer.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.buffer.height()
+    }
+
+    /// Row-major luma values without row padding.
+    pub fn data(&self) -> Cow<'_, [u8]> {
+        self.buffer.packed()
+    }
+
     pub fn is_full(&self) -> bool {
-        self.pixels.elements_count >= self.width * self.height
+        self.filled >= self.width() * self.height()
+    }
+
+    /// Fills the next pixel in row-major order; pixels past the last one are dropped.
+    pub fn push_pixel(&mut self, pixel: LumaPixel) {
+        if self.is_full() {
+            return;
+        }
+
+        let (x, y): (usize, usize) = (self.filled % self.width(), self.filled / self.width());
+
+        self.buffer.row_mut(y)[x] = pixel.y;
+        self.filled += 1;
+    }
+
+    pub fn adjust(mut self, adjustment: &ToneAdjustment) -> Self {
+        let (width, height): (usize, usize) = (self.width(), self.height());
+
+        adjustment.apply(self.buffer.packed_mut(), width, height);
+        self
+    }
+
+    pub fn adjust_all<'a>(self, adjustments: impl IntoIterator<Item = &'a ToneAdjustment>) -> Self {
+        adjustments
+            .into_iter()
+            .fold(self, |image: LumaImage, adjustment: &ToneAdjustment| {
+                image.adjust(adjustment)
+            })
+    }
+
+    pub fn brightness(self, offset: i16) -> Self {
+        self.adjust(&ToneAdjustment::Brightness(offset))
+    }
+
+    pub fn contrast(self, factor: f32) -> Self {
+        self.adjust(&ToneAdjustment::Contrast(factor))
+    }
+
+    pub fn gamma(self, gamma: f32) -> Self {
+        self.adjust(&ToneAdjustment::Gamma(gamma))
+    }
+
+    pub fn levels(self, black: u8, white: u8) -> Self {
+        self.adjust(&ToneAdjustment::Levels { black, white })
+    }
+
+    pub fn invert(self) -> Self {
+        self.adjust(&ToneAdjustment::Invert)
+    }
+
+    pub fn equalize(self) -> Self {
+        self.adjust(&ToneAdjustment::Equalize)
+    }
+
+    pub fn clahe(self, tiles: usize, clip_limit: f32) -> Self {
+        self.adjust(&ToneAdjustment::Clahe { tiles, clip_limit })
+    }
+
+    /// Quantizes to the `levels` glyphs of a ramp with `dither`; see [`Dither::apply`].
+    pub fn dither(mut self, levels: usize, dither: Dither) -> Self {
+        let (width, height): (usize, usize) = (self.width(), self.height());
+
+        dither.apply(self.buffer.packed_mut(), width, height, levels);
+        self
+    }
+
+    /// Resamples to `width` by `height` pixels, which fails when they do not fit in memory.
+    pub fn resize(
+        &self,
+        width: usize,
+        height: usize,
+        filter: ResampleFilter,
+    ) -> Result<Self, InvalidImageDataError> {
+        self.buffer
+            .resize(width, height, filter)
+            .map(Self::from_buffer)
+    }
+
+    /// Copies the part of the image covered by `rect`, clipped to the image bounds.
+    pub fn crop(&self, rect: Rect) -> Self {
+        Self::from_buffer(self.buffer.crop(rect))
+    }
+
+    pub fn detect_edges(&self, detector: &EdgeDetector) -> EdgeMap {
+        EdgeMap::detect(self, detector)
+    }
+}
+
+impl GlyphImage for LumaImage {
+    type Pixel = LumaPixel;
+
+    const CHANNELS: usize = 1;
+
+    fn from_image_data(data: Vec<u8>, width: usize, height: usize) -> Self {
+        Self::from_luma_data(width, height, data)
+    }
+
+    fn from_strided_image_data(
+        data: Vec<u8>,
+        width: usize,
+        height: usize,
+        stride: usize,
+    ) -> Result<Self, InvalidImageDataError> {
+        ImageBuffer::from_strided(data, width, height, 1, stride).map(Self::from_buffer)
+    }
+
+    fn get_image_data(&self) -> &[u8] {
+        self.buffer.raw()
+    }
+
+    fn get_width(&self) -> usize {
+        self.buffer.width()
+    }
+
+    fn get_height(&self) -> usize {
+        self.buffer.height()
+    }
+
+    fn get_stride(&self) -> usize {
+        self.buffer.stride()
+    }
+
+    fn pixel_from_samples(samples: &[u8]) -> LumaPixel {
+        LumaPixel::new(samples[0])
+    }
+}
+
+impl From<&RgbImage> for LumaImage {
+    fn from(image: &RgbImage) -> Self {
+        Self::from_buffer(image.buffer().map_pixels(1, |rgb, luma| {
+            luma[0] = LumaPixel::from_rgb(RgbPixel::new(rgb[0], rgb[1], rgb[2])).y;
+        }))
+    }
+}
+
+impl From<&RgbaImage> for LumaImage {
+    /// Drops the alpha channel; see [`RgbaImage`] for compositing first.
+    fn from(image: &RgbaImage) -> Self {
+        Self::from(&RgbImage::from(image))
     }
 }
 
 impl Glyph for LumaImage {
-    fn as_text(&self, engine: &dyn GlyphRenderingEngine) -> String {
-        self.pixels.as_text(engine)
+    fn render_into(
+        &self,
+        engine: &dyn GlyphRenderingEngine,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                out.write_char('\n')?;
+            }
+
+            for y in row {
+                out.write_char(engine.get_char_based_on_luma_value(*y))?;
+            }
+        }
+
+        Ok(())
     }
 
-    fn add_child(&mut self, child: Box<dyn Glyph>) {
-        self.pixels.add_child(child);
+    /// A byte per cell; charsets beyond ASCII take a few more.
+    fn size_hint(&self, _engine: &dyn GlyphRenderingEngine) -> usize {
+        (self.width() + 1) * self.height()
     }
 
-    fn get_child_mut(&mut self, _index: usize) -> Option<&mut Box<dyn Glyph>> {
-        todo!()
+    /// Glyphs carry no luma value; pixels are filled in with [`LumaImage::push_pixel`].
+    fn add_child(&mut self, _child: Box<dyn Glyph>) {}
+
+    fn get_child_mut(&mut self, _index: usize) -> Option<&mut dyn Glyph> {
+        None
     }
 }
 
@@ -40,20 +249,109 @@ mod test {
     use lib_core::domain::entities::{Glyph, GlyphRenderingEngine};
     use pretty_assertions::assert_eq;
 
-    use crate::domain::entities::{luma_image::LumaImage, luma_pixel::LumaPixel};
+    use crate::domain::entities::{
+        glyph_image::GlyphImage, image_buffer::InvalidImageDataError, luma_image::LumaImage,
+        luma_pixel::LumaPixel, placement::Rect, resample_filter::ResampleFilter,
+        rgb_image::RgbImage,
+    };
 
     #[test]
     fn should_operate_as_glyph_composite() {
         let engine: DummyEngine = DummyEngine;
         let mut image: LumaImage = LumaImage::new(1, 1);
 
-        image.add_child(Box::new(LumaPixel::new(255)));
-        image.add_child(Box::new(LumaPixel::new(255)));
-        image.add_child(Box::new(LumaPixel::new(255)));
+        image.push_pixel(LumaPixel::new(255));
+        image.push_pixel(LumaPixel::new(255));
+        image.push_pixel(LumaPixel::new(255));
 
         assert_eq!(image.as_text(&engine), "X");
     }
 
+    #[test]
+    fn should_fill_pixels_in_row_major_order() {
+        let mut image: LumaImage = LumaImage::new(3, 2);
+
+        for y in 0..6 {
+            assert!(!image.is_full());
+            image.push_pixel(LumaPixel::new(y));
+        }
+
+        assert!(image.is_full());
+        assert_eq!(image.as_text(&DigitEngine), "012\n345");
+    }
+
+    #[test]
+    fn should_chain_adjustments() {
+        let image: LumaImage = LumaImage::from_luma_data(3, 1, vec![0, 1, 2])
+            .brightness(2)
+            .invert()
+            .levels(250, 253);
+
+        assert_eq!(*image.data(), [255, 170, 85]);
+    }
+
+    #[test]
+    fn should_resize_with_filter() {
+        let image: LumaImage =
+            LumaImage::from_luma_data(4, 2, vec![0, 0, 100, 100, 0, 0, 100, 100]);
+
+        let result: LumaImage = image.resize(2, 1, ResampleFilter::Box).unwrap();
+
+        assert_eq!(result, LumaImage::from_luma_data(2, 1, vec![0, 100]));
+        assert_eq!(
+            image.resize(usize::MAX, 2, ResampleFilter::Box),
+            Err(InvalidImageDataError::SizeOverflow {
+                width: usize::MAX,
+                height: 2,
+                channels: 1
+            })
+        );
+    }
+
+    #[test]
+    fn should_crop_within_bounds() {
+        let image: LumaImage = LumaImage::from_luma_data(3, 3, (0..9).collect());
+
+        assert_eq!(
+            image.crop(Rect::new(1, 1, 5, 5)),
+            LumaImage::from_luma_data(2, 2, vec![4, 5, 7, 8])
+        );
+    }
+
+    #[test]
+    fn should_read_strided_rows_and_pixels() {
+        let image: LumaImage =
+            LumaImage::from_strided_image_data(vec![1, 2, 0, 3, 4, 0], 2, 2, 3).unwrap();
+
+        assert_eq!(image.row(1), &[3, 4]);
+        assert_eq!(image.pixel(1, 0), Some(LumaPixel::new(2)));
+        assert_eq!(image.pixel(2, 0), None);
+        assert_eq!(
+            image.pixels().map(|p| p.y).collect::<Vec<u8>>(),
+            vec![1, 2, 3, 4]
+        );
+        assert_eq!(image.as_text(&DigitEngine), "12\n34");
+    }
+
+    #[test]
+    fn should_adjust_strided_images() {
+        let image: LumaImage = LumaImage::from_strided_image_data(vec![1, 9, 2], 1, 2, 2)
+            .unwrap()
+            .brightness(1);
+
+        assert_eq!(image, LumaImage::from_luma_data(1, 2, vec![2, 3]));
+    }
+
+    #[test]
+    fn should_convert_from_rgb() {
+        let image: RgbImage = RgbImage::from_image_data(vec![255, 255, 255, 255, 0, 0], 2, 1);
+
+        assert_eq!(
+            LumaImage::from(&image),
+            LumaImage::from_luma_data(2, 1, vec![255, 54])
+        );
+    }
+
     struct DummyEngine;
 
     impl GlyphRenderingEngine for DummyEngine {
@@ -61,4 +359,12 @@ mod test {
             'X'
         }
     }
+
+    struct DigitEngine;
+
+    impl GlyphRenderingEngine for DigitEngine {
+        fn get_char_based_on_luma_value(&self, y: u8) -> char {
+            char::from_digit(y as u32, 10).unwrap()
+        }
+    }
 }
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use lib_core::domain::entities::{Glyph, GlyphRenderingEngine};
 
 use crate::domain::entities::rgb_pixel::RgbPixel;
@@ -12,7 +14,7 @@ impl LumaPixel {
         Self { y }
     }
 
-    pub(crate) fn from_rgb(rgb: RgbPixel) -> Self {
+    pub fn from_rgb(rgb: RgbPixel) -> Self {
         let (r, g, b) = (rgb.r, rgb.g, rgb.b);
 
         let luma_value: u8 = ((2126 * r as u32 + 7152 * g as u32 + 722 * b as u32) / 10000) as u8;
@@ -21,13 +23,17 @@ impl LumaPixel {
 }
 
 impl Glyph for LumaPixel {
-    fn as_text(&self, renderer: &dyn GlyphRenderingEngine) -> String {
-        renderer.get_char_based_on_luma_value(self.y).to_string()
+    fn render_into(
+        &self,
+        renderer: &dyn GlyphRenderingEngine,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        out.write_char(renderer.get_char_based_on_luma_value(self.y))
     }
 
     fn add_child(&mut self, _child: Box<dyn Glyph>) {}
 
-    fn get_child_mut(&mut self, _index: usize) -> Option<&mut Box<dyn Glyph>> {
+    fn get_child_mut(&mut self, _index: usize) -> Option<&mut dyn Glyph> {
         None
     }
 }
@@ -0,0 +1,191 @@
+use std::fmt;
+
+use lib_core::domain::entities::{Glyph, GlyphRenderingEngine};
+
+use crate::domain::entities::placement::{PlacementLayout, Rect};
+
+/// What the uncovered part of a canvas is drawn with.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PlacementFill {
+    /// A literal glyph, independent of the rendering engine.
+    Glyph(char),
+    /// A luma value rendered through the engine like any other pixel.
+    Luma(u8),
+}
+
+impl Default for PlacementFill {
+    fn default() -> Self {
+        PlacementFill::Glyph(' ')
+    }
+}
+
+/// A glyph positioned on a larger canvas, as produced by a [`PlacementLayout`].
+///
+/// The content is expected to render as `target.height` lines of `target.width` cells.
+#[derive(Debug)]
+pub struct PlacedImage {
+    content: Box<dyn Glyph>,
+    target: Rect,
+    canvas_width: usize,
+    canvas_height: usize,
+    fill: PlacementFill,
+}
+
+impl PlacedImage {
+    pub fn new(content: Box<dyn Glyph>, layout: &PlacementLayout, fill: PlacementFill) -> Self {
+        Self {
+            content,
+            target: layout.target,
+            canvas_width: layout.canvas_width,
+            canvas_height: layout.canvas_height,
+            fill,
+        }
+    }
+
+    pub fn content(&self) -> &dyn Glyph {
+        self.content.as_ref()
+    }
+
+    fn write_fill(
+        &self,
+        engine: &dyn GlyphRenderingEngine,
+        out: &mut dyn fmt::Write,
+        cells: usize,
+    ) -> fmt::Result {
+        let glyph: char = match self.fill {
+            PlacementFill::Glyph(glyph) => glyph,
+            PlacementFill::Luma(luma) => engine.get_char_based_on_luma_value(luma),
+        };
+
+        (0..cells).try_for_each(|_| out.write_char(glyph))
+    }
+}
+
+impl Glyph for PlacedImage {
+    fn render_into(
+        &self,
+        engine: &dyn GlyphRenderingEngine,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        let content: String = self.content.as_text(engine);
+        let mut content_lines = content.lines();
+        let right: usize = self
+            .canvas_width
+            .saturating_sub(self.target.x + self.target.width);
+
+        for y in 0..self.canvas_height {
+            if y > 0 {
+                out.write_char('\n')?;
+            }
+
+            if y < self.target.y || y >= self.target.y + self.target.height {
+                self.write_fill(engine, out, self.canvas_width)?;
+                continue;
+            }
+
+            self.write_fill(engine, out, self.target.x)?;
+            out.write_str(content_lines.next().unwrap_or_default())?;
+            self.write_fill(engine, out, right)?;
+        }
+
+        Ok(())
+    }
+
+    /// The content's size, and a byte for every fill cell and line break around it.
+    fn size_hint(&self, engine: &dyn GlyphRenderingEngine) -> usize {
+        let canvas: usize = (self.canvas_width + 1) * self.canvas_height;
+
+        self.content.size_hint(engine)
+            + canvas.saturating_sub((self.target.width + 1) * self.target.height)
+    }
+
+    fn add_child(&mut self, _child: Box<dyn Glyph>) {}
+
+    fn get_child_mut(&mut self, _index: usize) -> Option<&mut dyn Glyph> {
+        None
+    }
+
+    /// Only the content can be transparent; the fill around it is always drawn.
+    fn is_transparent_at(&self, x: usize, y: usize) -> bool {
+        let target: Rect = self.target;
+
+        (target.x..target.x + target.width).contains(&x)
+            && (target.y..target.y + target.height).contains(&y)
+            && self.content.is_transparent_at(x - target.x, y - target.y)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use lib_core::domain::entities::{Glyph, GlyphRenderingEngine};
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::entities::{
+        luma_image::LumaImage,
+        placed_image::{PlacedImage, PlacementFill},
+        placement::{FitMode, Placement},
+        resample_filter::ResampleFilter,
+    };
+
+    #[test]
+    fn should_centre_contained_image_with_fill_glyph() {
+        let image: LumaImage = LumaImage::from_luma_data(2, 1, vec![255, 255]);
+
+        let placed: PlacedImage = Placement::default()
+            .apply(
+                &image,
+                4,
+                4,
+                ResampleFilter::Nearest,
+                PlacementFill::Glyph('.'),
+            )
+            .unwrap();
+
+        assert_eq!(placed.as_text(&ThresholdEngine), "....\n####\n####\n....");
+    }
+
+    #[test]
+    fn should_render_luma_fill_through_engine() {
+        let image: LumaImage = LumaImage::from_luma_data(1, 2, vec![255, 255]);
+
+        let placed: PlacedImage = Placement::default()
+            .apply(
+                &image,
+                3,
+                2,
+                ResampleFilter::Nearest,
+                PlacementFill::Luma(0),
+            )
+            .unwrap();
+
+        assert_eq!(placed.as_text(&ThresholdEngine), " # \n # ");
+    }
+
+    #[test]
+    fn cover_should_fill_whole_canvas() {
+        let image: LumaImage = LumaImage::from_luma_data(4, 1, vec![0, 255, 255, 0]);
+
+        let placed: PlacedImage = Placement {
+            mode: FitMode::Cover,
+            ..Placement::default()
+        }
+        .apply(
+            &image,
+            2,
+            2,
+            ResampleFilter::Nearest,
+            PlacementFill::Glyph('.'),
+        )
+        .unwrap();
+
+        assert_eq!(placed.as_text(&ThresholdEngine), "##\n##");
+    }
+
+    struct ThresholdEngine;
+
+    impl GlyphRenderingEngine for ThresholdEngine {
+        fn get_char_based_on_luma_value(&self, y: u8) -> char {
+            if y > 127 { '#' } else { ' ' }
+        }
+    }
+}
@@ -0,0 +1,322 @@
+use std::{fmt, str::FromStr};
+
+use crate::domain::entities::{
+    image_buffer::InvalidImageDataError, luma_image::LumaImage, placed_image::PlacedImage,
+    placed_image::PlacementFill, resample_filter::ResampleFilter,
+};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum FitMode {
+    /// Fits the whole image inside the canvas, centred, with letterbox or pillarbox bars.
+    Contain,
+    /// Fills the whole canvas, cropping whatever overflows.
+    Cover,
+    /// Fills the whole canvas, ignoring the aspect ratio.
+    Stretch,
+}
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Rect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Rect {
+    pub fn new(x: usize, y: usize, width: usize, height: usize) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+        }
+    }
+}
+
+impl FromStr for Rect {
+    type Err = InvalidRectError;
+
+    /// Parses `x,y,width,height`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let values: Vec<usize> = s
+            .split(',')
+            .map(|v| v.trim().parse::<usize>())
+            .collect::<Result<Vec<usize>, _>>()
+            .map_err(|_| InvalidRectError(s.into()))?;
+
+        match values.as_slice() {
+            [x, y, width, height] => Ok(Rect::new(*x, *y, *width, *height)),
+            _ => Err(InvalidRectError(s.into())),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct InvalidRectError(pub String);
+
+impl fmt::Display for InvalidRectError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected 'x,y,width,height', got '{}'", self.0)
+    }
+}
+
+impl std::error::Error for InvalidRectError {}
+
+/// Where a source image lands on a canvas of glyph cells.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Placement {
+    pub mode: FitMode,
+    /// Region of the source to show, in source pixels.
+    pub crop: Option<Rect>,
+    /// Magnification applied on top of the crop; values below `1.0` are treated as `1.0`.
+    pub zoom: f32,
+    /// Position of the visible region inside the available slack, from `-1.0` (left/top) to
+    /// `1.0` (right/bottom).
+    pub pan: (f32, f32),
+    /// Height of a glyph cell divided by its width.
+    pub cell_aspect: f32,
+}
+
+impl Default for Placement {
+    fn default() -> Self {
+        Self {
+            mode: FitMode::Contain,
+            crop: None,
+            zoom: 1.0,
+            pan: (0.0, 0.0),
+            cell_aspect: 1.0,
+        }
+    }
+}
+
+/// Result of resolving a [`Placement`] for a given source and canvas size.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct PlacementLayout {
+    /// Region of the source image that is sampled.
+    pub source: Rect,
+    /// Region of the canvas the sampled source is scaled into.
+    pub target: Rect,
+    pub canvas_width: usize,
+    pub canvas_height: usize,
+}
+
+impl PlacementLayout {
+    /// Whether the whole source is used, so a scaler can resize it without cropping first.
+    pub fn uses_full_source(&self, source_width: usize, source_height: usize) -> bool {
+        self.source == Rect::new(0, 0, source_width, source_height)
+    }
+}
+
+impl Placement {
+    pub fn layout(
+        &self,
+        source_width: usize,
+        source_height: usize,
+        canvas_width: usize,
+        canvas_height: usize,
+    ) -> PlacementLayout {
+        let full_canvas: Rect = Rect::new(0, 0, canvas_width, canvas_height);
+        let empty: PlacementLayout = PlacementLayout {
+            source: Rect::new(0, 0, source_width, source_height),
+            target: Rect::new(0, 0, 0, 0),
+            canvas_width,
+            canvas_height,
+        };
+
+        if source_width == 0 || source_height == 0 || canvas_width == 0 || canvas_height == 0 {
+            return empty;
+        }
+
+        let (mut x, mut y, mut width, mut height): (f32, f32, f32, f32) = match self.crop {
+            Some(crop) => {
+                let x: usize = crop.x.min(source_width - 1);
+                let y: usize = crop.y.min(source_height - 1);
+
+                (
+                    x as f32,
+                    y as f32,
+                    crop.width.clamp(1, source_width - x) as f32,
+                    crop.height.clamp(1, source_height - y) as f32,
+                )
+            }
+            None => (0.0, 0.0, source_width as f32, source_height as f32),
+        };
+
+        let zoom: f32 = self.zoom.max(1.0);
+        (x, width) = pan_within(x, width, width / zoom, self.pan.0);
+        (y, height) = pan_within(y, height, height / zoom, self.pan.1);
+
+        let (canvas_w, canvas_h): (f32, f32) = (canvas_width as f32, canvas_height as f32);
+        let cell_aspect: f32 = self.cell_aspect.max(f32::EPSILON);
+        // Source extent measured in cells, so the aspect comparison accounts for tall glyphs.
+        let (cells_w, cells_h): (f32, f32) = (width, height / cell_aspect);
+
+        let target: Rect = match self.mode {
+            FitMode::Stretch => full_canvas,
+            FitMode::Contain => {
+                let scale: f32 = (canvas_w / cells_w).min(canvas_h / cells_h);
+                let target_w: usize = ((cells_w * scale).round() as usize).clamp(1, canvas_width);
+                let target_h: usize = ((cells_h * scale).round() as usize).clamp(1, canvas_height);
+
+                Rect::new(
+                    (canvas_width - target_w) / 2,
+                    (canvas_height - target_h) / 2,
+                    target_w,
+                    target_h,
+                )
+            }
+            FitMode::Cover => {
+                let scale: f32 = (canvas_w / cells_w).max(canvas_h / cells_h);
+                (x, width) = pan_within(x, width, canvas_w / scale, self.pan.0);
+                (y, height) = pan_within(y, height, canvas_h / scale * cell_aspect, self.pan.1);
+
+                full_canvas
+            }
+        };
+
+        let source_x: usize = (x.round() as usize).min(source_width - 1);
+        let source_y: usize = (y.round() as usize).min(source_height - 1);
+
+        PlacementLayout {
+            source: Rect::new(
+                source_x,
+                source_y,
+                (width.round() as usize).clamp(1, source_width - source_x),
+                (height.round() as usize).clamp(1, source_height - source_y),
+            ),
+            target,
+            canvas_width,
+            canvas_height,
+        }
+    }
+
+    /// Crops, resizes and positions `image` on a `canvas_width x canvas_height` canvas, which
+    /// fails when the canvas does not fit in memory.
+    pub fn apply(
+        &self,
+        image: &LumaImage,
+        canvas_width: usize,
+        canvas_height: usize,
+        filter: ResampleFilter,
+        fill: PlacementFill,
+    ) -> Result<PlacedImage, InvalidImageDataError> {
+        let layout: PlacementLayout =
+            self.layout(image.width(), image.height(), canvas_width, canvas_height);
+        let content: LumaImage =
+            image
+                .crop(layout.source)
+                .resize(layout.target.width, layout.target.height, filter)?;
+
+        Ok(PlacedImage::new(Box::new(content), &layout, fill))
+    }
+}
+
+/// Shrinks `start..start + length` to `visible`, positioned by `pan` within the removed slack.
+fn pan_within(start: f32, length: f32, visible: f32, pan: f32) -> (f32, f32) {
+    let visible: f32 = visible.min(length);
+    let offset: f32 = (length - visible) * (pan.clamp(-1.0, 1.0) + 1.0) / 2.0;
+
+    (start + offset, visible)
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::entities::placement::{FitMode, Placement, PlacementLayout, Rect};
+
+    fn placement(mode: FitMode) -> Placement {
+        Placement {
+            mode,
+            ..Placement::default()
+        }
+    }
+
+    #[test]
+    fn contain_should_letterbox_wide_sources() {
+        let layout: PlacementLayout = placement(FitMode::Contain).layout(200, 100, 40, 40);
+
+        assert_eq!(layout.source, Rect::new(0, 0, 200, 100));
+        assert_eq!(layout.target, Rect::new(0, 10, 40, 20));
+    }
+
+    #[test]
+    fn contain_should_pillarbox_tall_sources() {
+        let layout: PlacementLayout = placement(FitMode::Contain).layout(100, 200, 40, 40);
+
+        assert_eq!(layout.target, Rect::new(10, 0, 20, 40));
+    }
+
+    #[test]
+    fn contain_should_account_for_cell_aspect() {
+        let layout: PlacementLayout = Placement {
+            cell_aspect: 2.0,
+            ..Placement::default()
+        }
+        .layout(100, 100, 80, 24);
+
+        assert_eq!(layout.target, Rect::new(16, 0, 48, 24));
+    }
+
+    #[test]
+    fn cover_should_crop_centred_overflow() {
+        let layout: PlacementLayout = placement(FitMode::Cover).layout(200, 100, 40, 40);
+
+        assert_eq!(layout.source, Rect::new(50, 0, 100, 100));
+        assert_eq!(layout.target, Rect::new(0, 0, 40, 40));
+    }
+
+    #[test]
+    fn cover_should_follow_pan() {
+        let layout: PlacementLayout = Placement {
+            mode: FitMode::Cover,
+            pan: (1.0, 0.0),
+            ..Placement::default()
+        }
+        .layout(200, 100, 40, 40);
+
+        assert_eq!(layout.source, Rect::new(100, 0, 100, 100));
+    }
+
+    #[test]
+    fn stretch_should_fill_canvas_with_whole_source() {
+        let layout: PlacementLayout = placement(FitMode::Stretch).layout(200, 100, 40, 40);
+
+        assert_eq!(layout.source, Rect::new(0, 0, 200, 100));
+        assert_eq!(layout.target, Rect::new(0, 0, 40, 40));
+        assert!(layout.uses_full_source(200, 100));
+    }
+
+    #[test]
+    fn crop_and_zoom_should_narrow_source_region() {
+        let layout: PlacementLayout = Placement {
+            mode: FitMode::Stretch,
+            crop: Some(Rect::new(100, 0, 100, 100)),
+            zoom: 2.0,
+            pan: (-1.0, 1.0),
+            ..Placement::default()
+        }
+        .layout(200, 100, 10, 10);
+
+        assert_eq!(layout.source, Rect::new(100, 50, 50, 50));
+    }
+
+    #[test]
+    fn crop_should_be_clamped_to_source() {
+        let layout: PlacementLayout = Placement {
+            mode: FitMode::Stretch,
+            crop: Some(Rect::new(150, 90, 500, 500)),
+            ..Placement::default()
+        }
+        .layout(200, 100, 10, 10);
+
+        assert_eq!(layout.source, Rect::new(150, 90, 50, 10));
+    }
+
+    #[test]
+    fn should_parse_rects() {
+        assert_eq!("1, 2,3,4".parse(), Ok(Rect::new(1, 2, 3, 4)));
+        assert!("1,2,3".parse::<Rect>().is_err());
+    }
+}
@@ -0,0 +1,337 @@
+use std::{f32::consts::PI, fmt, str::FromStr};
+
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+use crate::domain::entities::image_buffer::{InvalidImageDataError, sample_count};
+
+/// Reconstruction filter used when resizing images.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ResampleFilter {
+    Nearest,
+    /// Area average; the usual choice for large downscales.
+    Box,
+    Bilinear,
+    /// Catmull-Rom cubic.
+    Bicubic,
+    Lanczos3,
+}
+
+impl ResampleFilter {
+    /// Kernel radius in source pixels at a 1:1 scale.
+    fn support(&self) -> f32 {
+        match self {
+            ResampleFilter::Nearest | ResampleFilter::Box => 0.5,
+            ResampleFilter::Bilinear => 1.0,
+            ResampleFilter::Bicubic => 2.0,
+            ResampleFilter::Lanczos3 => 3.0,
+        }
+    }
+
+    fn weight(&self, x: f32) -> f32 {
+        let x: f32 = x.abs();
+
+        match self {
+            ResampleFilter::Nearest | ResampleFilter::Box => {
+                if x <= 0.5 {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+            ResampleFilter::Bilinear => (1.0 - x).max(0.0),
+            ResampleFilter::Bicubic => {
+                const A: f32 = -0.5;
+
+                if x < 1.0 {
+                    (A + 2.0) * x.powi(3) - (A + 3.0) * x.powi(2) + 1.0
+                } else if x < 2.0 {
+                    A * x.powi(3) - 5.0 * A * x.powi(2) + 8.0 * A * x - 4.0 * A
+                } else {
+                    0.0
+                }
+            }
+            ResampleFilter::Lanczos3 => {
+                if x < f32::EPSILON {
+                    1.0
+                } else if x < 3.0 {
+                    let px: f32 = PI * x;
+
+                    3.0 * px.sin() * (px / 3.0).sin() / (px * px)
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+
+    /// Resizes interleaved 8-bit samples with `channels` values per pixel.
+    ///
+    /// The two axes are filtered separately; when downscaling the kernel is widened by the
+    /// scale factor so every source pixel contributes to the result. `data` must hold at least
+    /// `width * height * channels` samples, and `channels` must not be zero.
+    pub fn resize(
+        &self,
+        data: &[u8],
+        width: usize,
+        height: usize,
+        channels: usize,
+        new_width: usize,
+        new_height: usize,
+    ) -> Result<Vec<u8>, InvalidImageDataError> {
+        let required: usize = sample_count(width, height, channels)?;
+        let new_len: usize = sample_count(new_width, new_height, channels)?;
+
+        if data.len() < required {
+            return Err(InvalidImageDataError::BufferTooSmall {
+                len: data.len(),
+                required,
+            });
+        }
+        if width == 0 || height == 0 || new_width == 0 || new_height == 0 {
+            return Ok(vec![0; new_len]);
+        }
+
+        let columns: Vec<Contributions> = self.contributions(width, new_width);
+        let rows: Vec<Contributions> = self.contributions(height, new_height);
+        let mut horizontal: Vec<f32> = vec![0.0; new_width * height * channels];
+
+        for_each_row(&mut horizontal, new_width * channels, |y, out| {
+            let source: &[u8] = &data[y * width * channels..(y + 1) * width * channels];
+
+            for (x, contribution) in columns.iter().enumerate() {
+                for c in 0..channels {
+                    out[x * channels + c] = contribution
+                        .weights
+                        .iter()
+                        .enumerate()
+                        .map(|(i, w)| w * source[(contribution.start + i) * channels + c] as f32)
+                        .sum();
+                }
+            }
+        });
+
+        let mut result: Vec<u8> = vec![0; new_len];
+
+        for_each_row(&mut result, new_width * channels, |y, out| {
+            let contribution: &Contributions = &rows[y];
+
+            for (x, value) in out.iter_mut().enumerate() {
+                let sum: f32 = contribution
+                    .weights
+                    .iter()
+                    .enumerate()
+                    .map(|(i, w)| {
+                        w * horizontal[(contribution.start + i) * new_width * channels + x]
+                    })
+                    .sum();
+
+                *value = sum.round().clamp(0.0, 255.0) as u8;
+            }
+        });
+
+        Ok(result)
+    }
+
+    fn contributions(&self, source: usize, target: usize) -> Vec<Contributions> {
+        let scale: f32 = source as f32 / target as f32;
+
+        (0..target)
+            .map(|i| {
+                let centre: f32 = (i as f32 + 0.5) * scale;
+
+                if *self == ResampleFilter::Nearest {
+                    return Contributions {
+                        start: (centre as usize).min(source - 1),
+                        weights: vec![1.0],
+                    };
+                }
+
+                let filter_scale: f32 = scale.max(1.0);
+                let radius: f32 = self.support() * filter_scale;
+                let start: usize = (centre - radius).floor().max(0.0) as usize;
+                let end: usize = ((centre + radius).ceil() as usize).min(source);
+                let mut weights: Vec<f32> = (start..end)
+                    .map(|j| match self {
+                        // Exact overlap of the source pixel with the output footprint.
+                        ResampleFilter::Box => (((j + 1) as f32).min(centre + radius)
+                            - (j as f32).max(centre - radius))
+                        .max(0.0),
+                        _ => self.weight((j as f32 + 0.5 - centre) / filter_scale),
+                    })
+                    .collect();
+                let total: f32 = weights.iter().sum();
+
+                if total.abs() > f32::EPSILON {
+                    weights.iter_mut().for_each(|w| *w /= total);
+                }
+
+                Contributions { start, weights }
+            })
+            .collect()
+    }
+}
+
+impl FromStr for ResampleFilter {
+    type Err = UnknownResampleFilterError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "nearest" => Ok(ResampleFilter::Nearest),
+            "box" | "area" => Ok(ResampleFilter::Box),
+            "bilinear" => Ok(ResampleFilter::Bilinear),
+            "bicubic" => Ok(ResampleFilter::Bicubic),
+            "lanczos3" | "lanczos" => Ok(ResampleFilter::Lanczos3),
+            _ => Err(UnknownResampleFilterError(s.into())),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct UnknownResampleFilterError(pub String);
+
+impl fmt::Display for UnknownResampleFilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown resample filter '{}'", self.0)
+    }
+}
+
+impl std::error::Error for UnknownResampleFilterError {}
+
+/// Source window and normalized weights for one output sample.
+#[derive(Debug)]
+struct Contributions {
+    start: usize,
+    weights: Vec<f32>,
+}
+
+#[cfg(feature = "parallel")]
+fn for_each_row<T: Send>(data: &mut [T], row_len: usize, f: impl Fn(usize, &mut [T]) + Sync) {
+    data.par_chunks_mut(row_len)
+        .enumerate()
+        .for_each(|(y, row)| f(y, row));
+}
+
+#[cfg(not(feature = "parallel"))]
+fn for_each_row<T: Send>(data: &mut [T], row_len: usize, f: impl Fn(usize, &mut [T]) + Sync) {
+    data.chunks_mut(row_len)
+        .enumerate()
+        .for_each(|(y, row)| f(y, row));
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::entities::{
+        image_buffer::InvalidImageDataError, resample_filter::ResampleFilter,
+    };
+
+    const ALL_FILTERS: [ResampleFilter; 5] = [
+        ResampleFilter::Nearest,
+        ResampleFilter::Box,
+        ResampleFilter::Bilinear,
+        ResampleFilter::Bicubic,
+        ResampleFilter::Lanczos3,
+    ];
+
+    #[test]
+    fn should_keep_flat_images_flat_at_any_ratio() {
+        let data: Vec<u8> = vec![77; 7 * 5];
+
+        for filter in ALL_FILTERS {
+            for (w, h) in [(3, 2), (7, 5), (11, 9), (1, 1)] {
+                let result: Vec<u8> = filter.resize(&data, 7, 5, 1, w, h).unwrap();
+
+                assert_eq!(result, vec![77; w * h], "{filter:?} at {w}x{h}");
+            }
+        }
+    }
+
+    #[test]
+    fn identity_resize_should_preserve_pixels() {
+        let data: Vec<u8> = (0..16).map(|v| v * 16).collect();
+
+        for filter in ALL_FILTERS {
+            assert_eq!(
+                filter.resize(&data, 4, 4, 1, 4, 4).unwrap(),
+                data,
+                "{filter:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn nearest_should_pick_source_pixels() {
+        let data: Vec<u8> = vec![10, 20, 30, 40, 50, 60];
+
+        assert_eq!(
+            ResampleFilter::Nearest
+                .resize(&data, 6, 1, 1, 3, 1)
+                .unwrap(),
+            vec![20, 40, 60]
+        );
+    }
+
+    #[test]
+    fn box_should_average_areas() {
+        let data: Vec<u8> = vec![0, 100, 200, 0, 0, 100, 200, 0];
+
+        assert_eq!(
+            ResampleFilter::Box.resize(&data, 4, 2, 1, 2, 1).unwrap(),
+            vec![50, 100]
+        );
+    }
+
+    #[test]
+    fn box_should_weight_partial_pixels_for_non_integer_ratios() {
+        let data: Vec<u8> = vec![0, 0, 255];
+
+        assert_eq!(
+            ResampleFilter::Box.resize(&data, 3, 1, 1, 2, 1).unwrap(),
+            vec![0, 170]
+        );
+    }
+
+    #[test]
+    fn should_resize_channels_independently() {
+        let data: Vec<u8> = vec![255, 0, 0, 255, 0, 0, 0, 0, 255, 0, 0, 255];
+
+        assert_eq!(
+            ResampleFilter::Bilinear
+                .resize(&data, 2, 2, 3, 1, 1)
+                .unwrap(),
+            vec![128, 0, 128]
+        );
+    }
+
+    #[test]
+    fn should_reject_short_or_overflowing_data() {
+        assert_eq!(
+            ResampleFilter::Bilinear.resize(&[0; 5], 2, 2, 3, 1, 1),
+            Err(InvalidImageDataError::BufferTooSmall {
+                len: 5,
+                required: 12
+            })
+        );
+        assert_eq!(
+            ResampleFilter::Box.resize(&[], 2, 2, 0, 1, 1),
+            Err(InvalidImageDataError::ZeroChannels)
+        );
+        assert_eq!(
+            ResampleFilter::Nearest.resize(&[0; 4], usize::MAX, 2, 1, 1, 1),
+            Err(InvalidImageDataError::SizeOverflow {
+                width: usize::MAX,
+                height: 2,
+                channels: 1
+            })
+        );
+    }
+
+    #[test]
+    fn should_parse_filter_names() {
+        assert_eq!("lanczos3".parse(), Ok(ResampleFilter::Lanczos3));
+        assert_eq!("area".parse(), Ok(ResampleFilter::Box));
+        assert!("sinc".parse::<ResampleFilter>().is_err());
+    }
+}
@@ -0,0 +1,258 @@
+use std::{borrow::Cow, fmt};
+
+use lib_core::domain::entities::{Glyph, GlyphRenderingEngine};
+
+use crate::domain::entities::{
+    glyph_image::GlyphImage,
+    image_buffer::{ImageBuffer, InvalidImageDataError},
+    luma_image::LumaImage,
+    luma_pixel::LumaPixel,
+    placement::Rect,
+    resample_filter::ResampleFilter,
+    rgb_pixel::RgbPixel,
+    rgba_image::RgbaImage,
+    tone_adjustment::ToneAdjustment,
+};
+
+/// Interleaved 8-bit `R, G, B` image.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RgbImage {
+    buffer: ImageBuffer,
+}
+
+impl RgbImage {
+    /// A black image; panics when `width * height` pixels do not fit in memory.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::from_buffer(ImageBuffer::new(width, height, 3).expect("image does not fit in memory"))
+    }
+
+    /// Builds an image from row-major `R, G, B` triples, padding or truncating to fit.
+    /// Panics when `width * height` pixels do not fit in memory.
+    pub fn from_rgb_data(width: usize, height: usize, data: Vec<u8>) -> Self {
+        Self::from_buffer(
+            ImageBuffer::from_packed(data, width, height, 3).expect("image does not fit in memory"),
+        )
+    }
+
+    pub(crate) fn from_buffer(buffer: ImageBuffer) -> Self {
+        Self { buffer }
+    }
+
+    pub(crate) fn buffer(&self) -> &ImageBuffer {
+        &self.buffer
+    }
+
+    pub fn width(&self) -> usize {
+        self.buffer.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.buffer.height()
+    }
+
+    /// Row-major samples without row padding.
+    pub fn data(&self) -> Cow<'_, [u8]> {
+        self.buffer.packed()
+    }
+
+    pub fn adjust(mut self, adjustment: &ToneAdjustment) -> Self {
+        let (width, height): (usize, usize) = (self.width(), self.height());
+
+        adjustment.apply_color(self.buffer.packed_mut(), width, height, 3);
+        self
+    }
+
+    pub fn adjust_all<'a>(self, adjustments: impl IntoIterator<Item = &'a ToneAdjustment>) -> Self {
+        adjustments
+            .into_iter()
+            .fold(self, |image: RgbImage, adjustment: &ToneAdjustment| {
+                image.adjust(adjustment)
+            })
+    }
+
+    /// Resamples to `width` by `height` pixels, which fails when they do not fit in memory.
+    pub fn resize(
+        &self,
+        width: usize,
+        height: usize,
+        filter: ResampleFilter,
+    ) -> Result<Self, InvalidImageDataError> {
+        self.buffer
+            .resize(width, height, filter)
+            .map(Self::from_buffer)
+    }
+
+    /// Copies the part of the image covered by `rect`, clipped to the image bounds.
+    pub fn crop(&self, rect: Rect) -> Self {
+        Self::from_buffer(self.buffer.crop(rect))
+    }
+}
+
+impl GlyphImage for RgbImage {
+    type Pixel = RgbPixel;
+
+    const CHANNELS: usize = 3;
+
+    fn from_image_data(data: Vec<u8>, width: usize, height: usize) -> Self {
+        Self::from_rgb_data(width, height, data)
+    }
+
+    fn from_strided_image_data(
+        data: Vec<u8>,
+        width: usize,
+        height: usize,
+        stride: usize,
+    ) -> Result<Self, InvalidImageDataError> {
+        ImageBuffer::from_strided(data, width, height, 3, stride).map(Self::from_buffer)
+    }
+
+    fn get_image_data(&self) -> &[u8] {
+        self.buffer.raw()
+    }
+
+    fn get_width(&self) -> usize {
+        self.buffer.width()
+    }
+
+    fn get_height(&self) -> usize {
+        self.buffer.height()
+    }
+
+    fn get_stride(&self) -> usize {
+        self.buffer.stride()
+    }
+
+    fn pixel_from_samples(samples: &[u8]) -> RgbPixel {
+        RgbPixel::new(samples[0], samples[1], samples[2])
+    }
+}
+
+impl From<&LumaImage> for RgbImage {
+    fn from(image: &LumaImage) -> Self {
+        let data: Vec<u8> = image.data().iter().flat_map(|y| [*y; 3]).collect();
+
+        Self::from_rgb_data(image.width(), image.height(), data)
+    }
+}
+
+impl From<&RgbaImage> for RgbImage {
+    /// Drops the alpha channel.
+    fn from(image: &RgbaImage) -> Self {
+        Self::from_buffer(image.buffer().map_pixels(3, |rgba, rgb| {
+            rgb.copy_from_slice(&rgba[..3]);
+        }))
+    }
+}
+
+impl Glyph for RgbImage {
+    fn render_into(
+        &self,
+        engine: &dyn GlyphRenderingEngine,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        write_color_rows(self.rows(), 3, engine, out)
+    }
+
+    fn size_hint(&self, engine: &dyn GlyphRenderingEngine) -> usize {
+        color_size_hint(self.width(), self.height(), engine)
+    }
+
+    fn add_child(&mut self, _child: Box<dyn Glyph>) {}
+
+    fn get_child_mut(&mut self, _index: usize) -> Option<&mut dyn Glyph> {
+        None
+    }
+}
+
+/// Writes rows of interleaved samples whose first three channels are `R, G, B`.
+pub(crate) fn write_color_rows<'a>(
+    rows: impl Iterator<Item = &'a [u8]>,
+    channels: usize,
+    engine: &dyn GlyphRenderingEngine,
+    out: &mut dyn fmt::Write,
+) -> fmt::Result {
+    for (y, row) in rows.enumerate() {
+        if y > 0 {
+            out.write_char('\n')?;
+        }
+
+        for p in row.chunks_exact(channels) {
+            let y: u8 = LumaPixel::from_rgb(RgbPixel::new(p[0], p[1], p[2])).y;
+
+            engine.write_text_based_on_rgb_value(out, p[0], p[1], p[2], y)?;
+        }
+
+        out.write_str(engine.get_style_reset())?;
+    }
+
+    Ok(())
+}
+
+/// Bytes a `width` by `height` image of colored cells is expected to take.
+pub(crate) fn color_size_hint(
+    width: usize,
+    height: usize,
+    engine: &dyn GlyphRenderingEngine,
+) -> usize {
+    (width * engine.colored_cell_size_hint() + engine.get_style_reset().len() + 1) * height
+}
+
+#[cfg(test)]
+mod test {
+    use lib_core::domain::entities::{Glyph, GlyphRenderingEngine};
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::entities::{
+        glyph_image::GlyphImage, luma_image::LumaImage, rgb_image::RgbImage, rgb_pixel::RgbPixel,
+        tone_adjustment::ToneAdjustment,
+    };
+
+    #[test]
+    fn should_iterate_pixels_through_stride() {
+        let image: RgbImage =
+            RgbImage::from_strided_image_data(vec![1, 2, 3, 0, 4, 5, 6, 0], 1, 2, 4).unwrap();
+
+        assert_eq!(
+            image.pixels().collect::<Vec<RgbPixel>>(),
+            vec![RgbPixel::new(1, 2, 3), RgbPixel::new(4, 5, 6)]
+        );
+        assert_eq!(*image.data(), [1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn should_expand_luma_into_gray() {
+        let image: RgbImage = RgbImage::from(&LumaImage::from_luma_data(2, 1, vec![7, 9]));
+
+        assert_eq!(image, RgbImage::from_rgb_data(2, 1, vec![7, 7, 7, 9, 9, 9]));
+    }
+
+    #[test]
+    fn should_render_colors_through_engine() {
+        let image: RgbImage =
+            RgbImage::from_rgb_data(2, 2, vec![255, 0, 0, 0, 255, 0, 0, 0, 255, 9, 9, 9])
+                .adjust(&ToneAdjustment::Brightness(0));
+
+        assert_eq!(image.as_text(&ChannelEngine), "rg;\nb?;");
+    }
+
+    struct ChannelEngine;
+
+    impl GlyphRenderingEngine for ChannelEngine {
+        fn get_char_based_on_luma_value(&self, _y: u8) -> char {
+            '?'
+        }
+
+        fn get_text_based_on_rgb_value(&self, r: u8, g: u8, b: u8, y: u8) -> String {
+            match (r, g, b) {
+                (255, 0, 0) => "r".into(),
+                (0, 255, 0) => "g".into(),
+                (0, 0, 255) => "b".into(),
+                _ => self.get_char_based_on_luma_value(y).to_string(),
+            }
+        }
+
+        fn get_style_reset(&self) -> &str {
+            ";"
+        }
+    }
+}
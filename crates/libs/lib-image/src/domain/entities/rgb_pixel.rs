@@ -1,5 +1,7 @@
-#[derive(Debug, PartialEq, Clone)]
-pub(crate) struct RgbPixel {
+use std::{fmt, str::FromStr};
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct RgbPixel {
     pub r: u8,
     pub g: u8,
     pub b: u8,
@@ -15,10 +17,66 @@ impl RgbPixel {
     }
 }
 
+impl FromStr for RgbPixel {
+    type Err = InvalidColorError;
+
+    /// Parses `#rrggbb` or `r,g,b`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || InvalidColorError(s.into());
+        let s: &str = s.trim();
+
+        if let Some(hex) = s.strip_prefix('#') {
+            if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(error());
+            }
+
+            let value: u32 = u32::from_str_radix(hex, 16).map_err(|_| error())?;
+
+            return Ok(Self::new(
+                (value >> 16) as u8,
+                (value >> 8) as u8,
+                value as u8,
+            ));
+        }
+
+        let values: Vec<u8> = s
+            .split(',')
+            .map(|v| v.trim().parse::<u8>())
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|_| error())?;
+
+        match values.as_slice() {
+            [r, g, b] => Ok(Self::new(*r, *g, *b)),
+            _ => Err(error()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct InvalidColorError(pub String);
+
+impl fmt::Display for InvalidColorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected '#rrggbb' or 'r,g,b', got '{}'", self.0)
+    }
+}
+
+impl std::error::Error for InvalidColorError {}
+
 #[cfg(test)]
 mod test {
+    use pretty_assertions::assert_eq;
+
     use crate::domain::entities::rgb_pixel::RgbPixel;
 
+    #[test]
+    fn should_parse_hex_and_decimal_colors() {
+        assert_eq!("#ff8000".parse(), Ok(RgbPixel::new(255, 128, 0)));
+        assert_eq!("1, 2,3".parse(), Ok(RgbPixel::new(1, 2, 3)));
+        assert!("#fff".parse::<RgbPixel>().is_err());
+        assert!("1,2".parse::<RgbPixel>().is_err());
+    }
+
     #[test]
     fn should_create_pixel_from_slice() {
         let rgb_values: Vec<([u8; 3], RgbPixel)> = vec![
@@ -39,10 +97,7 @@ mod test {
             .collect();
 
         assert_eq!(
-            rgb_values
-                .iter()
-                .map(|i| i.1.clone())
-                .collect::<Vec<RgbPixel>>(),
+            rgb_values.iter().map(|i| i.1).collect::<Vec<RgbPixel>>(),
             results
         );
     }
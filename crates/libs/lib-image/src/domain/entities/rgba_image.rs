@@ -0,0 +1,250 @@
+use std::{borrow::Cow, fmt};
+
+use lib_core::domain::entities::{Glyph, GlyphRenderingEngine};
+
+use crate::domain::entities::{
+    glyph_image::GlyphImage,
+    image_buffer::{ImageBuffer, InvalidImageDataError},
+    luma_image::LumaImage,
+    placement::Rect,
+    resample_filter::ResampleFilter,
+    rgb_image::{RgbImage, color_size_hint, write_color_rows},
+    rgb_pixel::RgbPixel,
+    rgba_pixel::RgbaPixel,
+    tone_adjustment::ToneAdjustment,
+};
+
+/// Interleaved 8-bit `R, G, B, A` image with straight (non-premultiplied) alpha.
+#[derive(Debug, PartialEq, Clone)]
+pub struct RgbaImage {
+    buffer: ImageBuffer,
+}
+
+impl RgbaImage {
+    /// A transparent image; panics when `width * height` pixels do not fit in memory.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self::from_buffer(ImageBuffer::new(width, height, 4).expect("image does not fit in memory"))
+    }
+
+    /// Builds an image from row-major `R, G, B, A` quadruples, padding or truncating to fit.
+    /// Panics when `width * height` pixels do not fit in memory.
+    pub fn from_rgba_data(width: usize, height: usize, data: Vec<u8>) -> Self {
+        Self::from_buffer(
+            ImageBuffer::from_packed(data, width, height, 4).expect("image does not fit in memory"),
+        )
+    }
+
+    pub(crate) fn from_buffer(buffer: ImageBuffer) -> Self {
+        Self { buffer }
+    }
+
+    pub(crate) fn buffer(&self) -> &ImageBuffer {
+        &self.buffer
+    }
+
+    pub fn width(&self) -> usize {
+        self.buffer.width()
+    }
+
+    pub fn height(&self) -> usize {
+        self.buffer.height()
+    }
+
+    /// Row-major samples without row padding.
+    pub fn data(&self) -> Cow<'_, [u8]> {
+        self.buffer.packed()
+    }
+
+    /// Adjusts the color channels; alpha is kept as is.
+    pub fn adjust(mut self, adjustment: &ToneAdjustment) -> Self {
+        let (width, height): (usize, usize) = (self.width(), self.height());
+
+        adjustment.apply_color(self.buffer.packed_mut(), width, height, 4);
+        self
+    }
+
+    pub fn adjust_all<'a>(self, adjustments: impl IntoIterator<Item = &'a ToneAdjustment>) -> Self {
+        adjustments
+            .into_iter()
+            .fold(self, |image: RgbaImage, adjustment: &ToneAdjustment| {
+                image.adjust(adjustment)
+            })
+    }
+
+    /// Resamples with premultiplied alpha, so transparent pixels do not bleed their color.
+    /// Fails when `width` by `height` pixels do not fit in memory.
+    pub fn resize(
+        &self,
+        width: usize,
+        height: usize,
+        filter: ResampleFilter,
+    ) -> Result<Self, InvalidImageDataError> {
+        let premultiplied: ImageBuffer = self.buffer.map_pixels(4, |source, target| {
+            for c in 0..3 {
+                target[c] = ((source[c] as u32 * source[3] as u32 + 127) / 255) as u8;
+            }
+            target[3] = source[3];
+        });
+        let resized: ImageBuffer = premultiplied.resize(width, height, filter)?;
+
+        Ok(Self::from_buffer(resized.map_pixels(
+            4,
+            |source, target| {
+                let alpha: u32 = source[3] as u32;
+
+                for c in 0..3 {
+                    target[c] = match alpha {
+                        0 => 0,
+                        _ => ((source[c] as u32 * 255 + alpha / 2) / alpha).min(255) as u8,
+                    };
+                }
+                target[3] = source[3];
+            },
+        )))
+    }
+
+    /// Blends every pixel over an opaque `background` according to its alpha.
+    pub fn composite_over(&self, background: RgbPixel) -> RgbImage {
+        let background: [u8; 3] = [background.r, background.g, background.b];
+
+        RgbImage::from_buffer(self.buffer.map_pixels(3, |rgba, rgb| {
+            let alpha: u32 = rgba[3] as u32;
+
+            for c in 0..3 {
+                rgb[c] = ((rgba[c] as u32 * alpha + background[c] as u32 * (255 - alpha) + 127)
+                    / 255) as u8;
+            }
+        }))
+    }
+
+    /// Copies the part of the image covered by `rect`, clipped to the image bounds.
+    pub fn crop(&self, rect: Rect) -> Self {
+        Self::from_buffer(self.buffer.crop(rect))
+    }
+}
+
+impl GlyphImage for RgbaImage {
+    type Pixel = RgbaPixel;
+
+    const CHANNELS: usize = 4;
+
+    fn from_image_data(data: Vec<u8>, width: usize, height: usize) -> Self {
+        Self::from_rgba_data(width, height, data)
+    }
+
+    fn from_strided_image_data(
+        data: Vec<u8>,
+        width: usize,
+        height: usize,
+        stride: usize,
+    ) -> Result<Self, InvalidImageDataError> {
+        ImageBuffer::from_strided(data, width, height, 4, stride).map(Self::from_buffer)
+    }
+
+    fn get_image_data(&self) -> &[u8] {
+        self.buffer.raw()
+    }
+
+    fn get_width(&self) -> usize {
+        self.buffer.width()
+    }
+
+    fn get_height(&self) -> usize {
+        self.buffer.height()
+    }
+
+    fn get_stride(&self) -> usize {
+        self.buffer.stride()
+    }
+
+    fn pixel_from_samples(samples: &[u8]) -> RgbaPixel {
+        RgbaPixel::new(samples[0], samples[1], samples[2], samples[3])
+    }
+}
+
+impl From<&RgbImage> for RgbaImage {
+    /// Every pixel becomes fully opaque.
+    fn from(image: &RgbImage) -> Self {
+        Self::from_buffer(image.buffer().map_pixels(4, |rgb, rgba| {
+            rgba[..3].copy_from_slice(rgb);
+            rgba[3] = u8::MAX;
+        }))
+    }
+}
+
+impl From<&LumaImage> for RgbaImage {
+    fn from(image: &LumaImage) -> Self {
+        Self::from(&RgbImage::from(image))
+    }
+}
+
+impl Glyph for RgbaImage {
+    fn render_into(
+        &self,
+        engine: &dyn GlyphRenderingEngine,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        write_color_rows(self.rows(), 4, engine, out)
+    }
+
+    fn size_hint(&self, engine: &dyn GlyphRenderingEngine) -> usize {
+        color_size_hint(self.width(), self.height(), engine)
+    }
+
+    fn add_child(&mut self, _child: Box<dyn Glyph>) {}
+
+    fn get_child_mut(&mut self, _index: usize) -> Option<&mut dyn Glyph> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::entities::{
+        glyph_image::GlyphImage, luma_image::LumaImage, resample_filter::ResampleFilter,
+        rgb_image::RgbImage, rgb_pixel::RgbPixel, rgba_image::RgbaImage, rgba_pixel::RgbaPixel,
+        tone_adjustment::ToneAdjustment,
+    };
+
+    #[test]
+    fn should_round_trip_through_rgb() {
+        let rgb: RgbImage = RgbImage::from_rgb_data(1, 2, vec![1, 2, 3, 4, 5, 6]);
+
+        let rgba: RgbaImage = RgbaImage::from(&rgb);
+
+        assert_eq!(*rgba.data(), [1, 2, 3, 255, 4, 5, 6, 255]);
+        assert_eq!(RgbImage::from(&rgba), rgb);
+    }
+
+    #[test]
+    fn should_expose_rows_and_pixels() {
+        let image: RgbaImage = RgbaImage::from(&LumaImage::from_luma_data(2, 1, vec![0, 200]))
+            .adjust(&ToneAdjustment::Invert);
+
+        assert_eq!(image.row(0), &[255, 255, 255, 255, 55, 55, 55, 255]);
+        assert_eq!(image.pixel(1, 0), Some(RgbaPixel::new(55, 55, 55, 255)));
+        assert_eq!(image.pixels().count(), 2);
+    }
+
+    #[test]
+    fn should_composite_partial_alpha_over_background() {
+        let image: RgbaImage = RgbaImage::from_rgba_data(2, 1, vec![255, 0, 0, 255, 255, 0, 0, 51]);
+
+        assert_eq!(
+            image.composite_over(RgbPixel::new(0, 0, 255)),
+            RgbImage::from_rgb_data(2, 1, vec![255, 0, 0, 51, 0, 204])
+        );
+    }
+
+    #[test]
+    fn resize_should_not_bleed_transparent_color() {
+        let image: RgbaImage =
+            RgbaImage::from_rgba_data(2, 1, vec![255, 255, 255, 255, 0, 0, 0, 0]);
+
+        let result: RgbaImage = image.resize(1, 1, ResampleFilter::Box).unwrap();
+
+        assert_eq!(result.pixel(0, 0), Some(RgbaPixel::new(255, 255, 255, 128)));
+    }
+}
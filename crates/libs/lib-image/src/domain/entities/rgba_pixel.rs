@@ -0,0 +1,40 @@
+use crate::domain::entities::rgb_pixel::RgbPixel;
+
+#[derive(Debug, PartialEq, Clone)]
+pub struct RgbaPixel {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    /// Coverage, from `0` (fully transparent) to `255` (opaque).
+    pub a: u8,
+}
+
+impl RgbaPixel {
+    pub fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a }
+    }
+
+    pub fn from_slice(i: [u8; 4]) -> Self {
+        Self::new(i[0], i[1], i[2], i[3])
+    }
+
+    /// Color channels with the alpha discarded.
+    pub fn rgb(&self) -> RgbPixel {
+        RgbPixel::new(self.r, self.g, self.b)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::entities::{rgb_pixel::RgbPixel, rgba_pixel::RgbaPixel};
+
+    #[test]
+    fn should_drop_alpha_when_converting_to_rgb() {
+        let pixel: RgbaPixel = RgbaPixel::from_slice([10, 20, 30, 40]);
+
+        assert_eq!(pixel, RgbaPixel::new(10, 20, 30, 40));
+        assert_eq!(pixel.rgb(), RgbPixel::new(10, 20, 30));
+    }
+}
@@ -0,0 +1,204 @@
+use crate::domain::entities::luma_image::LumaImage;
+
+/// Width in pixels of one character cell drawn by [`LumaImage::from_text`].
+pub const TEXT_CELL_WIDTH: usize = 6;
+/// Height in pixels of one character cell drawn by [`LumaImage::from_text`].
+pub const TEXT_CELL_HEIGHT: usize = 8;
+
+/// 5x7 glyphs for printable ASCII, from `' '` to `'~'`.
+///
+/// Each glyph is five columns from left to right; bit 0 of a column is its top pixel.
+const FONT: [[u8; 5]; 95] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00], // ' '
+    [0x00, 0x00, 0x5F, 0x00, 0x00], // '!'
+    [0x00, 0x07, 0x00, 0x07, 0x00], // '"'
+    [0x14, 0x7F, 0x14, 0x7F, 0x14], // '#'
+    [0x24, 0x2A, 0x7F, 0x2A, 0x12], // '$'
+    [0x23, 0x13, 0x08, 0x64, 0x62], // '%'
+    [0x36, 0x49, 0x55, 0x22, 0x50], // '&'
+    [0x00, 0x05, 0x03, 0x00, 0x00], // '''
+    [0x00, 0x1C, 0x22, 0x41, 0x00], // '('
+    [0x00, 0x41, 0x22, 0x1C, 0x00], // ')'
+    [0x08, 0x2A, 0x1C, 0x2A, 0x08], // '*'
+    [0x08, 0x08, 0x3E, 0x08, 0x08], // '+'
+    [0x00, 0x50, 0x30, 0x00, 0x00], // ','
+    [0x08, 0x08, 0x08, 0x08, 0x08], // '-'
+    [0x00, 0x60, 0x60, 0x00, 0x00], // '.'
+    [0x20, 0x10, 0x08, 0x04, 0x02], // '/'
+    [0x3E, 0x51, 0x49, 0x45, 0x3E], // '0'
+    [0x00, 0x42, 0x7F, 0x40, 0x00], // '1'
+    [0x42, 0x61, 0x51, 0x49, 0x46], // '2'
+    [0x21, 0x41, 0x45, 0x4B, 0x31], // '3'
+    [0x18, 0x14, 0x12, 0x7F, 0x10], // '4'
+    [0x27, 0x45, 0x45, 0x45, 0x39], // '5'
+    [0x3C, 0x4A, 0x49, 0x49, 0x30], // '6'
+    [0x01, 0x71, 0x09, 0x05, 0x03], // '7'
+    [0x36, 0x49, 0x49, 0x49, 0x36], // '8'
+    [0x06, 0x49, 0x49, 0x29, 0x1E], // '9'
+    [0x00, 0x36, 0x36, 0x00, 0x00], // ':'
+    [0x00, 0x56, 0x36, 0x00, 0x00], // ';'
+    [0x08, 0x14, 0x22, 0x41, 0x00], // '<'
+    [0x14, 0x14, 0x14, 0x14, 0x14], // '='
+    [0x00, 0x41, 0x22, 0x14, 0x08], // '>'
+    [0x02, 0x01, 0x51, 0x09, 0x06], // '?'
+    [0x32, 0x49, 0x79, 0x41, 0x3E], // '@'
+    [0x7E, 0x11, 0x11, 0x11, 0x7E], // 'A'
+    [0x7F, 0x49, 0x49, 0x49, 0x36], // 'B'
+    [0x3E, 0x41, 0x41, 0x41, 0x22], // 'C'
+    [0x7F, 0x41, 0x41, 0x22, 0x1C], // 'D'
+    [0x7F, 0x49, 0x49, 0x49, 0x41], // 'E'
+    [0x7F, 0x09, 0x09, 0x09, 0x01], // 'F'
+    [0x3E, 0x41, 0x49, 0x49, 0x7A], // 'G'
+    [0x7F, 0x08, 0x08, 0x08, 0x7F], // 'H'
+    [0x00, 0x41, 0x7F, 0x41, 0x00], // 'I'
+    [0x20, 0x40, 0x41, 0x3F, 0x01], // 'J'
+    [0x7F, 0x08, 0x14, 0x22, 0x41], // 'K'
+    [0x7F, 0x40, 0x40, 0x40, 0x40], // 'L'
+    [0x7F, 0x02, 0x0C, 0x02, 0x7F], // 'M'
+    [0x7F, 0x04, 0x08, 0x10, 0x7F], // 'N'
+    [0x3E, 0x41, 0x41, 0x41, 0x3E], // 'O'
+    [0x7F, 0x09, 0x09, 0x09, 0x06], // 'P'
+    [0x3E, 0x41, 0x51, 0x21, 0x5E], // 'Q'
+    [0x7F, 0x09, 0x19, 0x29, 0x46], // 'R'
+    [0x46, 0x49, 0x49, 0x49, 0x31], // 'S'
+    [0x01, 0x01, 0x7F, 0x01, 0x01], // 'T'
+    [0x3F, 0x40, 0x40, 0x40, 0x3F], // 'U'
+    [0x1F, 0x20, 0x40, 0x20, 0x1F], // 'V'
+    [0x3F, 0x40, 0x38, 0x40, 0x3F], // 'W'
+    [0x63, 0x14, 0x08, 0x14, 0x63], // 'X'
+    [0x07, 0x08, 0x70, 0x08, 0x07], // 'Y'
+    [0x61, 0x51, 0x49, 0x45, 0x43], // 'Z'
+    [0x00, 0x7F, 0x41, 0x41, 0x00], // '['
+    [0x02, 0x04, 0x08, 0x10, 0x20], // '\'
+    [0x00, 0x41, 0x41, 0x7F, 0x00], // ']'
+    [0x04, 0x02, 0x01, 0x02, 0x04], // '^'
+    [0x40, 0x40, 0x40, 0x40, 0x40], // '_'
+    [0x00, 0x01, 0x02, 0x04, 0x00], // '`'
+    [0x20, 0x54, 0x54, 0x54, 0x78], // 'a'
+    [0x7F, 0x48, 0x44, 0x44, 0x38], // 'b'
+    [0x38, 0x44, 0x44, 0x44, 0x20], // 'c'
+    [0x38, 0x44, 0x44, 0x48, 0x7F], // 'd'
+    [0x38, 0x54, 0x54, 0x54, 0x18], // 'e'
+    [0x08, 0x7E, 0x09, 0x01, 0x02], // 'f'
+    [0x0C, 0x52, 0x52, 0x52, 0x3E], // 'g'
+    [0x7F, 0x08, 0x04, 0x04, 0x78], // 'h'
+    [0x00, 0x44, 0x7D, 0x40, 0x00], // 'i'
+    [0x20, 0x40, 0x44, 0x3D, 0x00], // 'j'
+    [0x7F, 0x10, 0x28, 0x44, 0x00], // 'k'
+    [0x00, 0x41, 0x7F, 0x40, 0x00], // 'l'
+    [0x7C, 0x04, 0x18, 0x04, 0x78], // 'm'
+    [0x7C, 0x08, 0x04, 0x04, 0x78], // 'n'
+    [0x38, 0x44, 0x44, 0x44, 0x38], // 'o'
+    [0x7C, 0x14, 0x14, 0x14, 0x08], // 'p'
+    [0x08, 0x14, 0x14, 0x18, 0x7C], // 'q'
+    [0x7C, 0x08, 0x04, 0x04, 0x08], // 'r'
+    [0x48, 0x54, 0x54, 0x54, 0x20], // 's'
+    [0x04, 0x3F, 0x44, 0x40, 0x20], // 't'
+    [0x3C, 0x40, 0x40, 0x20, 0x7C], // 'u'
+    [0x1C, 0x20, 0x40, 0x20, 0x1C], // 'v'
+    [0x3C, 0x40, 0x30, 0x40, 0x3C], // 'w'
+    [0x44, 0x28, 0x10, 0x28, 0x44], // 'x'
+    [0x0C, 0x50, 0x50, 0x50, 0x3C], // 'y'
+    [0x44, 0x64, 0x54, 0x4C, 0x44], // 'z'
+    [0x00, 0x08, 0x36, 0x41, 0x00], // '{'
+    [0x00, 0x00, 0x7F, 0x00, 0x00], // '|'
+    [0x00, 0x41, 0x36, 0x08, 0x00], // '}'
+    [0x08, 0x04, 0x08, 0x10, 0x08], // '~'
+];
+
+/// Drawn for characters outside printable ASCII.
+const MISSING_GLYPH: [u8; 5] = [0x7F, 0x41, 0x41, 0x41, 0x7F];
+
+/// Draws `text` in white on black into a grid of `columns` by `rows` character cells.
+///
+/// ANSI escape sequences such as color codes are skipped. Lines and characters beyond the grid
+/// are dropped, and missing ones are left blank.
+pub(crate) fn rasterize(text: &str, columns: usize, rows: usize) -> LumaImage {
+    let width: usize = columns * TEXT_CELL_WIDTH;
+    let mut data: Vec<u8> = vec![0; width * rows * TEXT_CELL_HEIGHT];
+
+    for (row, line) in text.lines().take(rows).enumerate() {
+        for (column, character) in visible_chars(line).take(columns).enumerate() {
+            let glyph: &[u8; 5] = match character {
+                ' '..='~' => &FONT[character as usize - ' ' as usize],
+                _ => &MISSING_GLYPH,
+            };
+
+            for (dx, bits) in glyph.iter().enumerate() {
+                for dy in (0..7).filter(|dy| bits & (1 << dy) != 0) {
+                    let x: usize = column * TEXT_CELL_WIDTH + dx;
+                    let y: usize = row * TEXT_CELL_HEIGHT + dy;
+                    data[y * width + x] = 255;
+                }
+            }
+        }
+    }
+
+    LumaImage::from_luma_data(width, rows * TEXT_CELL_HEIGHT, data)
+}
+
+/// Characters of `line` that take up a cell, skipping CSI escape sequences and carriage returns.
+fn visible_chars(line: &str) -> impl Iterator<Item = char> + '_ {
+    let mut chars = line.chars();
+
+    std::iter::from_fn(move || {
+        loop {
+            match chars.next()? {
+                '\x1b' => {
+                    if chars.next() == Some('[') {
+                        // Parameters and intermediates run up to a final byte in `@`..=`~`.
+                        chars.by_ref().find(|c| ('@'..='~').contains(c));
+                    }
+                }
+                '\r' => {}
+                character => return Some(character),
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::entities::{
+        luma_image::LumaImage,
+        text_raster::{TEXT_CELL_HEIGHT, TEXT_CELL_WIDTH, rasterize},
+    };
+
+    fn ink(image: &LumaImage, column: usize) -> usize {
+        (0..image.height())
+            .flat_map(|y| (0..TEXT_CELL_WIDTH).map(move |dx| (column * TEXT_CELL_WIDTH + dx, y)))
+            .filter(|(x, y)| image.data()[y * image.width() + x] == 255)
+            .count()
+    }
+
+    #[test]
+    fn should_size_image_to_the_grid() {
+        let image: LumaImage = rasterize("@\nmore lines than rows", 3, 1);
+
+        assert_eq!(
+            (image.width(), image.height()),
+            (3 * TEXT_CELL_WIDTH, TEXT_CELL_HEIGHT)
+        );
+        assert!(ink(&image, 0) > 0);
+        assert_eq!(ink(&image, 1), 0);
+    }
+
+    #[test]
+    fn should_skip_ansi_escapes() {
+        let plain: LumaImage = rasterize("#.", 2, 1);
+        let colored: LumaImage = rasterize("\x1b[38;2;255;0;0m#\x1b[0m.\r", 2, 1);
+
+        assert_eq!(colored, plain);
+    }
+
+    #[test]
+    fn should_draw_denser_glyphs_with_more_ink() {
+        let image: LumaImage = rasterize(" .:@", 4, 1);
+        let ink: Vec<usize> = (0..4).map(|column| ink(&image, column)).collect();
+
+        assert!(ink.is_sorted(), "{ink:?}");
+        assert_eq!(ink[0], 0);
+    }
+}
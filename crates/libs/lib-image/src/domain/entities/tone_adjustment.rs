@@ -0,0 +1,462 @@
+use std::{fmt, str::FromStr};
+
+use crate::domain::entities::{luma_pixel::LumaPixel, rgb_pixel::RgbPixel};
+
+/// A single tonal operation applied to 8-bit channel data before it reaches a
+/// [`lib_core::domain::entities::GlyphRenderingEngine`].
+#[derive(Debug, PartialEq, Clone)]
+pub enum ToneAdjustment {
+    /// Adds a constant offset to every value.
+    Brightness(i16),
+    /// Scales values around mid-gray; `1.0` leaves the image unchanged.
+    Contrast(f32),
+    /// Applies `255 * (v / 255) ^ (1 / gamma)`; values above `1.0` brighten.
+    Gamma(f32),
+    /// Stretches `black..=white` onto the full `0..=255` range.
+    Levels {
+        black: u8,
+        white: u8,
+    },
+    Invert,
+    /// Global histogram equalization.
+    Equalize,
+    /// Contrast-limited adaptive histogram equalization over a `tiles x tiles` grid.
+    Clahe {
+        tiles: usize,
+        clip_limit: f32,
+    },
+}
+
+impl ToneAdjustment {
+    pub fn apply(&self, data: &mut [u8], width: usize, height: usize) {
+        match self {
+            ToneAdjustment::Equalize => apply_lut(data, &equalization_lut(&histogram(data.iter()))),
+            ToneAdjustment::Clahe { tiles, clip_limit } => {
+                clahe(data, width, height, *tiles, *clip_limit)
+            }
+            point_operation => apply_lut(data, &point_operation.point_lut()),
+        }
+    }
+
+    /// Applies the adjustment to interleaved color samples with `channels` values per pixel.
+    ///
+    /// Point operations run on every color channel. Histogram based ones are computed on luma
+    /// and applied as a gain, so hues are kept. A fourth channel is alpha and is left untouched.
+    pub fn apply_color(&self, data: &mut [u8], width: usize, height: usize, channels: usize) {
+        match self {
+            ToneAdjustment::Equalize | ToneAdjustment::Clahe { .. } => {
+                let before: Vec<u8> = data
+                    .chunks_exact(channels)
+                    .map(|p| LumaPixel::from_rgb(RgbPixel::new(p[0], p[1], p[2])).y)
+                    .collect();
+                let mut after: Vec<u8> = before.clone();
+
+                self.apply(&mut after, width, height);
+
+                for ((pixel, before), after) in
+                    data.chunks_exact_mut(channels).zip(before).zip(after)
+                {
+                    for v in pixel[..3].iter_mut() {
+                        *v = match before {
+                            0 => after,
+                            _ => clamp_to_u8(*v as f32 * after as f32 / before as f32),
+                        };
+                    }
+                }
+            }
+            point_operation => {
+                let lut: [u8; 256] = point_operation.point_lut();
+
+                for pixel in data.chunks_exact_mut(channels) {
+                    apply_lut(&mut pixel[..3], &lut);
+                }
+            }
+        }
+    }
+
+    /// Lookup table for adjustments that only depend on the input value.
+    ///
+    /// Histogram based adjustments return the identity table.
+    pub fn point_lut(&self) -> [u8; 256] {
+        let mut lut: [u8; 256] = [0; 256];
+
+        for (v, out) in lut.iter_mut().enumerate() {
+            let value: f32 = v as f32;
+
+            *out = match self {
+                ToneAdjustment::Brightness(offset) => clamp_to_u8(value + *offset as f32),
+                ToneAdjustment::Contrast(factor) => clamp_to_u8((value - 128.0) * factor + 128.0),
+                ToneAdjustment::Gamma(gamma) => {
+                    clamp_to_u8(255.0 * (value / 255.0).powf(1.0 / gamma.max(f32::EPSILON)))
+                }
+                ToneAdjustment::Levels { black, white } => {
+                    let (black, white): (f32, f32) = (*black as f32, *white as f32);
+                    let range: f32 = (white - black).max(1.0);
+
+                    clamp_to_u8((value - black) * 255.0 / range)
+                }
+                ToneAdjustment::Invert => 255 - v as u8,
+                ToneAdjustment::Equalize | ToneAdjustment::Clahe { .. } => v as u8,
+            };
+        }
+
+        lut
+    }
+}
+
+impl FromStr for ToneAdjustment {
+    type Err = ToneAdjustmentParseError;
+
+    /// Parses `name[=value]` specs such as `gamma=0.8`, `levels=16:235` or `clahe=8:2.5`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value): (&str, Option<&str>) = match s.split_once('=') {
+            Some((name, value)) => (name.trim(), Some(value.trim())),
+            None => (s.trim(), None),
+        };
+        let required = || value.ok_or_else(|| ToneAdjustmentParseError::MissingValue(name.into()));
+
+        match name {
+            "brightness" => Ok(ToneAdjustment::Brightness(parse_value(required()?)?)),
+            "contrast" => Ok(ToneAdjustment::Contrast(parse_value(required()?)?)),
+            "gamma" => Ok(ToneAdjustment::Gamma(parse_value(required()?)?)),
+            "levels" => {
+                let (black, white) = parse_pair(required()?)?;
+
+                Ok(ToneAdjustment::Levels { black, white })
+            }
+            "invert" => Ok(ToneAdjustment::Invert),
+            "equalize" => Ok(ToneAdjustment::Equalize),
+            "clahe" => match value {
+                Some(value) => {
+                    let (tiles, clip_limit) = parse_pair(value)?;
+
+                    Ok(ToneAdjustment::Clahe { tiles, clip_limit })
+                }
+                None => Ok(ToneAdjustment::Clahe {
+                    tiles: 8,
+                    clip_limit: 2.0,
+                }),
+            },
+            _ => Err(ToneAdjustmentParseError::UnknownAdjustment(name.into())),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Clone)]
+pub enum ToneAdjustmentParseError {
+    UnknownAdjustment(String),
+    MissingValue(String),
+    InvalidValue(String),
+}
+
+impl fmt::Display for ToneAdjustmentParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ToneAdjustmentParseError::UnknownAdjustment(name) => {
+                write!(f, "unknown adjustment '{name}'")
+            }
+            ToneAdjustmentParseError::MissingValue(name) => {
+                write!(f, "adjustment '{name}' requires a value")
+            }
+            ToneAdjustmentParseError::InvalidValue(value) => write!(f, "invalid value '{value}'"),
+        }
+    }
+}
+
+impl std::error::Error for ToneAdjustmentParseError {}
+
+fn parse_value<T: FromStr>(value: &str) -> Result<T, ToneAdjustmentParseError> {
+    value
+        .parse::<T>()
+        .map_err(|_| ToneAdjustmentParseError::InvalidValue(value.into()))
+}
+
+fn parse_pair<A: FromStr, B: FromStr>(value: &str) -> Result<(A, B), ToneAdjustmentParseError> {
+    let (a, b) = value
+        .split_once(':')
+        .ok_or_else(|| ToneAdjustmentParseError::InvalidValue(value.into()))?;
+
+    Ok((parse_value(a)?, parse_value(b)?))
+}
+
+fn clamp_to_u8(value: f32) -> u8 {
+    value.round().clamp(0.0, 255.0) as u8
+}
+
+fn apply_lut(data: &mut [u8], lut: &[u8; 256]) {
+    for v in data.iter_mut() {
+        *v = lut[*v as usize];
+    }
+}
+
+fn histogram<'a>(values: impl IntoIterator<Item = &'a u8>) -> [u32; 256] {
+    let mut histogram: [u32; 256] = [0; 256];
+
+    for v in values {
+        histogram[*v as usize] += 1;
+    }
+
+    histogram
+}
+
+fn equalization_lut(histogram: &[u32; 256]) -> [u8; 256] {
+    let total: u32 = histogram.iter().sum();
+    let mut lut: [u8; 256] = [0; 256];
+
+    if total == 0 {
+        return lut;
+    }
+
+    let cdf_min: u32 = histogram.iter().copied().find(|c| *c > 0).unwrap_or(0);
+    let mut cdf: u32 = 0;
+
+    for (out, count) in lut.iter_mut().zip(histogram.iter()) {
+        cdf += count;
+        *out = if total == cdf_min {
+            (cdf * 255 / total) as u8
+        } else {
+            (((cdf.saturating_sub(cdf_min)) as f32 * 255.0) / (total - cdf_min) as f32).round()
+                as u8
+        };
+    }
+
+    lut
+}
+
+fn clahe(data: &mut [u8], width: usize, height: usize, tiles: usize, clip_limit: f32) {
+    if width == 0 || height == 0 {
+        return;
+    }
+
+    let tiles_x: usize = tiles.clamp(1, width);
+    let tiles_y: usize = tiles.clamp(1, height);
+    let tile_width: usize = width.div_ceil(tiles_x);
+    let tile_height: usize = height.div_ceil(tiles_y);
+
+    let luts: Vec<[u8; 256]> = (0..tiles_y * tiles_x)
+        .map(|tile| {
+            let (tx, ty) = (tile % tiles_x, tile / tiles_x);
+            let x_range = (tx * tile_width).min(width)..((tx + 1) * tile_width).min(width);
+            let y_range = (ty * tile_height).min(height)..((ty + 1) * tile_height).min(height);
+            let mut tile_histogram: [u32; 256] = histogram(
+                y_range
+                    .flat_map(|y| data[y * width + x_range.start..y * width + x_range.end].iter()),
+            );
+
+            clip_histogram(&mut tile_histogram, clip_limit);
+            equalization_lut(&tile_histogram)
+        })
+        .collect();
+
+    for y in 0..height {
+        // Position relative to tile centres, so each pixel blends the four nearest tiles.
+        let gy: f32 =
+            ((y as f32 + 0.5) / tile_height as f32 - 0.5).clamp(0.0, (tiles_y - 1) as f32);
+        let (y0, fy) = (gy.floor() as usize, gy.fract());
+        let y1: usize = (y0 + 1).min(tiles_y - 1);
+
+        for x in 0..width {
+            let gx: f32 =
+                ((x as f32 + 0.5) / tile_width as f32 - 0.5).clamp(0.0, (tiles_x - 1) as f32);
+            let (x0, fx) = (gx.floor() as usize, gx.fract());
+            let x1: usize = (x0 + 1).min(tiles_x - 1);
+
+            let v: usize = data[y * width + x] as usize;
+            let top: f32 = luts[y0 * tiles_x + x0][v] as f32 * (1.0 - fx)
+                + luts[y0 * tiles_x + x1][v] as f32 * fx;
+            let bottom: f32 = luts[y1 * tiles_x + x0][v] as f32 * (1.0 - fx)
+                + luts[y1 * tiles_x + x1][v] as f32 * fx;
+
+            data[y * width + x] = clamp_to_u8(top * (1.0 - fy) + bottom * fy);
+        }
+    }
+}
+
+/// Clips every bin at `clip_limit` times the uniform bin height and spreads the excess evenly.
+fn clip_histogram(histogram: &mut [u32; 256], clip_limit: f32) {
+    let total: u32 = histogram.iter().sum();
+    let limit: u32 = ((clip_limit.max(1.0) * total as f32) / 256.0)
+        .ceil()
+        .max(1.0) as u32;
+    let mut excess: u32 = 0;
+
+    for count in histogram.iter_mut() {
+        if *count > limit {
+            excess += *count - limit;
+            *count = limit;
+        }
+    }
+
+    let (share, remainder) = (excess / 256, (excess % 256) as usize);
+
+    for (i, count) in histogram.iter_mut().enumerate() {
+        *count += share + u32::from(i < remainder);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::domain::entities::tone_adjustment::{ToneAdjustment, ToneAdjustmentParseError};
+
+    #[test]
+    fn brightness_should_offset_and_clamp() {
+        let mut data: Vec<u8> = vec![0, 100, 250];
+
+        ToneAdjustment::Brightness(10).apply(&mut data, 3, 1);
+
+        assert_eq!(data, vec![10, 110, 255]);
+    }
+
+    #[test]
+    fn contrast_should_scale_around_mid_gray() {
+        let mut data: Vec<u8> = vec![0, 128, 192];
+
+        ToneAdjustment::Contrast(2.0).apply(&mut data, 3, 1);
+
+        assert_eq!(data, vec![0, 128, 255]);
+    }
+
+    #[test]
+    fn gamma_should_keep_extremes() {
+        let mut data: Vec<u8> = vec![0, 64, 255];
+
+        ToneAdjustment::Gamma(2.0).apply(&mut data, 3, 1);
+
+        assert_eq!(data, vec![0, 128, 255]);
+    }
+
+    #[test]
+    fn levels_should_stretch_range() {
+        let mut data: Vec<u8> = vec![0, 16, 128, 235, 255];
+
+        ToneAdjustment::Levels {
+            black: 16,
+            white: 235,
+        }
+        .apply(&mut data, 5, 1);
+
+        assert_eq!(data, vec![0, 0, 130, 255, 255]);
+    }
+
+    #[test]
+    fn invert_should_mirror_values() {
+        let mut data: Vec<u8> = vec![0, 55, 255];
+
+        ToneAdjustment::Invert.apply(&mut data, 3, 1);
+
+        assert_eq!(data, vec![255, 200, 0]);
+    }
+
+    #[test]
+    fn equalize_should_spread_values_over_full_range() {
+        let mut data: Vec<u8> = vec![100, 100, 101, 101, 102, 102, 103, 103];
+
+        ToneAdjustment::Equalize.apply(&mut data, 4, 2);
+
+        assert_eq!(data, vec![0, 0, 85, 85, 170, 170, 255, 255]);
+    }
+
+    #[test]
+    fn clahe_should_increase_local_contrast() {
+        let width: usize = 16;
+        let mut data: Vec<u8> = (0..width * width).map(|i| 100 + (i % 4) as u8).collect();
+
+        ToneAdjustment::Clahe {
+            tiles: 2,
+            clip_limit: 4.0,
+        }
+        .apply(&mut data, width, width);
+
+        let min: u8 = *data.iter().min().unwrap();
+        let max: u8 = *data.iter().max().unwrap();
+
+        assert!(max - min > 3, "expected stretched range, got {min}..{max}");
+    }
+
+    #[test]
+    fn clahe_should_handle_images_smaller_than_tile_grid() {
+        let mut data: Vec<u8> = vec![10, 20, 30];
+
+        ToneAdjustment::Clahe {
+            tiles: 8,
+            clip_limit: 2.0,
+        }
+        .apply(&mut data, 3, 1);
+
+        assert_eq!(data.len(), 3);
+    }
+
+    #[test]
+    fn color_point_operations_should_skip_alpha() {
+        let mut data: Vec<u8> = vec![0, 100, 250, 128];
+
+        ToneAdjustment::Invert.apply_color(&mut data, 1, 1, 4);
+
+        assert_eq!(data, vec![255, 155, 5, 128]);
+    }
+
+    #[test]
+    fn color_equalize_should_preserve_hue() {
+        let mut data: Vec<u8> = vec![0, 0, 0, 60, 30, 0, 255, 255, 255];
+
+        ToneAdjustment::Equalize.apply_color(&mut data, 3, 1, 3);
+
+        assert_eq!(data, vec![0, 0, 0, 226, 113, 0, 255, 255, 255]);
+    }
+
+    #[test]
+    fn should_parse_adjustment_specs() {
+        let specs: Vec<(&str, ToneAdjustment)> = vec![
+            ("brightness=-20", ToneAdjustment::Brightness(-20)),
+            ("contrast=1.5", ToneAdjustment::Contrast(1.5)),
+            ("gamma=0.8", ToneAdjustment::Gamma(0.8)),
+            (
+                "levels=16:235",
+                ToneAdjustment::Levels {
+                    black: 16,
+                    white: 235,
+                },
+            ),
+            ("invert", ToneAdjustment::Invert),
+            ("equalize", ToneAdjustment::Equalize),
+            (
+                "clahe",
+                ToneAdjustment::Clahe {
+                    tiles: 8,
+                    clip_limit: 2.0,
+                },
+            ),
+            (
+                "clahe=4:3.5",
+                ToneAdjustment::Clahe {
+                    tiles: 4,
+                    clip_limit: 3.5,
+                },
+            ),
+        ];
+
+        for (spec, expected) in specs {
+            assert_eq!(spec.parse::<ToneAdjustment>(), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn should_reject_invalid_specs() {
+        assert_eq!(
+            "sharpen".parse::<ToneAdjustment>(),
+            Err(ToneAdjustmentParseError::UnknownAdjustment(
+                "sharpen".to_string()
+            ))
+        );
+        assert_eq!(
+            "gamma".parse::<ToneAdjustment>(),
+            Err(ToneAdjustmentParseError::MissingValue("gamma".to_string()))
+        );
+        assert_eq!(
+            "levels=16".parse::<ToneAdjustment>(),
+            Err(ToneAdjustmentParseError::InvalidValue("16".to_string()))
+        );
+    }
+}
@@ -0,0 +1,461 @@
+use clap::{Parser, ValueEnum};
+use ffmpeg_next::software::scaling::flag::Flags;
+use lib_ascii::domain::entities::{BannerFilter, FigletFont, SubtitleTrack};
+use lib_image::domain::codecs::RawPixelFormat;
+use lib_image::domain::entities::{
+    AlphaSettings, AutoExposure, AutoExposureSettings, EdgeDetector, EdgeOperator, FitMode,
+    LineArtMode, Placement, Rect, ResampleFilter, RgbPixel, ToneAdjustment, TransparentCell,
+};
+
+/// Converts images and videos into ASCII art directly in the terminal.
+#[derive(Debug, Parser)]
+#[command(name = "glyphforge-cli", version)]
+pub struct Args {
+    /// Image or video to render, or `-` for a Y4M stream on stdin; with `--raw`, a file, a FIFO
+    /// or `-`.
+    pub path: String,
+
+    /// Read `path` as headerless frames in this pixel format, each drawn as soon as it arrives.
+    ///
+    /// Pairs with `ffmpeg -f rawvideo -pix_fmt <format>`; frames are `--size` pixels each.
+    #[arg(long, value_enum, value_name = "FORMAT", requires = "size")]
+    pub raw: Option<RawFormatArg>,
+
+    /// Dimensions of each `--raw` frame, as `WxH` in pixels.
+    #[arg(long, value_name = "WxH", value_parser = parse_size)]
+    pub size: Option<(usize, usize)>,
+
+    /// Frame rate `--raw` frames are paced at, instead of drawing them as they arrive.
+    #[arg(long)]
+    pub fps: Option<f64>,
+
+    /// Tonal adjustment applied before glyph mapping, in the order given.
+    ///
+    /// One of `brightness=<i16>`, `contrast=<f32>`, `gamma=<f32>`, `levels=<black>:<white>`,
+    /// `invert`, `equalize` or `clahe[=<tiles>:<clip_limit>]`.
+    #[arg(short = 'a', long = "adjust", value_name = "SPEC")]
+    pub adjustments: Vec<ToneAdjustment>,
+
+    /// Tint glyphs with the source colors using 24-bit ANSI escapes.
+    ///
+    /// Edge rendering with `--edges` stays monochrome.
+    #[arg(long)]
+    pub color: bool,
+
+    /// Characters luma is mapped onto, from dark to bright.
+    #[arg(long, value_enum, default_value_t = CharsetArg::Standard)]
+    pub charset: CharsetArg,
+
+    /// Color that partially transparent pixels are blended over, as `#rrggbb` or `r,g,b`.
+    #[arg(long, default_value = "#000000")]
+    pub background: RgbPixel,
+
+    /// What fully transparent cells show.
+    ///
+    /// One of `clear`, `glyph:<char>`, `color:<color>` or `checkerboard[=<size>:<light>:<dark>]`.
+    #[arg(long, value_name = "SPEC", default_value = "clear")]
+    pub transparent: TransparentCell,
+
+    /// Alpha at or below which a pixel counts as fully transparent.
+    #[arg(long, default_value_t = 0)]
+    pub alpha_threshold: u8,
+
+    /// How frames are fitted to the terminal.
+    #[arg(long, value_enum, default_value_t = FitArg::Contain)]
+    pub fit: FitArg,
+
+    /// Glyph drawn in the letterbox or pillarbox bars left by `--fit contain`.
+    #[arg(long, default_value_t = ' ')]
+    pub fill_glyph: char,
+
+    /// Source region to show, as `x,y,width,height` in source pixels.
+    #[arg(long, value_name = "X,Y,W,H")]
+    pub crop: Option<Rect>,
+
+    /// Magnification applied after `--crop`.
+    #[arg(long, default_value_t = 1.0)]
+    pub zoom: f32,
+
+    /// Position of the zoomed or covered region, as `x,y` from -1.0 (left/top) to 1.0 (right/bottom).
+    #[arg(long, value_name = "X,Y", value_parser = parse_pan, default_value = "0,0")]
+    pub pan: (f32, f32),
+
+    /// Height of a terminal cell divided by its width.
+    #[arg(long, default_value_t = 1.0)]
+    pub cell_aspect: f32,
+
+    /// Downscaling method used to fit frames to the terminal.
+    #[arg(long, value_enum, default_value_t = ResamplerArg::Swscale)]
+    pub resampler: ResamplerArg,
+
+    /// Start with temporal auto-exposure enabled; press `e` during playback to toggle it.
+    #[arg(long)]
+    pub auto_exposure: bool,
+
+    /// How far auto-exposure stretches each frame onto the full ramp, from 0.0 to 1.0.
+    #[arg(long, default_value_t = 1.0)]
+    pub auto_exposure_strength: f32,
+
+    /// Draw detected edges with `-`, `|`, `/` and `\` characters.
+    #[arg(long, value_enum)]
+    pub edges: Option<EdgeModeArg>,
+
+    /// Edge detection filter used by `--edges`.
+    #[arg(long, value_enum, default_value_t = EdgeDetectorArg::Sobel)]
+    pub edge_detector: EdgeDetectorArg,
+
+    /// Gradient magnitude an edge must reach, in luma units.
+    ///
+    /// Canny uses it as the high hysteresis threshold.
+    #[arg(long, default_value_t = 64.0)]
+    pub edge_threshold: f32,
+
+    /// Low hysteresis threshold for Canny; defaults to half of `--edge-threshold`.
+    #[arg(long)]
+    pub edge_low_threshold: Option<f32>,
+
+    /// Also write every rendered frame to this Y4M file, drawn as white glyphs on black.
+    #[arg(long, value_name = "PATH")]
+    pub record: Option<String>,
+
+    /// Frame rate stored in the `--record` file; each rendered frame is written once.
+    #[arg(long, default_value_t = 25.0)]
+    pub record_fps: f64,
+
+    /// Instead of playing locally, stream the playback to telnet clients listening here, as
+    /// `host:port`.
+    ///
+    /// Each client gets frames at the window size its telnet client reports.
+    #[arg(long, value_name = "ADDR")]
+    pub telnet: Option<String>,
+
+    /// Instead of playing locally, stream the playback to plain TCP clients such as `nc`
+    /// listening here, as `host:port`.
+    #[arg(long, value_name = "ADDR")]
+    pub tcp: Option<String>,
+
+    /// Instead of playing locally, stream the playback over HTTP listening here, as
+    /// `host:port`; `curl host:port/video` plays it.
+    ///
+    /// `width`, `height`, `charset` and `color` query parameters override the defaults per
+    /// request, and `mode=plain` drops escapes. Browsers get plain frames unless `mode=ansi`.
+    /// `/` serves a browser viewer that plays over a WebSocket, with its own pause and seek.
+    #[arg(long, value_name = "ADDR")]
+    pub http: Option<String>,
+
+    /// Size frames are rendered at for network clients that do not report one, as `WxH` in
+    /// cells.
+    #[arg(long, value_name = "WxH", value_parser = parse_size, default_value = "80x24")]
+    pub client_size: (usize, usize),
+
+    /// Treat `path` as a playlist: one image or video per line, played in a loop. Blank lines
+    /// and lines starting with `#` are skipped; relative paths are relative to the playlist.
+    #[arg(long)]
+    pub playlist: bool,
+
+    /// Draw this text in large letters, centred over the top of every frame.
+    #[arg(long, value_name = "TEXT")]
+    pub banner: Option<String>,
+
+    /// Font `--banner` is drawn in: `block`, `segment`, `shade`, or the path of a FIGlet
+    /// `.flf` or TOIlet `.tlf` file.
+    #[arg(long, value_name = "NAME|PATH", value_parser = parse_font, default_value = "block")]
+    pub banner_font: FigletFont,
+
+    /// Color the letters of `--banner`; needs `--color`.
+    #[arg(long, value_enum)]
+    pub banner_filter: Option<BannerFilterArg>,
+
+    /// Show subtitles during local playback: an SRT, WebVTT or ASS file, `embedded` for the
+    /// video's own text subtitle stream, or `embedded:<index>` for the stream with that index.
+    #[arg(long, value_name = "PATH|embedded[:INDEX]", value_parser = parse_subtitles)]
+    pub subtitles: Option<SubtitleSource>,
+
+    /// Where `--subtitles` are drawn.
+    #[arg(long, value_enum, default_value_t = SubtitlePositionArg::Over)]
+    pub subtitle_position: SubtitlePositionArg,
+
+    /// Rows kept free below the frame for `--subtitle-position under`.
+    #[arg(long, default_value_t = 2)]
+    pub subtitle_rows: usize,
+
+    /// Draw subtitles over the frame as they are, instead of blanking the rows they are on.
+    #[arg(long)]
+    pub no_subtitle_band: bool,
+
+    /// Show a status line below the frame during local playback: position and duration, a
+    /// progress bar, source and grid sizes, render mode, frame rate and dropped frames.
+    #[arg(long)]
+    pub hud: bool,
+}
+
+impl Args {
+    /// Whether playback goes to network clients instead of this terminal.
+    pub fn serving(&self) -> bool {
+        self.telnet.is_some() || self.tcp.is_some() || self.http.is_some()
+    }
+
+    /// Terminal rows kept below the frame for subtitles.
+    pub fn reserved_subtitle_rows(&self) -> usize {
+        match (&self.subtitles, self.subtitle_position) {
+            (Some(_), SubtitlePositionArg::Under) => self.subtitle_rows,
+            _ => 0,
+        }
+    }
+
+    /// Terminal rows kept below the frame for subtitles and the `--hud`, which frames are
+    /// fitted around.
+    pub fn reserved_rows(&self) -> usize {
+        self.reserved_subtitle_rows() + usize::from(self.hud)
+    }
+
+    pub fn placement(&self) -> Placement {
+        Placement {
+            mode: self.fit.into(),
+            crop: self.crop,
+            zoom: self.zoom,
+            pan: self.pan,
+            cell_aspect: self.cell_aspect,
+        }
+    }
+
+    pub fn auto_exposure(&self) -> AutoExposure {
+        let mut auto_exposure: AutoExposure = AutoExposure::new(AutoExposureSettings {
+            strength: self.auto_exposure_strength,
+            ..AutoExposureSettings::default()
+        });
+        auto_exposure.set_enabled(self.auto_exposure);
+
+        auto_exposure
+    }
+
+    pub fn alpha_settings(&self) -> AlphaSettings {
+        AlphaSettings {
+            background: self.background,
+            threshold: self.alpha_threshold,
+            transparent: self.transparent,
+        }
+    }
+
+    pub fn edge_detector(&self) -> EdgeDetector {
+        match self.edge_detector {
+            EdgeDetectorArg::Sobel => EdgeDetector::Gradient {
+                operator: EdgeOperator::Sobel,
+                threshold: self.edge_threshold,
+            },
+            EdgeDetectorArg::Scharr => EdgeDetector::Gradient {
+                operator: EdgeOperator::Scharr,
+                threshold: self.edge_threshold,
+            },
+            EdgeDetectorArg::Canny => EdgeDetector::Canny {
+                operator: EdgeOperator::Sobel,
+                low: self.edge_low_threshold.unwrap_or(self.edge_threshold / 2.0),
+                high: self.edge_threshold,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum RawFormatArg {
+    /// One byte of luma per pixel.
+    Gray,
+    /// Three bytes per pixel: red, green, blue.
+    Rgb24,
+    /// Four bytes per pixel: red, green, blue, alpha.
+    Rgba,
+}
+
+impl From<RawFormatArg> for RawPixelFormat {
+    fn from(value: RawFormatArg) -> Self {
+        match value {
+            RawFormatArg::Gray => RawPixelFormat::Gray8,
+            RawFormatArg::Rgb24 => RawPixelFormat::Rgb24,
+            RawFormatArg::Rgba => RawPixelFormat::Rgba,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum CharsetArg {
+    /// ` .:-=+*#%@`
+    Standard,
+    /// ` .oO@`
+    Simple,
+    /// Shade blocks, ` ░▒▓█`.
+    Blocks,
+    /// ` #`
+    Binary,
+}
+
+impl CharsetArg {
+    /// The glyphs, darkest first.
+    pub fn ramp(&self) -> &'static str {
+        match self {
+            CharsetArg::Standard => " .:-=+*#%@",
+            CharsetArg::Simple => " .oO@",
+            CharsetArg::Blocks => " ░▒▓█",
+            CharsetArg::Binary => " #",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum EdgeModeArg {
+    /// Edges only.
+    LineArt,
+    /// Edges over the regular luma fill.
+    Hybrid,
+}
+
+impl From<EdgeModeArg> for LineArtMode {
+    fn from(value: EdgeModeArg) -> Self {
+        match value {
+            EdgeModeArg::LineArt => LineArtMode::Outline,
+            EdgeModeArg::Hybrid => LineArtMode::Hybrid,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum EdgeDetectorArg {
+    Sobel,
+    Scharr,
+    Canny,
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ResamplerArg {
+    /// ffmpeg swscale, bilinear.
+    Swscale,
+    /// ffmpeg swscale, area averaging.
+    SwscaleArea,
+    /// ffmpeg swscale, bicubic.
+    SwscaleBicubic,
+    /// ffmpeg swscale, Lanczos.
+    SwscaleLanczos,
+    Nearest,
+    Box,
+    Bilinear,
+    Bicubic,
+    Lanczos3,
+}
+
+impl ResamplerArg {
+    /// The lib-image filter to resize with, or `None` when swscale resizes.
+    pub fn filter(&self) -> Option<ResampleFilter> {
+        match self {
+            ResamplerArg::Swscale
+            | ResamplerArg::SwscaleArea
+            | ResamplerArg::SwscaleBicubic
+            | ResamplerArg::SwscaleLanczos => None,
+            ResamplerArg::Nearest => Some(ResampleFilter::Nearest),
+            ResamplerArg::Box => Some(ResampleFilter::Box),
+            ResamplerArg::Bilinear => Some(ResampleFilter::Bilinear),
+            ResamplerArg::Bicubic => Some(ResampleFilter::Bicubic),
+            ResamplerArg::Lanczos3 => Some(ResampleFilter::Lanczos3),
+        }
+    }
+
+    pub fn swscale_flags(&self) -> Flags {
+        match self {
+            ResamplerArg::SwscaleArea => Flags::AREA,
+            ResamplerArg::SwscaleBicubic => Flags::BICUBIC,
+            ResamplerArg::SwscaleLanczos => Flags::LANCZOS,
+            // Only pixel format conversion is left to swscale for lib-image filters.
+            _ => Flags::BILINEAR,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum FitArg {
+    /// Whole frame, centred, with bars.
+    Contain,
+    /// Fill the terminal, cropping overflow.
+    Cover,
+    /// Fill the terminal, ignoring aspect ratio.
+    Stretch,
+}
+
+impl From<FitArg> for FitMode {
+    fn from(value: FitArg) -> Self {
+        match value {
+            FitArg::Contain => FitMode::Contain,
+            FitArg::Cover => FitMode::Cover,
+            FitArg::Stretch => FitMode::Stretch,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum BannerFilterArg {
+    /// Diagonal rainbow stripes.
+    Rainbow,
+    /// Blue and gray bands.
+    Metal,
+}
+
+impl From<BannerFilterArg> for BannerFilter {
+    fn from(value: BannerFilterArg) -> Self {
+        match value {
+            BannerFilterArg::Rainbow => BannerFilter::Rainbow,
+            BannerFilterArg::Metal => BannerFilter::Metal,
+        }
+    }
+}
+
+/// Where `--subtitles` come from.
+#[derive(Debug, Clone)]
+pub enum SubtitleSource {
+    File(SubtitleTrack),
+    /// A subtitle stream of the video: the one with this index, or else the best one ffmpeg
+    /// finds.
+    Embedded(Option<usize>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum SubtitlePositionArg {
+    /// Over the bottom of the frame, or wherever the subtitles place themselves.
+    Over,
+    /// In `--subtitle-rows` rows of their own below the frame.
+    Under,
+}
+
+fn parse_subtitles(value: &str) -> Result<SubtitleSource, String> {
+    match value.strip_prefix("embedded") {
+        Some("") => Ok(SubtitleSource::Embedded(None)),
+        Some(index) if index.starts_with(':') => index[1..]
+            .parse::<usize>()
+            .map(|index: usize| SubtitleSource::Embedded(Some(index)))
+            .map_err(|e| e.to_string()),
+        _ => SubtitleTrack::load(value)
+            .map(SubtitleSource::File)
+            .map_err(|e| e.to_string()),
+    }
+}
+
+fn parse_font(value: &str) -> Result<FigletFont, String> {
+    match FigletFont::bundled(value) {
+        Some(font) => Ok(font),
+        None => FigletFont::load(value).map_err(|e| e.to_string()),
+    }
+}
+
+fn parse_pan(value: &str) -> Result<(f32, f32), String> {
+    let (x, y) = value
+        .split_once(',')
+        .ok_or_else(|| format!("expected 'x,y', got '{value}'"))?;
+    let parse = |v: &str| v.trim().parse::<f32>().map_err(|e| e.to_string());
+
+    Ok((parse(x)?, parse(y)?))
+}
+
+pub fn parse_size(value: &str) -> Result<(usize, usize), String> {
+    let (width, height) = value
+        .split_once('x')
+        .ok_or_else(|| format!("expected 'WxH', got '{value}'"))?;
+    let parse = |v: &str| v.trim().parse::<usize>().map_err(|e| e.to_string());
+
+    Ok((parse(width)?, parse(height)?))
+}
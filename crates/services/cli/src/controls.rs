@@ -0,0 +1,36 @@
+use std::{io, time::Duration};
+
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+
+/// Keyboard commands accepted while frames are playing.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PlaybackCommand {
+    Quit,
+    ToggleAutoExposure,
+}
+
+/// Drains pending terminal events without blocking and returns the last recognised command.
+pub fn poll_command() -> io::Result<Option<PlaybackCommand>> {
+    let mut command: Option<PlaybackCommand> = None;
+
+    while event::poll(Duration::ZERO)? {
+        if let Event::Key(KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            ..
+        }) = event::read()?
+        {
+            match code {
+                KeyCode::Char('q') | KeyCode::Esc => return Ok(Some(PlaybackCommand::Quit)),
+                KeyCode::Char('c') if modifiers.contains(KeyModifiers::CONTROL) => {
+                    return Ok(Some(PlaybackCommand::Quit));
+                }
+                KeyCode::Char('e') => command = Some(PlaybackCommand::ToggleAutoExposure),
+                _ => {}
+            }
+        }
+    }
+
+    Ok(command)
+}
@@ -0,0 +1,237 @@
+use std::{io, time::Duration};
+
+use ffmpeg_next::format::Pixel;
+use lib_image::domain::codecs::{DecodeError, DecodedImage, FrameSource, LoopCount, TimedFrame};
+use lib_image::domain::entities::{
+    GlyphImage, InvalidImageDataError, LumaImage, RgbImage, RgbaImage,
+};
+
+use crate::frame_clock::TimestampDelays;
+
+/// Widest frame [`FfmpegFrames`] hands out; larger videos are scaled down to it.
+const MAX_WIDTH: u32 = 640;
+
+/// Pixel format frames are converted to: RGBA keeps transparency, RGB24 keeps color.
+pub fn output_format(color: bool, source: Pixel) -> Pixel {
+    // Formats with a fourth component, or gray plus alpha, carry an alpha channel.
+    let has_alpha: bool = source
+        .descriptor()
+        .is_some_and(|d| matches!(d.nb_components(), 2 | 4));
+
+    if has_alpha {
+        Pixel::RGBA
+    } else if color {
+        Pixel::RGB24
+    } else {
+        Pixel::GRAY8
+    }
+}
+
+/// Wraps a frame converted to RGBA, RGB24 or GRAY8 as a lib-image image.
+pub fn decoded_image(
+    frame: &ffmpeg_next::frame::Video,
+) -> Result<DecodedImage, InvalidImageDataError> {
+    let width = frame.width() as usize;
+    let height = frame.height() as usize;
+    let data: Vec<u8> = frame.data(0).to_vec();
+
+    Ok(match frame.format() {
+        Pixel::RGBA => DecodedImage::Rgba(RgbaImage::from_strided_image_data(
+            data,
+            width,
+            height,
+            frame.stride(0),
+        )?),
+        Pixel::RGB24 => DecodedImage::Rgb(RgbImage::from_strided_image_data(
+            data,
+            width,
+            height,
+            frame.stride(0),
+        )?),
+        _ => DecodedImage::Luma(LumaImage::from_strided_image_data(
+            data,
+            width,
+            height,
+            frame.stride(0),
+        )?),
+    })
+}
+
+/// Paces frames by their own timestamps; the stream's average rate only fills gaps.
+pub fn timestamp_delays(stream: &ffmpeg_next::Stream<'_>) -> TimestampDelays {
+    let frame_rate: ffmpeg_next::Rational = stream.avg_frame_rate();
+
+    let duration_per_frame: Duration =
+        if frame_rate.denominator() != 0 && frame_rate.numerator() != 0 {
+            Duration::from_secs_f64(frame_rate.denominator() as f64 / frame_rate.numerator() as f64)
+        } else {
+            Duration::from_millis(40) // fallback ~25fps
+        };
+
+    let time_base: ffmpeg_next::Rational = stream.time_base();
+
+    TimestampDelays::new(
+        (time_base.numerator(), time_base.denominator()),
+        duration_per_frame,
+    )
+}
+
+/// Frames of a video decoded by ffmpeg at source resolution, up to [`MAX_WIDTH`] pixels wide.
+///
+/// Unlike the terminal player, which lets swscale resize straight to the terminal, this keeps
+/// frames independent of any one output size so they can be rendered at several.
+pub struct FfmpegFrames {
+    path: String,
+    color: bool,
+    input: ffmpeg_next::format::context::Input,
+    decoder: ffmpeg_next::decoder::Video,
+    stream_index: usize,
+    scaler: Option<Scaler>,
+    delays: TimestampDelays,
+    /// A frame's delay is only known once the next frame's timestamp is.
+    pending: Option<DecodedImage>,
+    flushed: bool,
+}
+
+/// A swscale context and the source format and size it was created for.
+struct Scaler {
+    context: ffmpeg_next::software::scaling::context::Context,
+    source: (Pixel, u32, u32),
+}
+
+impl FfmpegFrames {
+    /// Opens the best video stream of `path`; `color` keeps RGB instead of converting to luma.
+    pub fn open(path: &str, color: bool) -> Result<Self, DecodeError> {
+        ffmpeg_next::init().map_err(ffmpeg_error)?;
+
+        let input: ffmpeg_next::format::context::Input =
+            ffmpeg_next::format::input(path).map_err(ffmpeg_error)?;
+        let stream: ffmpeg_next::Stream<'_> = input
+            .streams()
+            .best(ffmpeg_next::media::Type::Video)
+            .ok_or_else(|| ffmpeg_error(ffmpeg_next::Error::StreamNotFound))?;
+        let stream_index: usize = stream.index();
+        let delays: TimestampDelays = timestamp_delays(&stream);
+        let decoder: ffmpeg_next::decoder::Video =
+            ffmpeg_next::codec::context::Context::from_parameters(stream.parameters())
+                .and_then(|context| context.decoder().video())
+                .map_err(ffmpeg_error)?;
+
+        Ok(Self {
+            path: path.to_string(),
+            color,
+            input,
+            decoder,
+            stream_index,
+            scaler: None,
+            delays,
+            pending: None,
+            flushed: false,
+        })
+    }
+
+    /// Decodes the next frame and converts it, or returns `None` once the decoder is drained.
+    fn decode_next(&mut self) -> Result<Option<(DecodedImage, Option<i64>)>, DecodeError> {
+        let mut frame: ffmpeg_next::frame::Video = ffmpeg_next::frame::Video::empty();
+
+        while self.decoder.receive_frame(&mut frame).is_err() {
+            if self.flushed {
+                return Ok(None);
+            }
+
+            let mut packet: ffmpeg_next::Packet = ffmpeg_next::Packet::empty();
+            match packet.read(&mut self.input) {
+                Ok(()) if packet.stream() == self.stream_index => {
+                    self.decoder.send_packet(&packet).map_err(ffmpeg_error)?
+                }
+                Ok(()) => {}
+                Err(ffmpeg_next::Error::Eof) => {
+                    self.decoder.send_eof().map_err(ffmpeg_error)?;
+                    self.flushed = true;
+                }
+                Err(error) => return Err(ffmpeg_error(error)),
+            }
+        }
+
+        let image: DecodedImage = self.convert(&frame)?;
+
+        Ok(Some((image, frame.timestamp())))
+    }
+
+    fn convert(&mut self, frame: &ffmpeg_next::frame::Video) -> Result<DecodedImage, DecodeError> {
+        let source: (Pixel, u32, u32) = (frame.format(), frame.width(), frame.height());
+
+        if self.scaler.as_ref().map(|scaler| scaler.source) != Some(source) {
+            let (width, height): (u32, u32) = match source.1 > MAX_WIDTH {
+                true => (MAX_WIDTH, (source.2 * MAX_WIDTH / source.1).max(1)),
+                false => (source.1, source.2),
+            };
+
+            self.scaler = Some(Scaler {
+                context: ffmpeg_next::software::scaling::context::Context::get(
+                    source.0,
+                    source.1,
+                    source.2,
+                    output_format(self.color, source.0),
+                    width.max(1),
+                    height.max(1),
+                    ffmpeg_next::software::scaling::flag::Flags::AREA,
+                )
+                .map_err(ffmpeg_error)?,
+                source,
+            });
+        }
+
+        let mut converted: ffmpeg_next::frame::Video = ffmpeg_next::frame::Video::empty();
+        if let Some(scaler) = &mut self.scaler {
+            scaler
+                .context
+                .run(frame, &mut converted)
+                .map_err(ffmpeg_error)?;
+        }
+
+        decoded_image(&converted).map_err(|error| DecodeError::Io(io::Error::other(error)))
+    }
+}
+
+impl FrameSource for FfmpegFrames {
+    fn next_frame(&mut self) -> Option<Result<TimedFrame, DecodeError>> {
+        loop {
+            match self.decode_next() {
+                Ok(Some((image, timestamp))) => {
+                    let delay: Duration = self.delays.next(timestamp);
+
+                    if let Some(previous) = self.pending.replace(image) {
+                        return Some(Ok(TimedFrame {
+                            image: previous,
+                            delay,
+                        }));
+                    }
+                }
+                // The last frame lasts as long as an average one.
+                Ok(None) => {
+                    return self.pending.take().map(|image| {
+                        Ok(TimedFrame {
+                            image,
+                            delay: self.delays.fallback(),
+                        })
+                    });
+                }
+                Err(error) => return Some(Err(error)),
+            }
+        }
+    }
+
+    fn rewind(&mut self) -> Result<(), DecodeError> {
+        *self = Self::open(&self.path, self.color)?;
+        Ok(())
+    }
+
+    fn loop_count(&self) -> LoopCount {
+        LoopCount::Finite(1)
+    }
+}
+
+fn ffmpeg_error(error: ffmpeg_next::Error) -> DecodeError {
+    DecodeError::Io(io::Error::other(error))
+}
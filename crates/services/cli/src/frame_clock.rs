@@ -0,0 +1,86 @@
+use std::time::{Duration, Instant};
+
+/// Paces playback so each frame stays on screen for its own delay.
+///
+/// Delays are measured from deadline to deadline rather than from the end of rendering, so the
+/// time spent drawing a frame is not added on top. When rendering falls behind, the lost time is
+/// dropped instead of being made up with a burst of frames.
+#[derive(Debug, Default)]
+pub struct FrameClock {
+    deadline: Option<Instant>,
+    missed: u64,
+}
+
+impl FrameClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sleeps until `delay` has passed since the previous deadline.
+    pub fn wait(&mut self, delay: Duration) {
+        let now: Instant = Instant::now();
+        let deadline: Instant = self.deadline.unwrap_or(now) + delay;
+
+        match deadline.checked_duration_since(now) {
+            Some(remaining) => {
+                std::thread::sleep(remaining);
+                self.deadline = Some(deadline);
+            }
+            None => {
+                self.deadline = Some(now);
+                self.missed += 1;
+            }
+        }
+    }
+
+    /// How many frames were ready only after their deadline had passed, because rendering fell
+    /// behind.
+    pub fn missed(&self) -> u64 {
+        self.missed
+    }
+}
+
+/// Turns presentation timestamps into the delay before each frame is shown.
+#[derive(Debug)]
+pub struct TimestampDelays {
+    time_base: (i32, i32),
+    fallback: Duration,
+    previous: Option<Option<i64>>,
+}
+
+impl TimestampDelays {
+    /// `fallback` is used whenever timestamps are missing or out of order.
+    pub fn new(time_base: (i32, i32), fallback: Duration) -> Self {
+        Self {
+            time_base,
+            fallback,
+            previous: None,
+        }
+    }
+
+    /// The delay used when timestamps cannot tell.
+    pub fn fallback(&self) -> Duration {
+        self.fallback
+    }
+
+    /// How long the previous frame stays on screen before the one stamped `timestamp`.
+    pub fn next(&mut self, timestamp: Option<i64>) -> Duration {
+        match self.previous.replace(timestamp) {
+            None => Duration::ZERO,
+            Some(previous) => self.between(previous, timestamp).unwrap_or(self.fallback),
+        }
+    }
+
+    fn between(&self, previous: Option<i64>, current: Option<i64>) -> Option<Duration> {
+        let (numerator, denominator) = self.time_base;
+        let ticks: i64 = current?.checked_sub(previous?)?;
+
+        if ticks <= 0 || numerator <= 0 || denominator <= 0 {
+            return None;
+        }
+
+        Some(Duration::from_secs_f64(
+            ticks as f64 * numerator as f64 / denominator as f64,
+        ))
+    }
+}
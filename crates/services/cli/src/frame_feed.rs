@@ -0,0 +1,110 @@
+use std::{
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    time::Duration,
+};
+
+use lib_image::domain::codecs::DecodedImage;
+
+/// The latest frame of a shared playback, handed to any number of readers.
+///
+/// Publishing replaces the frame without waiting for anyone, so a slow reader skips frames
+/// instead of holding back the player and every other reader.
+#[derive(Clone, Default)]
+pub struct FrameFeed {
+    shared: Arc<Shared>,
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<State>,
+    changed: Condvar,
+}
+
+#[derive(Default)]
+struct State {
+    sequence: u64,
+    frame: Option<Arc<DecodedImage>>,
+    closed: bool,
+}
+
+/// What a [`FeedReader`] got while waiting.
+pub enum FeedEvent {
+    Frame(Arc<DecodedImage>),
+    Timeout,
+    Closed,
+}
+
+impl FrameFeed {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn publish(&self, image: DecodedImage) {
+        let mut state: MutexGuard<'_, State> = self.lock();
+        state.sequence += 1;
+        state.frame = Some(Arc::new(image));
+        self.shared.changed.notify_all();
+    }
+
+    /// Tells readers that no more frames are coming.
+    pub fn close(&self) {
+        self.lock().closed = true;
+        self.shared.changed.notify_all();
+    }
+
+    /// A reader that starts with the current frame, if there is one.
+    pub fn reader(&self) -> FeedReader {
+        FeedReader {
+            shared: self.shared.clone(),
+            seen: 0,
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        lock(&self.shared)
+    }
+}
+
+/// Follows a [`FrameFeed`], returning each frame it has not returned yet.
+pub struct FeedReader {
+    shared: Arc<Shared>,
+    seen: u64,
+}
+
+impl FeedReader {
+    /// Waits up to `timeout` for a frame newer than the last one returned.
+    pub fn next(&mut self, timeout: Duration) -> FeedEvent {
+        let state: MutexGuard<'_, State> = lock(&self.shared);
+        let (state, _) = self
+            .shared
+            .changed
+            .wait_timeout_while(state, timeout, |state: &mut State| {
+                state.sequence == self.seen && !state.closed
+            })
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        match &state.frame {
+            Some(frame) if state.sequence != self.seen => {
+                self.seen = state.sequence;
+                FeedEvent::Frame(frame.clone())
+            }
+            _ if state.closed => FeedEvent::Closed,
+            _ => FeedEvent::Timeout,
+        }
+    }
+
+    /// The latest frame, to draw again after a resize, once this reader has had one.
+    pub fn current(&self) -> Option<Arc<DecodedImage>> {
+        let state: MutexGuard<'_, State> = lock(&self.shared);
+
+        state.frame.clone().filter(|_| self.seen != 0)
+    }
+}
+
+fn lock(shared: &Shared) -> MutexGuard<'_, State> {
+    // A reader panicking mid-frame leaves nothing half-written worth refusing.
+    shared
+        .state
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
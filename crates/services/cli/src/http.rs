@@ -0,0 +1,469 @@
+use std::{
+    fmt,
+    io::{self, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::Arc,
+    thread,
+    time::Duration,
+};
+
+use clap::ValueEnum;
+use lib_image::domain::codecs::DecodedImage;
+use lib_image::domain::entities::{AutoExposure, PlacementLayout};
+
+use crate::args::{Args, CharsetArg};
+use crate::frame_feed::{FeedEvent, FeedReader, FrameFeed};
+use crate::render::{self, FrameOverlays, TextStyle};
+use crate::viewer;
+
+/// The ANSI stream.
+const STREAM_PATH: &str = "/video";
+
+/// The browser viewer page, which connects back to [`SOCKET_PATH`].
+const VIEWER_PATH: &str = "/";
+
+/// WebSocket frames for the viewer, each viewer with its own playback.
+const SOCKET_PATH: &str = "/ws";
+
+/// Longest request head read; anything bigger is not a request for the stream.
+const MAX_REQUEST_HEAD: usize = 8 * 1024;
+
+/// How long a client has to send its request, and a write may block before it is dropped.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Widest frame a query may ask for.
+const MAX_COLUMNS: usize = 1000;
+
+/// User agents that show a response in a terminal, and so get frames repainted in place.
+const TERMINAL_AGENTS: [&str; 4] = ["curl/", "wget/", "httpie/", "xh/"];
+
+/// What one `GET /video` asked for.
+#[derive(Debug, PartialEq)]
+struct StreamRequest {
+    columns: usize,
+    /// Rows to fit frames into; by default as many as the frame needs at `columns`.
+    rows: Option<usize>,
+    style: TextStyle,
+    /// Repaint frames in place with ANSI escapes instead of writing them one after another.
+    ansi: bool,
+}
+
+/// A request that is answered with an error status instead of the stream.
+#[derive(Debug, PartialEq)]
+enum RequestError {
+    BadRequest(String),
+    NotFound,
+    MethodNotAllowed,
+}
+
+impl RequestError {
+    fn status(&self) -> &'static str {
+        match self {
+            RequestError::BadRequest(_) => "400 Bad Request",
+            RequestError::NotFound => "404 Not Found",
+            RequestError::MethodNotAllowed => "405 Method Not Allowed",
+        }
+    }
+}
+
+impl fmt::Display for RequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RequestError::BadRequest(reason) => write!(f, "{reason}"),
+            RequestError::NotFound => write!(
+                f,
+                "the viewer is at {VIEWER_PATH} and the stream at {STREAM_PATH}"
+            ),
+            RequestError::MethodNotAllowed => write!(f, "only GET is supported"),
+        }
+    }
+}
+
+/// Accepts HTTP clients on `listener` in the background, each on its own thread. Stream clients
+/// follow `feed`; viewers play `entries` for themselves.
+pub fn spawn(listener: TcpListener, feed: FrameFeed, entries: Arc<Vec<String>>, args: Arc<Args>) {
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream: TcpStream = match stream {
+                Ok(stream) => stream,
+                Err(error) => {
+                    eprintln!("HTTP accept failed: {error}");
+                    continue;
+                }
+            };
+            let frames: FeedReader = feed.reader();
+            let entries: Arc<Vec<String>> = entries.clone();
+            let args: Arc<Args> = args.clone();
+
+            thread::spawn(move || {
+                // Errors here are clients hanging up or falling too far behind.
+                let _ = serve_client(stream, frames, &entries, &args);
+            });
+        }
+    });
+}
+
+/// Answers one request. The stream is a chunked response, one chunk per frame, until the client
+/// hangs up, stalls or the playback ends.
+fn serve_client(
+    mut stream: TcpStream,
+    mut frames: FeedReader,
+    entries: &[String],
+    args: &Args,
+) -> io::Result<()> {
+    stream.set_nodelay(true)?;
+    stream.set_read_timeout(Some(TIMEOUT))?;
+    stream.set_write_timeout(Some(TIMEOUT))?;
+
+    let head: String = read_request_head(&mut stream)?;
+    let request: StreamRequest = match route(&head, args) {
+        Ok(Route::Stream(request)) => request,
+        Ok(Route::Viewer) => {
+            return write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: text/html; charset=utf-8\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                viewer::PAGE.len(),
+                viewer::PAGE
+            );
+        }
+        Ok(Route::Socket(key)) => return viewer::serve(stream, &key, entries, args),
+        Err(error) => {
+            let body: String = format!("{error}\n");
+            return write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Type: text/plain; charset=utf-8\r\n\
+                 Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                error.status(),
+                body.len()
+            );
+        }
+    };
+
+    stream.write_all(
+        b"HTTP/1.1 200 OK\r\nContent-Type: text/plain; charset=utf-8\r\n\
+          Transfer-Encoding: chunked\r\nCache-Control: no-cache\r\n\
+          X-Content-Type-Options: nosniff\r\nConnection: close\r\n\r\n",
+    )?;
+    if request.ansi {
+        write_chunk(&mut stream, b"\x1b[2J")?;
+    }
+
+    let mut auto_exposure: AutoExposure = args.auto_exposure();
+    loop {
+        match frames.next(TIMEOUT) {
+            FeedEvent::Frame(image) => {
+                let text: Vec<u8> = frame(&image, &request, args, &mut auto_exposure);
+                write_chunk(&mut stream, &text)?;
+            }
+            FeedEvent::Timeout => {}
+            FeedEvent::Closed => break,
+        }
+    }
+
+    stream.write_all(b"0\r\n\r\n")
+}
+
+/// Reads up to the blank line ending the request head; the body, if any, is ignored.
+fn read_request_head(stream: &mut TcpStream) -> io::Result<String> {
+    let mut head: Vec<u8> = Vec::new();
+    let mut buffer: [u8; 1024] = [0; 1024];
+
+    while !head.windows(4).any(|end: &[u8]| end == b"\r\n\r\n") {
+        if head.len() > MAX_REQUEST_HEAD {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "request head too long",
+            ));
+        }
+
+        match stream.read(&mut buffer)? {
+            0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+            read => head.extend_from_slice(&buffer[..read]),
+        }
+    }
+
+    Ok(String::from_utf8_lossy(&head).into_owned())
+}
+
+/// What a request is for.
+#[derive(Debug, PartialEq)]
+enum Route {
+    /// The browser viewer page.
+    Viewer,
+    /// The viewer's WebSocket upgrade, with the client's `Sec-WebSocket-Key`.
+    Socket(String),
+    Stream(StreamRequest),
+}
+
+/// Reads the request line and headers, and for the stream its query parameters, with `args`
+/// supplying the defaults.
+fn route(head: &str, args: &Args) -> Result<Route, RequestError> {
+    let request_line: &str = head.lines().next().unwrap_or_default();
+    let (method, target): (&str, &str) = match request_line.split_whitespace().collect::<Vec<_>>()[..]
+    {
+        [method, target, _version] => (method, target),
+        _ => {
+            return Err(RequestError::BadRequest(
+                "malformed request line".to_string(),
+            ));
+        }
+    };
+    let (path, query): (&str, &str) = target.split_once('?').unwrap_or((target, ""));
+
+    if ![VIEWER_PATH, SOCKET_PATH, STREAM_PATH].contains(&path) {
+        return Err(RequestError::NotFound);
+    }
+    if method != "GET" {
+        return Err(RequestError::MethodNotAllowed);
+    }
+
+    match path {
+        VIEWER_PATH => return Ok(Route::Viewer),
+        SOCKET_PATH => {
+            return header(head, "sec-websocket-key")
+                .map(|key: &str| Route::Socket(key.to_string()))
+                .ok_or_else(|| {
+                    RequestError::BadRequest("expected a WebSocket upgrade".to_string())
+                });
+        }
+        _ => {}
+    }
+
+    let user_agent: Option<&str> = header(head, "user-agent");
+    let mut request: StreamRequest = StreamRequest {
+        columns: args.client_size.0,
+        rows: None,
+        style: TextStyle::from(args),
+        ansi: is_terminal_agent(user_agent),
+    };
+
+    for (name, value) in query
+        .split('&')
+        .filter(|pair: &&str| !pair.is_empty())
+        .map(|pair: &str| pair.split_once('=').unwrap_or((pair, "")))
+    {
+        let invalid = || RequestError::BadRequest(format!("invalid {name}: '{value}'"));
+
+        match name {
+            "width" => request.columns = parse_cells(value).ok_or_else(invalid)?,
+            "height" => request.rows = Some(parse_cells(value).ok_or_else(invalid)?),
+            "charset" => {
+                request.style.charset = CharsetArg::from_str(value, true).map_err(|_| invalid())?
+            }
+            "color" => request.style.color = parse_flag(value).ok_or_else(invalid)?,
+            "mode" => {
+                request.ansi = match value {
+                    "ansi" => true,
+                    "plain" => false,
+                    _ => return Err(invalid()),
+                }
+            }
+            _ => {
+                return Err(RequestError::BadRequest(format!(
+                    "unknown parameter '{name}'"
+                )));
+            }
+        }
+    }
+
+    // Color is drawn with escapes, so plain frames are monochrome.
+    request.style.color &= request.ansi;
+
+    Ok(Route::Stream(request))
+}
+
+/// The value of the first header called `name`, ignoring case.
+fn header<'a>(head: &'a str, name: &str) -> Option<&'a str> {
+    head.lines().skip(1).find_map(|line: &str| {
+        let (header, value) = line.split_once(':')?;
+
+        header
+            .trim()
+            .eq_ignore_ascii_case(name)
+            .then_some(value.trim())
+    })
+}
+
+fn parse_cells(value: &str) -> Option<usize> {
+    value
+        .parse::<usize>()
+        .ok()
+        .filter(|cells: &usize| (1..=MAX_COLUMNS).contains(cells))
+}
+
+fn parse_flag(value: &str) -> Option<bool> {
+    match value {
+        "" | "1" | "true" | "on" | "yes" => Some(true),
+        "0" | "false" | "off" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// Whether a client renders escapes: command-line tools do, browsers show them as garbage. A
+/// client that sends no user agent is assumed to be a bare tool like `nc`.
+fn is_terminal_agent(user_agent: Option<&str>) -> bool {
+    user_agent.is_none_or(|agent: &str| {
+        let agent: String = agent.to_ascii_lowercase();
+
+        TERMINAL_AGENTS
+            .iter()
+            .any(|prefix: &&str| agent.starts_with(prefix))
+    })
+}
+
+/// One frame's chunk: repainted over the previous one in ANSI mode, otherwise appended after a
+/// blank line.
+fn frame(
+    image: &DecodedImage,
+    request: &StreamRequest,
+    args: &Args,
+    auto_exposure: &mut AutoExposure,
+) -> Vec<u8> {
+    // Frames are as tall as their aspect needs at the requested width, unless told otherwise.
+    let rows: usize = request.rows.unwrap_or_else(|| {
+        let rows: f32 = request.columns as f32 * image.height() as f32
+            / image.width().max(1) as f32
+            / args.cell_aspect.max(f32::EPSILON);
+
+        (rows.round() as usize).clamp(1, MAX_COLUMNS)
+    });
+    let layout: PlacementLayout =
+        args.placement()
+            .layout(image.width(), image.height(), request.columns, rows);
+    let text: String = render::render_text(
+        image.clone(),
+        &layout,
+        args,
+        auto_exposure,
+        request.style,
+        &FrameOverlays::default(),
+    );
+
+    match request.ansi {
+        true => format!("\x1b[H{text}\x1b[J"),
+        false => format!("{text}\n\n"),
+    }
+    .into_bytes()
+}
+
+fn write_chunk(stream: &mut TcpStream, data: &[u8]) -> io::Result<()> {
+    write!(stream, "{:x}\r\n", data.len())?;
+    stream.write_all(data)?;
+    stream.write_all(b"\r\n")
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::{Read, Write},
+        net::{TcpListener, TcpStream},
+        sync::Arc,
+    };
+
+    use clap::Parser;
+    use lib_image::domain::codecs::DecodedImage;
+    use lib_image::domain::entities::LumaImage;
+    use pretty_assertions::assert_eq;
+
+    use crate::args::{Args, CharsetArg};
+    use crate::frame_feed::FrameFeed;
+    use crate::http::{RequestError, Route, StreamRequest, route, spawn};
+    use crate::render::TextStyle;
+
+    fn args() -> Args {
+        Args::parse_from(["glyphforge-cli", "unused"])
+    }
+
+    #[test]
+    fn query_overrides_defaults_and_terminal_agents_get_ansi() {
+        let head: &str = "GET /video?width=40&height=12&charset=blocks&color=1 HTTP/1.1\r\n\
+                          Host: localhost\r\nUser-Agent: curl/8.5.0\r\n\r\n";
+
+        let route: Result<Route, RequestError> = route(head, &args());
+
+        assert_eq!(
+            route,
+            Ok(Route::Stream(StreamRequest {
+                columns: 40,
+                rows: Some(12),
+                style: TextStyle {
+                    color: true,
+                    charset: CharsetArg::Blocks,
+                },
+                ansi: true,
+            }))
+        );
+    }
+
+    #[test]
+    fn browsers_get_plain_monochrome_frames() {
+        let head: &str = "GET /video?color=1 HTTP/1.1\r\nUser-Agent: Mozilla/5.0\r\n\r\n";
+
+        let Ok(Route::Stream(request)) = route(head, &args()) else {
+            panic!("expected the stream");
+        };
+
+        assert!(!request.ansi);
+        assert!(!request.style.color);
+    }
+
+    #[test]
+    fn bad_requests_are_refused() {
+        let refusal = |head: &str| route(head, &args()).err();
+
+        assert_eq!(
+            refusal("GET /index.html HTTP/1.1\r\n\r\n"),
+            Some(RequestError::NotFound)
+        );
+        assert_eq!(
+            refusal("POST /video HTTP/1.1\r\n\r\n"),
+            Some(RequestError::MethodNotAllowed)
+        );
+        assert_eq!(
+            refusal("GET /video?width=0 HTTP/1.1\r\n\r\n"),
+            Some(RequestError::BadRequest("invalid width: '0'".to_string()))
+        );
+        assert_eq!(
+            refusal("GET /ws HTTP/1.1\r\n\r\n"),
+            Some(RequestError::BadRequest(
+                "expected a WebSocket upgrade".to_string()
+            ))
+        );
+    }
+
+    #[test]
+    fn stream_is_sent_as_chunked_frames() {
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let feed: FrameFeed = FrameFeed::new();
+        spawn(
+            listener,
+            feed.clone(),
+            Arc::new(Vec::new()),
+            Arc::new(args()),
+        );
+
+        let mut client: TcpStream = TcpStream::connect(address).unwrap();
+        client
+            .write_all(b"GET /video?width=8&mode=plain HTTP/1.1\r\n\r\n")
+            .unwrap();
+        feed.publish(DecodedImage::Luma(LumaImage::from_luma_data(
+            4,
+            2,
+            vec![255; 8],
+        )));
+        feed.close();
+
+        let mut response: String = String::new();
+        client.read_to_string(&mut response).unwrap();
+
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        assert!(head.starts_with("HTTP/1.1 200 OK"));
+        assert!(head.contains("Transfer-Encoding: chunked"));
+        assert_eq!(
+            body,
+            "25\r\n@@@@@@@@\n@@@@@@@@\n@@@@@@@@\n@@@@@@@@\n\n\r\n0\r\n\r\n"
+        );
+    }
+}
@@ -0,0 +1,284 @@
+use std::{
+    collections::VecDeque,
+    fmt,
+    time::{Duration, Instant},
+};
+
+use clap::ValueEnum;
+use lib_core::domain::entities::{Glyph, GlyphRenderingEngine};
+use lib_image::domain::entities::PlacementLayout;
+
+use crate::args::Args;
+
+/// How far back [`FpsMeter`] looks.
+const FPS_WINDOW: Duration = Duration::from_secs(1);
+
+/// Figures shown on the `--hud` status line with one frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HudStatus {
+    /// Time since the start of the video.
+    pub position: Duration,
+    /// Length of the video, when the container knows it.
+    pub duration: Option<Duration>,
+    /// Frame size in pixels.
+    pub source: (usize, usize),
+    /// Size the frame is drawn at, in cells.
+    pub grid: (usize, usize),
+    pub mode: String,
+    /// Frames drawn per second lately, once there are enough to tell.
+    pub fps: Option<f64>,
+    /// Frames that missed their deadline because rendering fell behind.
+    pub dropped: u64,
+}
+
+impl HudStatus {
+    /// The status line, exactly `width` cells wide: the position and duration, a progress bar
+    /// filling whatever the other figures leave, then the sizes, mode, frame rate and dropped
+    /// frames. What does not fit is cut off at the right.
+    pub fn line(&self, width: usize) -> String {
+        let time: String = match self.duration {
+            Some(duration) => format!("{} / {}", timecode(self.position), timecode(duration)),
+            None => timecode(self.position),
+        };
+        let fps: String = self
+            .fps
+            .map_or_else(|| "--".to_string(), |fps: f64| format!("{fps:.1}"));
+        let stats: String = format!(
+            "{}x{}→{}x{} {} {fps} fps {} dropped",
+            self.source.0, self.source.1, self.grid.0, self.grid.1, self.mode, self.dropped
+        );
+        // The bar and its brackets go between the time and the stats, a space either side.
+        let bar_width: usize =
+            width.saturating_sub(time.chars().count() + stats.chars().count() + 4);
+
+        let line: String = match self.duration {
+            Some(duration) if bar_width >= 3 => {
+                let progress: f64 = if duration.is_zero() {
+                    0.0
+                } else {
+                    (self.position.as_secs_f64() / duration.as_secs_f64()).clamp(0.0, 1.0)
+                };
+                let filled: usize = (progress * bar_width as f64).round() as usize;
+
+                format!(
+                    "{time} [{}{}] {stats}",
+                    "█".repeat(filled),
+                    "░".repeat(bar_width - filled)
+                )
+            }
+            _ => format!("{time} {stats}"),
+        };
+
+        line.chars()
+            .chain(std::iter::repeat(' '))
+            .take(width)
+            .collect()
+    }
+}
+
+/// Gathers the `--hud` figures as playback goes on.
+#[derive(Debug)]
+pub struct Hud {
+    duration: Option<Duration>,
+    mode: String,
+    fps: FpsMeter,
+}
+
+impl Hud {
+    pub fn new(args: &Args, duration: Option<Duration>) -> Self {
+        Self {
+            duration,
+            mode: render_mode(args),
+            fps: FpsMeter::new(),
+        }
+    }
+
+    /// Status for a frame of `source` pixels about to be drawn at `position`, counting it toward
+    /// the frame rate.
+    pub fn frame(
+        &mut self,
+        position: Duration,
+        source: (usize, usize),
+        layout: &PlacementLayout,
+        dropped: u64,
+    ) -> HudStatus {
+        self.fps.tick();
+
+        HudStatus {
+            position,
+            duration: self.duration,
+            source,
+            grid: (layout.canvas_width, layout.canvas_height),
+            mode: self.mode.clone(),
+            fps: self.fps.fps(),
+            dropped,
+        }
+    }
+}
+
+/// A line of plain text, to layer the status line with the frame.
+#[derive(Debug)]
+pub struct StatusLine(pub String);
+
+impl Glyph for StatusLine {
+    fn render_into(
+        &self,
+        _engine: &dyn GlyphRenderingEngine,
+        out: &mut dyn fmt::Write,
+    ) -> fmt::Result {
+        out.write_str(&self.0)
+    }
+
+    fn add_child(&mut self, _child: Box<dyn Glyph>) {}
+
+    fn get_child_mut(&mut self, _index: usize) -> Option<&mut dyn Glyph> {
+        None
+    }
+}
+
+/// Frames drawn per second over the last [`FPS_WINDOW`].
+#[derive(Debug, Default)]
+pub struct FpsMeter {
+    frames: VecDeque<Instant>,
+}
+
+impl FpsMeter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn tick(&mut self) {
+        self.tick_at(Instant::now());
+    }
+
+    fn tick_at(&mut self, now: Instant) {
+        while self
+            .frames
+            .front()
+            .is_some_and(|frame: &Instant| now.duration_since(*frame) > FPS_WINDOW)
+        {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(now);
+    }
+
+    /// `None` until two frames have been drawn.
+    pub fn fps(&self) -> Option<f64> {
+        let (first, last) = (self.frames.front()?, self.frames.back()?);
+        let elapsed: f64 = last.duration_since(*first).as_secs_f64();
+
+        (elapsed > 0.0).then(|| (self.frames.len() - 1) as f64 / elapsed)
+    }
+}
+
+/// Color or mono, the charset and any edge mode, as the options that set them are spelled.
+fn render_mode(args: &Args) -> String {
+    let name = |value: Option<clap::builder::PossibleValue>| {
+        value.map_or_else(String::new, |value| value.get_name().to_string())
+    };
+    let mut mode: String = format!(
+        "{} {}",
+        if args.color { "color" } else { "mono" },
+        name(args.charset.to_possible_value())
+    );
+
+    if let Some(edges) = args.edges {
+        mode.push(' ');
+        mode.push_str(&name(edges.to_possible_value()));
+    }
+
+    mode
+}
+
+/// `m:ss`, or `h:mm:ss` from an hour on.
+fn timecode(time: Duration) -> String {
+    let seconds: u64 = time.as_secs();
+
+    match seconds / 3600 {
+        0 => format!("{}:{:02}", seconds / 60, seconds % 60),
+        hours => format!("{hours}:{:02}:{:02}", seconds / 60 % 60, seconds % 60),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, Instant};
+
+    use clap::Parser;
+    use pretty_assertions::assert_eq;
+
+    use crate::args::Args;
+    use crate::hud::{FpsMeter, HudStatus, render_mode, timecode};
+
+    fn status() -> HudStatus {
+        HudStatus {
+            position: Duration::from_secs(30),
+            duration: Some(Duration::from_secs(120)),
+            source: (1920, 1080),
+            grid: (80, 23),
+            mode: "mono standard".to_string(),
+            fps: Some(24.96),
+            dropped: 3,
+        }
+    }
+
+    #[test]
+    fn fills_the_width_with_a_progress_bar() {
+        assert_eq!(
+            status().line(80),
+            "0:30 / 2:00 [████░░░░░░░░░░░░░] 1920x1080→80x23 mono standard 25.0 fps 3 dropped"
+        );
+        assert_eq!(status().line(90).chars().count(), 90);
+    }
+
+    #[test]
+    fn drops_the_bar_when_there_is_no_room_or_duration() {
+        let unknown: HudStatus = HudStatus {
+            duration: None,
+            fps: None,
+            ..status()
+        };
+
+        assert_eq!(unknown.line(40), "0:30 1920x1080→80x23 mono standard -- fp");
+        assert_eq!(
+            status().line(64),
+            "0:30 / 2:00 1920x1080→80x23 mono standard 25.0 fps 3 dropped    "
+        );
+    }
+
+    #[test]
+    fn formats_timecodes() {
+        assert_eq!(timecode(Duration::from_millis(59_999)), "0:59");
+        assert_eq!(timecode(Duration::from_secs(3723)), "1:02:03");
+    }
+
+    #[test]
+    fn measures_frames_over_the_last_second() {
+        let start: Instant = Instant::now();
+        let mut meter: FpsMeter = FpsMeter::new();
+
+        meter.tick_at(start);
+        assert_eq!(meter.fps(), None);
+
+        for i in 1..=20 {
+            meter.tick_at(start + Duration::from_millis(100 * i));
+        }
+
+        assert_eq!(meter.fps(), Some(10.0));
+    }
+
+    #[test]
+    fn names_the_render_mode_like_the_options() {
+        let args: Args = Args::parse_from([
+            "cli",
+            "in.mp4",
+            "--color",
+            "--charset",
+            "blocks",
+            "--edges",
+            "hybrid",
+        ]);
+
+        assert_eq!(render_mode(&args), "color blocks hybrid");
+    }
+}
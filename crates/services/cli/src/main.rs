@@ -1,78 +1,124 @@
-use lib_ascii::domain::entities::GlyphEngineAsciiImpl;
-use lib_core::domain::entities::Glyph;
-use lib_image::domain::entities::{LumaImage, LumaPixel};
-use std::{
-    env,
-    io::{self, Write, stdout},
-};
+mod args;
+mod controls;
+mod ffmpeg_frames;
+mod frame_clock;
+mod frame_feed;
+mod http;
+mod hud;
+mod recorder;
+mod render;
+mod serve;
+mod sources;
+mod subtitles;
+mod telnet;
+mod viewer;
+mod websocket;
+
+use args::Args;
+use clap::Parser;
+use controls::PlaybackCommand;
+use frame_clock::FrameClock;
+use hud::{Hud, HudStatus};
+use lib_image::domain::codecs::{DecodedImage, FrameSource, Playback, TimedFrame};
+use lib_image::domain::entities::{AutoExposure, Placement, PlacementLayout};
+use recorder::Recorder;
+use render::{FrameOverlays, TextStyle};
+use std::io::{self, Write, stdout};
+use std::time::Duration;
+use subtitles::{StreamTime, Subtitles};
 
 fn receive_and_process_decoded_frames(
-    decoder: &mut ffmpeg_next::decoder::Video,
     input_context: &mut ffmpeg_next::format::context::Input,
     video_stream_index: &usize,
     stdout_handle: &mut io::Stdout,
-    duration_per_frame: &std::time::Duration,
+    args: &Args,
+    auto_exposure: &mut AutoExposure,
+    recorder: &mut Option<Recorder>,
+    subtitles: &mut Option<Subtitles>,
 ) -> Result<(), ffmpeg_next::Error> {
     let mut frame = ffmpeg_next::frame::Video::empty();
     let mut scaler = None;
-
-    // Get initial terminal size
-    let (mut term_width, mut term_height): (u32, u32) = match crossterm::terminal::size() {
-        Ok((w, h)) => (w.max(1) as u32, h.max(1) as u32), // ensure at least 1x1
-        Err(_) => (80, 24),                               // fallback
+    let mut layout: Option<PlacementLayout> = None;
+    let placement: Placement = args.placement();
+    let mut clock: FrameClock = FrameClock::new();
+    let (mut decoder, mut delays, stream_time) = match input_context.stream(*video_stream_index) {
+        Some(stream) => (
+            ffmpeg_next::codec::context::Context::from_parameters(stream.parameters())?
+                .decoder()
+                .video()?,
+            ffmpeg_frames::timestamp_delays(&stream),
+            StreamTime::new(&stream),
+        ),
+        None => return Ok(()),
     };
+    let subtitle_stream: Option<usize> = subtitles.as_ref().and_then(Subtitles::stream_index);
+    let mut time: Duration = Duration::ZERO;
+    // Input durations are in microseconds (`AV_TIME_BASE`), and unknown ones are not positive.
+    let duration: Option<Duration> = u64::try_from(input_context.duration())
+        .ok()
+        .filter(|duration: &u64| *duration > 0)
+        .map(Duration::from_micros);
+    let mut hud: Option<Hud> = args.hud.then(|| Hud::new(args, duration));
+
     for (stream, packet) in input_context.packets() {
+        if Some(stream.index()) == subtitle_stream
+            && let Some(subtitles) = subtitles
+        {
+            // A cue that fails to decode is dropped rather than ending playback.
+            let _ = subtitles.decode(&packet);
+            continue;
+        }
         if stream.index() != *video_stream_index {
             continue;
         }
 
         decoder.send_packet(&packet)?;
         while decoder.receive_frame(&mut frame).is_ok() {
+            match controls::poll_command() {
+                Ok(Some(PlaybackCommand::Quit)) => return Ok(()),
+                Ok(Some(PlaybackCommand::ToggleAutoExposure)) => auto_exposure.toggle(),
+                _ => {}
+            }
+
             let width = frame.width();
             let height = frame.height();
             // Re-check terminal size on every frame (to handle resize)
-            let (new_term_width, new_term_height) = match crossterm::terminal::size() {
-                Ok((w, h)) => (w.max(1) as u32, h.max(1) as u32),
-                Err(_) => (term_width, term_height),
-            };
+            let (term_width, term_height) = terminal_size();
+            let new_layout: PlacementLayout = placement.layout(
+                width as usize,
+                height as usize,
+                term_width,
+                frame_rows(term_height, args),
+            );
 
-            // Update terminal size if changed
-            if new_term_width != term_width || new_term_height != term_height {
-                term_width = new_term_width;
-                term_height = new_term_height;
-                // Invalidate scaler so it recreates with new size
+            // Invalidate scaler so it recreates with the new layout
+            if layout != Some(new_layout) {
+                layout = Some(new_layout);
                 scaler = None;
             }
 
-            // Compute target size while preserving aspect ratio
-            let aspect_ratio = width as f64 / height as f64;
-            let terminal_ratio = term_width as f64 / term_height as f64;
-
-            let (target_width, target_height) = if aspect_ratio > terminal_ratio {
-                // Video is wider than terminal → constrain by width
-                (term_width, (term_width as f64 / aspect_ratio) as u32)
+            // Swscale can only resize when the whole frame is shown; otherwise it just converts
+            // to GRAY8, RGB24 or RGBA and lib-image crops and resizes.
+            let (scaled_width, scaled_height) = if swscale_resizes(args, &new_layout, width, height)
+            {
+                (
+                    new_layout.target.width as u32,
+                    new_layout.target.height as u32,
+                )
             } else {
-                // Video is taller than terminal → constrain by height
-                ((term_height as f64 * aspect_ratio) as u32, term_height)
+                (width, height)
             };
 
-            // Adjust for character aspect ratio (ASCII chars are taller than wide)
-            // Most terminals have ~2:1 char height:width ratio (e.g., 16x8 pixels per char)
-            // let target_height = target_height / 2; // Compensate for character shape
-
-            // Clamp to reasonable bounds
-            // let target_width = target_width.min(200); // Avoid huge output
-            // let target_height = target_height.clamp(1, 100);
             // Initialize scaler on first frame
             if scaler.is_none() {
                 scaler = Some(ffmpeg_next::software::scaling::context::Context::get(
                     frame.format(),
                     width,
                     height,
-                    ffmpeg_next::format::Pixel::GRAY8,
-                    target_width,
-                    target_height,
-                    ffmpeg_next::software::scaling::flag::Flags::BILINEAR,
+                    ffmpeg_frames::output_format(args.color, frame.format()),
+                    scaled_width.max(1),
+                    scaled_height.max(1),
+                    args.resampler.swscale_flags(),
                 )?);
             }
 
@@ -82,150 +128,332 @@ fn receive_and_process_decoded_frames(
             let mut scaled_frame = ffmpeg_next::frame::Video::empty();
             scaler.run(&frame, &mut scaled_frame)?;
 
-            // Convert to ASCII and print
-            render_ascii_frame(&scaled_frame, stdout_handle).unwrap();
+            // Keep the previous frame up until this one is due
+            clock.wait(delays.next(frame.timestamp()));
+            time = frame.timestamp().map_or(time, |ts: i64| stream_time.at(ts));
 
-            // Wait to maintain frame rate
-            std::thread::sleep(*duration_per_frame);
+            // Convert to ASCII and print
+            let text: String = render_ascii_frame(
+                &scaled_frame,
+                &new_layout,
+                stdout_handle,
+                args,
+                auto_exposure,
+                &overlays(
+                    subtitles.as_ref(),
+                    time,
+                    args,
+                    hud.as_mut().map(|hud: &mut Hud| {
+                        hud.frame(
+                            time,
+                            (width as usize, height as usize),
+                            &new_layout,
+                            clock.missed(),
+                        )
+                    }),
+                ),
+            )
+            .unwrap();
+            if let Some(recorder) = recorder {
+                recorder.record(&text).unwrap();
+            }
         }
     }
 
     // Flush decoder
     decoder.send_eof()?;
     while decoder.receive_frame(&mut frame).is_ok() {
-        if let Some(scaler) = &mut scaler {
+        if let (Some(scaler), Some(layout)) = (&mut scaler, &layout) {
             let mut scaled_frame = ffmpeg_next::frame::Video::empty();
             scaler.run(&frame, &mut scaled_frame)?;
-            render_ascii_frame(&scaled_frame, stdout_handle).unwrap();
-            std::thread::sleep(*duration_per_frame);
+            clock.wait(delays.next(frame.timestamp()));
+            time = frame.timestamp().map_or(time, |ts: i64| stream_time.at(ts));
+            let text: String = render_ascii_frame(
+                &scaled_frame,
+                layout,
+                stdout_handle,
+                args,
+                auto_exposure,
+                &overlays(
+                    subtitles.as_ref(),
+                    time,
+                    args,
+                    hud.as_mut().map(|hud: &mut Hud| {
+                        hud.frame(
+                            time,
+                            (frame.width() as usize, frame.height() as usize),
+                            layout,
+                            clock.missed(),
+                        )
+                    }),
+                ),
+            )
+            .unwrap();
+            if let Some(recorder) = recorder {
+                recorder.record(&text).unwrap();
+            }
         }
     }
 
     Ok(())
 }
 
+fn terminal_size() -> (usize, usize) {
+    match crossterm::terminal::size() {
+        Ok((w, h)) => (w.max(1) as usize, h.max(1) as usize), // ensure at least 1x1
+        Err(_) => (80, 24),                                   // fallback
+    }
+}
+
+/// Terminal rows left for the frame after those kept for subtitles and the HUD.
+fn frame_rows(term_height: usize, args: &Args) -> usize {
+    term_height.saturating_sub(args.reserved_rows()).max(1)
+}
+
+/// What is drawn with the frame shown `time` into playback.
+fn overlays<'a>(
+    subtitles: Option<&'a Subtitles>,
+    time: Duration,
+    args: &Args,
+    hud: Option<HudStatus>,
+) -> FrameOverlays<'a> {
+    FrameOverlays {
+        subtitles: subtitles
+            .map_or_else(Vec::new, |subtitles: &Subtitles| subtitles.active_at(time)),
+        subtitle_rows: args.reserved_subtitle_rows(),
+        hud,
+    }
+}
+
+fn swscale_resizes(args: &Args, layout: &PlacementLayout, width: u32, height: u32) -> bool {
+    args.resampler.filter().is_none() && layout.uses_full_source(width as usize, height as usize)
+}
+
 fn render_ascii_frame(
     frame: &ffmpeg_next::frame::Video,
+    layout: &PlacementLayout,
     stdout_handle: &mut std::io::Stdout,
-) -> Result<(), Box<dyn std::error::Error>> {
+    args: &Args,
+    auto_exposure: &mut AutoExposure,
+    overlays: &FrameOverlays<'_>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let image: DecodedImage = ffmpeg_frames::decoded_image(frame)?;
+
+    render_image(image, layout, stdout_handle, args, auto_exposure, overlays)
+}
+
+/// Draws `image` in place of the previous frame and returns the text written, without the
+/// screen-clearing escapes.
+fn render_image(
+    image: DecodedImage,
+    layout: &PlacementLayout,
+    stdout_handle: &mut std::io::Stdout,
+    args: &Args,
+    auto_exposure: &mut AutoExposure,
+    overlays: &FrameOverlays<'_>,
+) -> Result<String, Box<dyn std::error::Error>> {
     crossterm::execute!(
         stdout_handle,
-        crossterm::terminal::Clear(crossterm::terminal::ClearType::All)
+        crossterm::terminal::Clear(crossterm::terminal::ClearType::All),
+        crossterm::cursor::MoveTo(0, 0)
     )?;
 
-    let data: &[u8] = frame.data(0);
-    let linesize = frame.stride(0);
-    let width = frame.width() as usize;
-    let height = frame.height() as usize;
-    let mut image = LumaImage::new(width, height);
-
-    for y in 0..height {
-        let row_start = y * linesize;
-        for x in 0..width {
-            let offset = row_start + x;
-            let byte: u8 = data[offset];
-            image.add_child(Box::new(LumaPixel::new(byte)));
+    let text: String = render::render_text(
+        image,
+        layout,
+        args,
+        auto_exposure,
+        TextStyle::from(args),
+        overlays,
+    );
+
+    stdout_handle.flush()?;
+    // Raw mode disables output post-processing, so line feeds need an explicit carriage return.
+    write!(stdout_handle, "{}", text.replace('\n', "\r\n"))?;
+
+    Ok(text)
+}
+
+/// Opens the `--subtitles`, if any; embedded ones need the ffmpeg `input` and its `video`
+/// stream.
+fn open_subtitles(
+    args: &Args,
+    input: Option<&ffmpeg_next::format::context::Input>,
+    video: Option<&ffmpeg_next::Stream<'_>>,
+) -> Result<Option<Subtitles>, String> {
+    args.subtitles
+        .as_ref()
+        .map(|source| Subtitles::open(source, input, video))
+        .transpose()
+}
+
+/// Flushes the `--record` file, reporting rather than losing a failed final write.
+fn finish_recording(recorder: Option<Recorder>, args: &Args) {
+    if let (Some(recorder), Some(path)) = (recorder, &args.record)
+        && let Err(error) = recorder.finish()
+    {
+        eprint!("{path}: {error}\r\n");
+    }
+}
+
+/// Plays natively decoded or raw frames sized to the terminal, honoring each frame's delay and
+/// the loop count. A still image is a single frame.
+fn play_frames(
+    source: Box<dyn FrameSource>,
+    stdout_handle: &mut io::Stdout,
+    args: &Args,
+    auto_exposure: &mut AutoExposure,
+    recorder: &mut Option<Recorder>,
+    subtitles: Option<&Subtitles>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let placement: Placement = args.placement();
+    let mut clock: FrameClock = FrameClock::new();
+    // Frames carry no timestamps, so subtitles follow the delays played so far.
+    let mut time: Duration = Duration::ZERO;
+    let mut hud: Option<Hud> = args.hud.then(|| Hud::new(args, None));
+
+    for frame in Playback::new(source) {
+        match controls::poll_command() {
+            Ok(Some(PlaybackCommand::Quit)) => break,
+            Ok(Some(PlaybackCommand::ToggleAutoExposure)) => auto_exposure.toggle(),
+            _ => {}
+        }
+
+        let frame: TimedFrame = frame?;
+        let (term_width, term_height) = terminal_size();
+        let layout: PlacementLayout = placement.layout(
+            frame.image.width(),
+            frame.image.height(),
+            term_width,
+            frame_rows(term_height, args),
+        );
+        let status: Option<HudStatus> = hud.as_mut().map(|hud: &mut Hud| {
+            hud.frame(
+                time,
+                (frame.image.width(), frame.image.height()),
+                &layout,
+                clock.missed(),
+            )
+        });
+        let text: String = render_image(
+            frame.image,
+            &layout,
+            stdout_handle,
+            args,
+            auto_exposure,
+            &overlays(subtitles, time, args, status),
+        )?;
+        if let Some(recorder) = recorder {
+            recorder.record(&text)?;
         }
+        clock.wait(frame.delay);
+        time += frame.delay;
     }
-    stdout_handle.flush()?;
-    write!(
-        stdout_handle,
-        "{}",
-        image.as_text(&GlyphEngineAsciiImpl::new())
-    )?;
+
+    write!(stdout_handle, "\r\n")?;
 
     Ok(())
 }
+
 fn main() {
-    let args: Vec<String> = env::args().skip(1).collect();
+    let args: Args = Args::parse();
 
     println!("GlyphForge CLI - ASCII Media Renderer");
     println!("--------------------------------------");
-    println!("Arguments: {:?}", &args[1..]);
+    println!("Arguments: {:?}", args);
 
-    if args.len() != 1 {
-        eprintln!("Usage: <path>");
-        std::process::exit(1);
+    if args.serving() {
+        if let Err(error) = serve::run(args) {
+            eprintln!("{error}");
+            std::process::exit(1);
+        }
+        return;
     }
 
-    let path: &str = args[0].as_str();
-    ffmpeg_next::init().unwrap();
-
-    let mut input: ffmpeg_next::format::context::Input = ffmpeg_next::format::input(path).unwrap();
-    let stream: ffmpeg_next::Stream<'_> = input
-        .streams()
-        .best(ffmpeg_next::media::Type::Video)
-        .unwrap();
+    let path: &str = args.path.as_str();
 
-    let video_stream_index = stream.index();
-    let context_decoder =
-        ffmpeg_next::codec::context::Context::from_parameters(stream.parameters()).unwrap();
-    let mut decoder = context_decoder.decoder().video().unwrap();
+    let native_frames: Option<Box<dyn FrameSource>> =
+        sources::native_frames(path, &args).map(|frames| {
+            frames.unwrap_or_else(|error| {
+                eprintln!("{path}: {error}");
+                std::process::exit(1);
+            })
+        });
+    let mut recorder: Option<Recorder> = args.record.as_deref().map(|record_path| {
+        let (columns, rows) = terminal_size();
+        Recorder::create(record_path, columns, rows, args.record_fps).unwrap_or_else(|error| {
+            eprintln!("{record_path}: {error}");
+            std::process::exit(1);
+        })
+    });
 
     // Terminal setup
     let mut stdout_handle = stdout();
     crossterm::execute!(stdout_handle, crossterm::cursor::Hide).unwrap();
     let _guard = scopeguard::guard((), |_| {
+        let _ = crossterm::terminal::disable_raw_mode();
         let _ = crossterm::execute!(stdout(), crossterm::cursor::Show);
     });
 
-    let frame_rate: ffmpeg_next::Rational = stream.avg_frame_rate();
+    println!("Playing... Press q to quit, e to toggle auto-exposure.");
 
-    let duration_per_frame: std::time::Duration =
-        if frame_rate.denominator() != 0 && frame_rate.numerator() != 0 {
-            std::time::Duration::from_secs_f64(
-                frame_rate.denominator() as f64 / frame_rate.numerator() as f64,
-            )
-        } else {
-            std::time::Duration::from_millis(40) // fallback ~25fps
+    let mut auto_exposure: AutoExposure = args.auto_exposure();
+
+    // Raw mode lets playback keys arrive without waiting for Enter. Without a terminal (output
+    // piped to a file) there are no keys to read, so rendering goes ahead regardless.
+    let _ = crossterm::terminal::enable_raw_mode();
+
+    if let Some(frames) = native_frames {
+        let subtitles: Option<Subtitles> = match open_subtitles(&args, None, None) {
+            Ok(subtitles) => subtitles,
+            Err(error) => {
+                eprint!("--subtitles: {error}\r\n");
+                return;
+            }
         };
+        if let Err(error) = play_frames(
+            frames,
+            &mut stdout_handle,
+            &args,
+            &mut auto_exposure,
+            &mut recorder,
+            subtitles.as_ref(),
+        ) {
+            eprint!("{path}: {error}\r\n");
+        }
+        finish_recording(recorder, &args);
+        return;
+    }
+
+    ffmpeg_next::init().unwrap();
 
-    println!("Playing... Press Ctrl+C to quit.");
+    let mut input: ffmpeg_next::format::context::Input = ffmpeg_next::format::input(path).unwrap();
+    let stream: ffmpeg_next::Stream<'_> = input
+        .streams()
+        .best(ffmpeg_next::media::Type::Video)
+        .unwrap();
+
+    let video_stream_index = stream.index();
+    let mut subtitles: Option<Subtitles> = match open_subtitles(&args, Some(&input), Some(&stream))
+    {
+        Ok(subtitles) => subtitles,
+        Err(error) => {
+            eprint!("--subtitles: {error}\r\n");
+            return;
+        }
+    };
 
     // Decode and render frames
 
     receive_and_process_decoded_frames(
-        &mut decoder,
         &mut input,
         &video_stream_index,
         &mut stdout_handle,
-        &duration_per_frame,
+        &args,
+        &mut auto_exposure,
+        &mut recorder,
+        &mut subtitles,
     )
     .unwrap();
-    // let size_parts: Vec<&str> = args[0].split('x').collect();
-    //
-    // println!("Received size argument: {}", args[0]);
-    // let target_width: usize = size_parts[0].parse::<usize>().unwrap();
-    // let target_height: usize = size_parts[1].parse::<usize>().unwrap();
-    //
-    // println!(
-    //     "Target dimensions: width={} height={}",
-    //     target_width, target_height
-    // );
-    //
-    // // Get a handle to the standard input stream.
-    // let stdin_handle: io::StdinLock<'static> = io::stdin().lock();
-    // let mut bytes: io::Bytes<io::StdinLock<'static>> = stdin_handle.bytes();
-    // let mut is_done: bool = false;
-    // let mut byte_num: u16 = 1;
-    // let mut image: LumaImage = LumaImage::new(target_width, target_height);
-    // let engine: GlyphEngineAsciiImpl = GlyphEngineAsciiImpl::new();
-    //
-    // while !is_done {
-    //     println!("Reading {:?} bytes from stdin...", bytes.size_hint());
-    //     match bytes.take_while() {
-    //         Some(byte) => {
-    //             // println!("Reading byte #{}: {:?}", byte_num, byte);
-    //             image.add_raw_byte(byte.unwrap());
-    //             byte_num = byte_num.wrapping_add(1);
-    //         }
-    //         _ => {
-    //             println!("No more bytes to read from stdin.");
-    //             is_done = true;
-    //         }
-    //     }
-    //
-    //     println!("{}", image.as_text(&engine));
-    //     image = LumaImage::new(target_width, target_height);
-    // }
+    finish_recording(recorder, &args);
 }
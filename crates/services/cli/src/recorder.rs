@@ -0,0 +1,44 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter},
+};
+
+use lib_image::domain::{
+    codecs::{Y4mHeader, Y4mWriter},
+    entities::{LumaImage, TEXT_CELL_HEIGHT, TEXT_CELL_WIDTH},
+};
+
+/// Writes each rendered frame, rasterized as white glyphs on black, to a Y4M file.
+pub struct Recorder {
+    writer: Y4mWriter<BufWriter<File>>,
+    columns: usize,
+    rows: usize,
+}
+
+impl Recorder {
+    /// Records a fixed grid of `columns` by `rows` cells; text outside it is cut off.
+    pub fn create(path: &str, columns: usize, rows: usize, fps: f64) -> io::Result<Self> {
+        // Y4M stores the rate as a ratio; millihertz keep rates like 29.97 exact.
+        let frame_rate: (u32, u32) = ((fps.max(0.001) * 1000.0).round() as u32, 1000);
+        let header: Y4mHeader = Y4mHeader::new(
+            columns * TEXT_CELL_WIDTH,
+            rows * TEXT_CELL_HEIGHT,
+            frame_rate,
+        );
+
+        Ok(Self {
+            writer: Y4mWriter::new(BufWriter::new(File::create(path)?), header)?,
+            columns,
+            rows,
+        })
+    }
+
+    pub fn record(&mut self, text: &str) -> io::Result<()> {
+        self.writer
+            .write_frame(&LumaImage::from_text(text, self.columns, self.rows))
+    }
+
+    pub fn finish(mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
@@ -0,0 +1,210 @@
+use lib_ascii::domain::entities::{
+    FigletBanner, GlyphEngineAnsiColorImpl, GlyphEngineAsciiImpl, SubtitleCue, SubtitleGlyph,
+};
+use lib_core::domain::entities::{Glyph, GlyphCompositor, GlyphLayer, GlyphRenderingEngine};
+use lib_image::domain::codecs::DecodedImage;
+use lib_image::domain::entities::{
+    AlphaImage, AlphaSettings, AutoExposure, EdgeMap, LineArtImage, LumaImage, PlacedImage,
+    PlacementFill, PlacementLayout, ResampleFilter, RgbImage, RgbaImage,
+};
+
+use crate::args::{Args, CharsetArg};
+use crate::hud::{HudStatus, StatusLine};
+
+/// How glyph text is written, which network clients may choose for themselves.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextStyle {
+    /// Tint glyphs with ANSI escapes; without it color sources are reduced to luma first.
+    pub color: bool,
+    pub charset: CharsetArg,
+}
+
+impl From<&Args> for TextStyle {
+    fn from(args: &Args) -> Self {
+        Self {
+            color: args.color,
+            charset: args.charset,
+        }
+    }
+}
+
+/// Text drawn with a frame that changes as playback goes on, unlike the `--banner`.
+#[derive(Debug, Default)]
+pub struct FrameOverlays<'a> {
+    /// Subtitle cues shown with this frame.
+    pub subtitles: Vec<&'a SubtitleCue>,
+    /// Rows below the frame kept for subtitles; without any they are drawn over the frame.
+    pub subtitle_rows: usize,
+    /// Status drawn on a row of its own below the frame and any subtitle rows.
+    pub hud: Option<HudStatus>,
+}
+
+/// Renders `image` placed by `layout` as glyph text, one line per terminal row, followed by any
+/// rows `overlays` keep below it. Alpha is always kept.
+pub fn render_text(
+    image: DecodedImage,
+    layout: &PlacementLayout,
+    args: &Args,
+    auto_exposure: &mut AutoExposure,
+    style: TextStyle,
+    overlays: &FrameOverlays<'_>,
+) -> String {
+    let image: DecodedImage = match image {
+        DecodedImage::Rgb(image) if !style.color => DecodedImage::Luma(LumaImage::from(&image)),
+        image => image,
+    };
+    let width: usize = image.width();
+    let height: usize = image.height();
+    let resample = (width, height) != (layout.target.width, layout.target.height)
+        || !layout.uses_full_source(width, height);
+    let filter: ResampleFilter = args.resampler.filter().unwrap_or(ResampleFilter::Bilinear);
+
+    let content: Box<dyn Glyph> = match image {
+        DecodedImage::Rgba(mut image) => {
+            if resample {
+                image = image
+                    .crop(layout.source)
+                    .resize(layout.target.width, layout.target.height, filter)
+                    .expect("layout targets are no larger than the screen");
+            }
+
+            let image: RgbaImage = image.adjust_all(&args.adjustments);
+            let settings: AlphaSettings = args.alpha_settings();
+
+            match args.edges {
+                Some(_) => with_edges(
+                    LumaImage::from(&image.composite_over(settings.background)),
+                    args,
+                ),
+                None => Box::new(AlphaImage::new(image, settings)),
+            }
+        }
+        DecodedImage::Rgb(mut image) => {
+            if resample {
+                image = image
+                    .crop(layout.source)
+                    .resize(layout.target.width, layout.target.height, filter)
+                    .expect("layout targets are no larger than the screen");
+            }
+
+            let image: RgbImage = auto_exposure
+                .apply_color(image)
+                .adjust_all(&args.adjustments);
+
+            match args.edges {
+                Some(_) => with_edges(LumaImage::from(&image), args),
+                None => Box::new(image),
+            }
+        }
+        DecodedImage::Luma(mut image) => {
+            if resample {
+                image = image
+                    .crop(layout.source)
+                    .resize(layout.target.width, layout.target.height, filter)
+                    .expect("layout targets are no larger than the screen");
+            }
+
+            with_edges(
+                auto_exposure.apply(image).adjust_all(&args.adjustments),
+                args,
+            )
+        }
+    };
+    let glyphs: GlyphEngineAsciiImpl =
+        GlyphEngineAsciiImpl::with_charset(style.charset.ramp().chars())
+            .unwrap_or_else(GlyphEngineAsciiImpl::new);
+    let engine: Box<dyn GlyphRenderingEngine> = if style.color {
+        Box::new(GlyphEngineAnsiColorImpl::new(glyphs))
+    } else {
+        Box::new(glyphs)
+    };
+
+    let placed: Box<dyn Glyph> = Box::new(PlacedImage::new(
+        content,
+        layout,
+        PlacementFill::Glyph(args.fill_glyph),
+    ));
+
+    with_overlays(placed, layout, args, overlays).as_text(engine.as_ref())
+}
+
+/// Layers the `--banner` text over the top of `frame`, centred, the subtitles over the frame or
+/// in their own rows below it, and the status line below everything.
+fn with_overlays(
+    frame: Box<dyn Glyph>,
+    layout: &PlacementLayout,
+    args: &Args,
+    overlays: &FrameOverlays<'_>,
+) -> Box<dyn Glyph> {
+    if args.banner.is_none()
+        && overlays.subtitles.is_empty()
+        && overlays.subtitle_rows == 0
+        && overlays.hud.is_none()
+    {
+        return frame;
+    }
+
+    let width: usize = layout.canvas_width;
+    let hud_row: usize = layout.canvas_height + overlays.subtitle_rows;
+    let mut compositor: GlyphCompositor =
+        GlyphCompositor::new(width, hud_row + usize::from(overlays.hud.is_some()))
+            .with_layer(GlyphLayer::new(frame));
+
+    if let Some(text) = &args.banner {
+        let mut banner: FigletBanner = args.banner_font.render(text);
+        if let Some(filter) = args.banner_filter {
+            banner = banner.with_filter(filter.into());
+        }
+        let column: usize = width.saturating_sub(banner.width()) / 2;
+
+        compositor.add_layer(
+            GlyphLayer::new(Box::new(banner))
+                .with_offset(column as isize, 0)
+                .with_z(1),
+        );
+    }
+
+    let subtitles: SubtitleGlyph = match overlays.subtitle_rows {
+        0 => SubtitleGlyph::new(
+            overlays.subtitles.iter().copied(),
+            width,
+            layout.canvas_height,
+        )
+        .with_band(!args.no_subtitle_band),
+        rows => SubtitleGlyph::new(overlays.subtitles.iter().copied(), width, rows),
+    };
+    if !subtitles.is_empty() {
+        let row: usize = match overlays.subtitle_rows {
+            0 => 0,
+            _ => layout.canvas_height,
+        };
+
+        compositor.add_layer(
+            GlyphLayer::new(Box::new(subtitles))
+                .with_offset(0, row as isize)
+                .with_z(2),
+        );
+    }
+
+    if let Some(hud) = &overlays.hud {
+        compositor.add_layer(
+            GlyphLayer::new(Box::new(StatusLine(hud.line(width))))
+                .with_offset(0, hud_row as isize)
+                .with_z(3),
+        );
+    }
+
+    Box::new(compositor)
+}
+
+/// Wraps `image` in line art when `--edges` is set.
+fn with_edges(image: LumaImage, args: &Args) -> Box<dyn Glyph> {
+    match args.edges {
+        Some(mode) => {
+            let edges: EdgeMap = image.detect_edges(&args.edge_detector());
+
+            Box::new(LineArtImage::new(edges, image, mode.into()))
+        }
+        None => Box::new(image),
+    }
+}
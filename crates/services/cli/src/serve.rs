@@ -0,0 +1,117 @@
+use std::{io, net::TcpListener, path::Path, sync::Arc, time::Duration};
+
+use lib_image::domain::codecs::{FrameSource, TimedFrame};
+
+use crate::args::Args;
+use crate::frame_clock::FrameClock;
+use crate::frame_feed::FrameFeed;
+use crate::http;
+use crate::sources;
+use crate::telnet::{self, Protocol};
+
+/// How long a still image stays up before the playlist moves on.
+pub const STILL_DURATION: Duration = Duration::from_secs(5);
+
+/// Plays `args.path`, or every entry of a `--playlist`, over and over to everyone connected to
+/// the `--telnet`, `--tcp` and `--http` listeners. Returns once no entry can be played any more.
+pub fn run(args: Args) -> io::Result<()> {
+    let args: Arc<Args> = Arc::new(args);
+    let entries: Arc<Vec<String>> = Arc::new(match args.playlist {
+        true => playlist_entries(
+            &std::fs::read_to_string(&args.path)?,
+            Path::new(&args.path).parent().unwrap_or(Path::new("")),
+        ),
+        false => vec![args.path.clone()],
+    });
+    let feed: FrameFeed = FrameFeed::new();
+
+    for (address, protocol) in [(&args.telnet, Protocol::Telnet), (&args.tcp, Protocol::Raw)] {
+        if let Some(address) = address {
+            let listener: TcpListener = TcpListener::bind(address)?;
+            println!("Serving {protocol} on {}", listener.local_addr()?);
+            telnet::spawn(listener, protocol, feed.clone(), args.clone());
+        }
+    }
+    if let Some(address) = &args.http {
+        let listener: TcpListener = TcpListener::bind(address)?;
+        println!("Serving HTTP on http://{}/video", listener.local_addr()?);
+        http::spawn(listener, feed.clone(), entries.clone(), args.clone());
+    }
+
+    play(&entries, &args, &feed);
+    feed.close();
+
+    Ok(())
+}
+
+/// Publishes each entry's frames at their own pace, looping over the entries until a whole
+/// pass shows nothing. Entries that fail to open or decode are reported and skipped.
+fn play(entries: &[String], args: &Args, feed: &FrameFeed) {
+    let mut clock: FrameClock = FrameClock::new();
+
+    loop {
+        let mut published: bool = false;
+
+        for entry in entries {
+            let mut source: Box<dyn FrameSource> = match sources::open_frames(entry, args) {
+                Ok(source) => source,
+                Err(error) => {
+                    eprintln!("{entry}: {error}");
+                    continue;
+                }
+            };
+            let mut frames: usize = 0;
+
+            // One pass per entry: a GIF that loops forever would otherwise never hand over.
+            while let Some(frame) = source.next_frame() {
+                match frame {
+                    Ok(TimedFrame { image, delay }) => {
+                        feed.publish(image);
+                        frames += 1;
+                        clock.wait(delay);
+                    }
+                    Err(error) => {
+                        eprintln!("{entry}: {error}");
+                        break;
+                    }
+                }
+            }
+
+            if frames == 1 {
+                clock.wait(STILL_DURATION);
+            }
+            published |= frames > 0;
+        }
+
+        if !published {
+            return;
+        }
+    }
+}
+
+/// Media paths listed in a playlist, resolved against the playlist's directory `base`.
+fn playlist_entries(text: &str, base: &Path) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| base.join(line).to_string_lossy().into_owned())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use pretty_assertions::assert_eq;
+
+    use super::playlist_entries;
+
+    #[test]
+    fn playlist_skips_comments_and_resolves_relative_paths() {
+        let text: &str = "# intro\nclips/a.mp4\n\n  /media/b.gif  \n";
+
+        let entries: Vec<String> = playlist_entries(text, Path::new("/srv/show"));
+
+        assert_eq!(entries, vec!["/srv/show/clips/a.mp4", "/media/b.gif"]);
+    }
+}
@@ -0,0 +1,71 @@
+use std::io::{self, BufRead, BufReader, Read};
+use std::time::Duration;
+
+use lib_image::domain::codecs::{
+    DecodeError, FrameSource, ImageFormat, RawFrames, RawPixelFormat, Y4mReader, decode_frames_file,
+};
+
+use crate::args::Args;
+use crate::ffmpeg_frames::FfmpegFrames;
+
+/// Opens `path` as frames: natively where lib-image can, otherwise through ffmpeg.
+pub fn open_frames(
+    path: &str,
+    args: &Args,
+) -> Result<Box<dyn FrameSource>, Box<dyn std::error::Error>> {
+    native_frames(path, args).unwrap_or_else(|| Ok(Box::new(FfmpegFrames::open(path, args.color)?)))
+}
+
+/// Opens sources lib-image plays without ffmpeg: `--raw` streams, Y4M from a file or stdin
+/// (`-`), and images it decodes natively, animated or not. `None` leaves the path to ffmpeg.
+pub fn native_frames(
+    path: &str,
+    args: &Args,
+) -> Option<Result<Box<dyn FrameSource>, Box<dyn std::error::Error>>> {
+    // Streams are opened before any sniffing, which would eat the start of a pipe.
+    if let Some(format) = args.raw {
+        return Some(raw_frames(path, args, format.into()).map_err(Into::into));
+    }
+
+    let frames: Result<Box<dyn FrameSource>, DecodeError> = match path {
+        "-" => y4m_frames(io::stdin().lock()),
+        path => match native_image_format(path)? {
+            // Y4M is raw video, so it is streamed rather than read into memory whole.
+            ImageFormat::Y4m => std::fs::File::open(path)
+                .map_err(DecodeError::from)
+                .and_then(|file| y4m_frames(BufReader::new(file))),
+            _ => decode_frames_file(path),
+        },
+    };
+
+    Some(frames.map_err(Into::into))
+}
+
+/// Recognises still-image formats lib-image decodes natively from the file's first bytes.
+fn native_image_format(path: &str) -> Option<ImageFormat> {
+    let mut signature: Vec<u8> = Vec::with_capacity(16);
+    std::fs::File::open(path)
+        .and_then(|file| file.take(16).read_to_end(&mut signature))
+        .ok()?;
+
+    ImageFormat::detect(&signature).filter(ImageFormat::is_enabled)
+}
+
+fn y4m_frames(reader: impl BufRead + 'static) -> Result<Box<dyn FrameSource>, DecodeError> {
+    Ok(Box::new(Y4mReader::new(reader)?))
+}
+
+/// Opens the `--raw` stream: stdin for `-`, otherwise a file or FIFO.
+fn raw_frames(path: &str, args: &Args, format: RawPixelFormat) -> io::Result<Box<dyn FrameSource>> {
+    let (width, height) = args.size.unwrap_or_default();
+    let reader: Box<dyn Read> = match path {
+        "-" => Box::new(io::stdin().lock()),
+        path => Box::new(std::fs::File::open(path)?),
+    };
+    let frames: RawFrames<Box<dyn Read>> = RawFrames::new(reader, format, width, height);
+
+    Ok(match args.fps.filter(|fps| *fps > 0.0) {
+        Some(fps) => Box::new(frames.with_delay(Duration::from_secs_f64(1.0 / fps))),
+        None => Box::new(frames),
+    })
+}
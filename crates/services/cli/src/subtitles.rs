@@ -0,0 +1,187 @@
+use std::time::Duration;
+
+use ffmpeg_next::codec::subtitle::{Rect, Subtitle};
+use lib_ascii::domain::entities::{SubtitleCue, SubtitleTrack};
+
+use crate::args::SubtitleSource;
+
+/// How long a decoded cue stays up when its stream gives it no end.
+const UNTIMED_CUE: Duration = Duration::from_secs(5);
+
+/// The `--subtitles` of a playback: a file read up front, or cues decoded from one of the
+/// video's subtitle streams as its packets arrive.
+pub struct Subtitles {
+    track: SubtitleTrack,
+    stream: Option<SubtitleStream>,
+}
+
+struct SubtitleStream {
+    index: usize,
+    decoder: ffmpeg_next::decoder::Subtitle,
+    time: StreamTime,
+}
+
+/// Converts a stream's timestamps to time since the start of the video.
+#[derive(Debug, Clone, Copy)]
+pub struct StreamTime {
+    time_base: ffmpeg_next::Rational,
+    /// Start of the video, in seconds.
+    origin: f64,
+}
+
+impl Subtitles {
+    /// Subtitles from `source`; embedded ones need the `input` the video was opened from, and
+    /// `video` is the stream whose start cue times are measured from.
+    pub fn open(
+        source: &SubtitleSource,
+        input: Option<&ffmpeg_next::format::context::Input>,
+        video: Option<&ffmpeg_next::Stream<'_>>,
+    ) -> Result<Self, String> {
+        let index: Option<usize> = match source {
+            SubtitleSource::File(track) => {
+                return Ok(Self {
+                    track: track.clone(),
+                    stream: None,
+                });
+            }
+            SubtitleSource::Embedded(index) => *index,
+        };
+        let (Some(input), Some(video)) = (input, video) else {
+            return Err("only videos opened with ffmpeg have embedded subtitles".to_string());
+        };
+        let stream: ffmpeg_next::Stream<'_> = match index {
+            Some(index) => input
+                .stream(index)
+                .filter(|stream| stream.parameters().medium() == ffmpeg_next::media::Type::Subtitle)
+                .ok_or_else(|| format!("stream {index} is not a subtitle stream"))?,
+            None => input
+                .streams()
+                .best(ffmpeg_next::media::Type::Subtitle)
+                .ok_or("the video has no subtitle stream")?,
+        };
+        let decoder: ffmpeg_next::decoder::Subtitle =
+            ffmpeg_next::codec::context::Context::from_parameters(stream.parameters())
+                .and_then(|context| context.decoder().subtitle())
+                .map_err(|e| format!("cannot decode subtitle stream {}: {e}", stream.index()))?;
+
+        Ok(Self {
+            track: SubtitleTrack::new(),
+            stream: Some(SubtitleStream {
+                index: stream.index(),
+                decoder,
+                time: StreamTime {
+                    time_base: stream.time_base(),
+                    origin: StreamTime::new(video).origin,
+                },
+            }),
+        })
+    }
+
+    /// Index of the stream whose packets go to [`Subtitles::decode`], if any.
+    pub fn stream_index(&self) -> Option<usize> {
+        self.stream
+            .as_ref()
+            .map(|stream: &SubtitleStream| stream.index)
+    }
+
+    /// Adds the text cues of a subtitle stream packet. Bitmap subtitles have no text to draw
+    /// and are skipped.
+    pub fn decode(&mut self, packet: &ffmpeg_next::Packet) -> Result<(), ffmpeg_next::Error> {
+        let Some(stream) = &mut self.stream else {
+            return Ok(());
+        };
+        let mut subtitle: Subtitle = Subtitle::new();
+        if !stream.decoder.decode(packet, &mut subtitle)? {
+            return Ok(());
+        }
+
+        let shown: f64 = packet
+            .pts()
+            .map_or(0.0, |pts: i64| stream.time.seconds(pts));
+        let start: Duration = seconds(shown) + Duration::from_millis(subtitle.start() as u64);
+        let end: Duration = if subtitle.end() > subtitle.start() {
+            seconds(shown) + Duration::from_millis(subtitle.end() as u64)
+        } else if packet.duration() > 0 {
+            seconds(shown + packet.duration() as f64 * f64::from(stream.time.time_base))
+        } else {
+            start + UNTIMED_CUE
+        };
+
+        for rect in subtitle.rects() {
+            let cue: SubtitleCue = match rect {
+                Rect::Text(text) => SubtitleCue::new(start, end, text.get()),
+                Rect::Ass(ass) => SubtitleCue::from_ass_text(start, end, ass_event_text(ass.get())),
+                _ => continue,
+            };
+            self.track.push(cue);
+        }
+
+        Ok(())
+    }
+
+    /// Cues shown `time` after the start of the video.
+    pub fn active_at(&self, time: Duration) -> Vec<&SubtitleCue> {
+        self.track.active_at(time)
+    }
+}
+
+impl StreamTime {
+    /// Times of `stream`, measured from its start.
+    pub fn new(stream: &ffmpeg_next::Stream<'_>) -> Self {
+        let time_base: ffmpeg_next::Rational = stream.time_base();
+        let origin: f64 = match stream.start_time() {
+            // Containers that do not say where a stream starts leave it unset.
+            i64::MIN => 0.0,
+            start => start as f64 * f64::from(time_base),
+        };
+
+        Self { time_base, origin }
+    }
+
+    /// Time since the start of the video at `timestamp`, which is in the stream's time base.
+    pub fn at(&self, timestamp: i64) -> Duration {
+        seconds(self.seconds(timestamp))
+    }
+
+    fn seconds(&self, timestamp: i64) -> f64 {
+        timestamp as f64 * f64::from(self.time_base) - self.origin
+    }
+}
+
+fn seconds(seconds: f64) -> Duration {
+    Duration::try_from_secs_f64(seconds).unwrap_or_default()
+}
+
+/// The text of a decoded ASS event. ffmpeg gives `ReadOrder,Layer,Style,Name,MarginL,MarginR,
+/// MarginV,Effect,Text`, and versions before 3.0 a full `Dialogue:` line.
+fn ass_event_text(event: &str) -> &str {
+    let fields: usize = if event.starts_with("Dialogue:") {
+        10
+    } else {
+        9
+    };
+
+    event
+        .splitn(fields, ',')
+        .nth(fields - 1)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::subtitles::ass_event_text;
+
+    #[test]
+    fn takes_the_text_of_ass_events() {
+        assert_eq!(
+            ass_event_text("3,0,Default,,0,0,0,,{\\i1}Hi, there"),
+            "{\\i1}Hi, there"
+        );
+        assert_eq!(
+            ass_event_text("Dialogue: 0,0:00:01.00,0:00:02.00,Default,,0,0,0,,Hi"),
+            "Hi"
+        );
+    }
+}
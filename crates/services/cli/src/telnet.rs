@@ -0,0 +1,375 @@
+use std::{
+    fmt,
+    io::{self, Read, Write},
+    net::{TcpListener, TcpStream},
+    sync::{Arc, mpsc},
+    thread,
+    time::Duration,
+};
+
+use lib_image::domain::codecs::DecodedImage;
+use lib_image::domain::entities::{AutoExposure, PlacementLayout};
+
+use crate::args::Args;
+use crate::controls::PlaybackCommand;
+use crate::frame_feed::{FeedEvent, FeedReader, FrameFeed};
+use crate::render::{self, FrameOverlays, TextStyle};
+
+const IAC: u8 = 255;
+const DONT: u8 = 254;
+const DO: u8 = 253;
+const WILL: u8 = 251;
+const SB: u8 = 250;
+const SE: u8 = 240;
+const ECHO: u8 = 1;
+const SUPPRESS_GO_AHEAD: u8 = 3;
+const NAWS: u8 = 31;
+
+/// Longest subnegotiation kept; NAWS needs five bytes, anything much longer is not for us.
+const MAX_SUBNEGOTIATION: usize = 64;
+
+/// How often a client without new frames checks for input such as a resize.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// How long a write may block before the client is given up on as too slow or gone.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How clients of a listener talk.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Protocol {
+    /// Telnet, negotiating window size (NAWS) and character-at-a-time input.
+    Telnet,
+    /// Bare TCP for tools like `nc`: no negotiation, frames at `--client-size`.
+    Raw,
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Protocol::Telnet => write!(f, "telnet"),
+            Protocol::Raw => write!(f, "TCP"),
+        }
+    }
+}
+
+/// Something a client sent, with telnet commands taken out.
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum ClientInput {
+    /// The client's window is now this many columns by rows.
+    Resize(usize, usize),
+    Command(PlaybackCommand),
+}
+
+/// Splits telnet commands out of what a client sends, keeping window sizes and playback keys.
+#[derive(Debug, Default)]
+pub struct TelnetParser {
+    state: ParserState,
+    subnegotiation: Vec<u8>,
+}
+
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+enum ParserState {
+    #[default]
+    Data,
+    Command,
+    /// After WILL, WONT, DO or DONT, waiting for the option byte.
+    Option,
+    Subnegotiation,
+    SubnegotiationCommand,
+}
+
+impl TelnetParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn feed(&mut self, bytes: &[u8]) -> Vec<ClientInput> {
+        bytes
+            .iter()
+            .filter_map(|byte: &u8| self.push(*byte))
+            .collect()
+    }
+
+    fn push(&mut self, byte: u8) -> Option<ClientInput> {
+        match (self.state, byte) {
+            (ParserState::Data, IAC) => self.state = ParserState::Command,
+            (ParserState::Data, byte) => return key_command(byte).map(ClientInput::Command),
+            // A doubled IAC is a literal 255, which is no key we care about.
+            (ParserState::Command, IAC) => self.state = ParserState::Data,
+            (ParserState::Command, SB) => {
+                self.subnegotiation.clear();
+                self.state = ParserState::Subnegotiation;
+            }
+            (ParserState::Command, WILL..=DONT) => self.state = ParserState::Option,
+            (ParserState::Command, _) | (ParserState::Option, _) => self.state = ParserState::Data,
+            (ParserState::Subnegotiation, IAC) => self.state = ParserState::SubnegotiationCommand,
+            (ParserState::Subnegotiation, byte) => self.push_subnegotiation(byte),
+            (ParserState::SubnegotiationCommand, IAC) => {
+                self.push_subnegotiation(IAC);
+                self.state = ParserState::Subnegotiation;
+            }
+            (ParserState::SubnegotiationCommand, SE) => {
+                self.state = ParserState::Data;
+                return self.window_size();
+            }
+            (ParserState::SubnegotiationCommand, _) => self.state = ParserState::Data,
+        }
+
+        None
+    }
+
+    fn push_subnegotiation(&mut self, byte: u8) {
+        if self.subnegotiation.len() < MAX_SUBNEGOTIATION {
+            self.subnegotiation.push(byte);
+        }
+    }
+
+    /// The size in a finished NAWS subnegotiation: option, then width and height as big-endian
+    /// 16-bit values. Zero means the client does not know, so it is ignored.
+    fn window_size(&self) -> Option<ClientInput> {
+        match self.subnegotiation[..] {
+            [NAWS, w0, w1, h0, h1] => {
+                let width: usize = u16::from_be_bytes([w0, w1]) as usize;
+                let height: usize = u16::from_be_bytes([h0, h1]) as usize;
+
+                (width > 0 && height > 0).then_some(ClientInput::Resize(width, height))
+            }
+            _ => None,
+        }
+    }
+}
+
+fn key_command(byte: u8) -> Option<PlaybackCommand> {
+    match byte {
+        // Ctrl-C and Ctrl-D arrive as plain bytes once the client is in character mode.
+        b'q' | 0x03 | 0x04 => Some(PlaybackCommand::Quit),
+        b'e' => Some(PlaybackCommand::ToggleAutoExposure),
+        _ => None,
+    }
+}
+
+/// Accepts clients on `listener` in the background, each following `feed` on its own thread.
+pub fn spawn(listener: TcpListener, protocol: Protocol, feed: FrameFeed, args: Arc<Args>) {
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream: TcpStream = match stream {
+                Ok(stream) => stream,
+                Err(error) => {
+                    eprintln!("{protocol} accept failed: {error}");
+                    continue;
+                }
+            };
+            let frames: FeedReader = feed.reader();
+            let args: Arc<Args> = args.clone();
+
+            thread::spawn(move || {
+                // Errors here are clients hanging up or falling too far behind.
+                let _ = serve_client(stream, protocol, frames, &args);
+            });
+        }
+    });
+}
+
+/// Streams frames to one client until it quits, hangs up, stalls or the playback ends.
+///
+/// Input is read on a second thread so a resize or `q` is seen while waiting for frames. Frames
+/// that arrive while a write is blocked are skipped, never queued.
+fn serve_client(
+    stream: TcpStream,
+    protocol: Protocol,
+    mut frames: FeedReader,
+    args: &Args,
+) -> io::Result<()> {
+    stream.set_nodelay(true)?;
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+
+    let (sender, inputs) = mpsc::channel::<ClientInput>();
+    let mut reader: TcpStream = stream.try_clone()?;
+    thread::spawn(move || {
+        let mut parser: TelnetParser = TelnetParser::new();
+        let mut buffer: [u8; 256] = [0; 256];
+
+        while let Ok(read @ 1..) = reader.read(&mut buffer) {
+            for input in parser.feed(&buffer[..read]) {
+                if sender.send(input).is_err() {
+                    return;
+                }
+            }
+        }
+    });
+
+    let mut writer: TcpStream = stream;
+    if protocol == Protocol::Telnet {
+        // Ask for the window size, and take over echo so keys act without Enter or showing up.
+        writer.write_all(&[IAC, DO, NAWS, IAC, WILL, ECHO, IAC, WILL, SUPPRESS_GO_AHEAD])?;
+    }
+    writer.write_all(b"\x1b[?25l\x1b[2J")?;
+
+    let mut size: (usize, usize) = args.client_size;
+    let mut auto_exposure: AutoExposure = args.auto_exposure();
+
+    let result: io::Result<()> = 'frames: loop {
+        let mut redraw: bool = false;
+
+        loop {
+            match inputs.try_recv() {
+                Ok(ClientInput::Resize(width, height)) => {
+                    size = (width, height);
+                    redraw = true;
+                }
+                Ok(ClientInput::Command(PlaybackCommand::ToggleAutoExposure)) => {
+                    auto_exposure.toggle()
+                }
+                Ok(ClientInput::Command(PlaybackCommand::Quit)) => break 'frames Ok(()),
+                // A client that closed its sending side, like `nc` at the end of its input, may
+                // still be watching; a gone client shows up as a failed write.
+                Err(mpsc::TryRecvError::Empty | mpsc::TryRecvError::Disconnected) => break,
+            }
+        }
+
+        let image: Option<Arc<DecodedImage>> = match frames.next(POLL_INTERVAL) {
+            FeedEvent::Frame(image) => Some(image),
+            FeedEvent::Timeout if redraw => frames.current(),
+            FeedEvent::Timeout => None,
+            FeedEvent::Closed => break 'frames Ok(()),
+        };
+
+        if redraw && let Err(error) = writer.write_all(b"\x1b[2J") {
+            break Err(error);
+        }
+        if let Some(image) = image
+            && let Err(error) = writer.write_all(&screen(&image, size, args, &mut auto_exposure))
+        {
+            break Err(error);
+        }
+    };
+
+    writer.write_all(b"\x1b[?25h\r\n")?;
+
+    result
+}
+
+/// One frame for a client: drawn from the top-left over the previous one, rest of screen
+/// cleared.
+fn screen(
+    image: &DecodedImage,
+    (columns, rows): (usize, usize),
+    args: &Args,
+    auto_exposure: &mut AutoExposure,
+) -> Vec<u8> {
+    let layout: PlacementLayout =
+        args.placement()
+            .layout(image.width(), image.height(), columns, rows);
+    let text: String = render::render_text(
+        image.clone(),
+        &layout,
+        args,
+        auto_exposure,
+        TextStyle::from(args),
+        &FrameOverlays::default(),
+    );
+
+    // A line feed after the bottom row would scroll the frame up by one.
+    format!(
+        "\x1b[H{}\x1b[J",
+        text.trim_end_matches('\n').replace('\n', "\r\n")
+    )
+    .into_bytes()
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::{Read, Write},
+        net::{TcpListener, TcpStream},
+        sync::Arc,
+        time::{Duration, Instant},
+    };
+
+    use clap::Parser;
+    use lib_image::domain::codecs::DecodedImage;
+    use lib_image::domain::entities::LumaImage;
+    use pretty_assertions::assert_eq;
+
+    use crate::args::Args;
+    use crate::controls::PlaybackCommand;
+    use crate::frame_feed::FrameFeed;
+    use crate::telnet::{ClientInput, DO, IAC, NAWS, Protocol, SB, SE, TelnetParser, spawn};
+
+    #[test]
+    fn parser_reports_window_size_and_keys_across_reads() {
+        let mut parser: TelnetParser = TelnetParser::new();
+
+        let first: Vec<ClientInput> = parser.feed(&[IAC, 251, NAWS, b'x', IAC, SB, NAWS, 0]);
+        let second: Vec<ClientInput> = parser.feed(&[120, 0, 40, IAC, SE, b'e', b'q']);
+
+        assert_eq!(first, vec![]);
+        assert_eq!(
+            second,
+            vec![
+                ClientInput::Resize(120, 40),
+                ClientInput::Command(PlaybackCommand::ToggleAutoExposure),
+                ClientInput::Command(PlaybackCommand::Quit),
+            ]
+        );
+    }
+
+    #[test]
+    fn parser_unescapes_iac_inside_subnegotiation() {
+        let mut parser: TelnetParser = TelnetParser::new();
+
+        let inputs: Vec<ClientInput> = parser.feed(&[IAC, SB, NAWS, 1, IAC, IAC, 0, 50, IAC, SE]);
+
+        assert_eq!(inputs, vec![ClientInput::Resize(511, 50)]);
+    }
+
+    #[test]
+    fn telnet_client_gets_frames_at_its_window_size() {
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let feed: FrameFeed = FrameFeed::new();
+        let args: Arc<Args> = Arc::new(Args::parse_from(["glyphforge-cli", "unused"]));
+        spawn(listener, Protocol::Telnet, feed.clone(), args);
+
+        let mut client: TcpStream = TcpStream::connect(address).unwrap();
+        client
+            .set_read_timeout(Some(Duration::from_millis(100)))
+            .unwrap();
+        client
+            .write_all(&[IAC, SB, NAWS, 0, 10, 0, 3, IAC, SE])
+            .unwrap();
+        feed.publish(DecodedImage::Luma(LumaImage::from_luma_data(
+            2,
+            2,
+            vec![255; 4],
+        )));
+
+        // The first frame may still be drawn at the default size before NAWS is read.
+        let deadline: Instant = Instant::now() + Duration::from_secs(5);
+        let mut received: Vec<u8> = Vec::new();
+        let mut buffer: [u8; 4096] = [0; 4096];
+        let frame: Option<String> = loop {
+            if let Ok(read) = client.read(&mut buffer) {
+                received.extend_from_slice(&buffer[..read]);
+            }
+            let text: String = String::from_utf8_lossy(&received).into_owned();
+            let sized: Option<String> = text
+                .split("\x1b[H")
+                .filter_map(|frame: &str| frame.strip_suffix("\x1b[J"))
+                .find(|frame: &&str| frame.split("\r\n").count() == 3)
+                .map(str::to_string);
+
+            if sized.is_some() || Instant::now() > deadline {
+                break sized;
+            }
+        };
+
+        let frame: String = frame.expect("no frame at the negotiated size");
+        assert!(
+            frame
+                .split("\r\n")
+                .all(|row: &str| row.chars().count() == 10)
+        );
+        assert_eq!(&received[..3], &[IAC, DO, NAWS]);
+    }
+}
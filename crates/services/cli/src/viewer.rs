@@ -0,0 +1,551 @@
+use std::{
+    io::{self, Write},
+    net::TcpStream,
+    sync::mpsc::{self, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use clap::ValueEnum;
+use lib_image::domain::codecs::{DecodeError, DecodedImage, FrameSource, TimedFrame};
+use lib_image::domain::entities::{AutoExposure, PlacementLayout};
+
+use crate::args::{self, Args, CharsetArg};
+use crate::render::{self, FrameOverlays, TextStyle};
+use crate::serve::STILL_DURATION;
+use crate::sources;
+use crate::websocket::{self, Message, MessageReader};
+
+/// The browser viewer, served at `/`.
+pub const PAGE: &str = include_str!("../assets/viewer.html");
+
+/// Largest grid a viewer may ask for, in either direction.
+const MAX_CELLS: usize = 1000;
+
+/// Color of cells drawn without one, such as every cell of a grayscale source.
+const DEFAULT_COLOR: [u8; 3] = [255, 255, 255];
+
+/// How frames are sent to a viewer.
+#[derive(Debug, PartialEq, Clone, Copy, ValueEnum)]
+enum FrameMode {
+    /// Binary cell buffers carrying each glyph's color; see [`cell_buffer`].
+    Cells,
+    /// Text messages of plain glyphs: `frame <position in ms>` on the first line, then the rows.
+    Text,
+}
+
+/// What a viewer can ask for over the socket, one command per text message.
+#[derive(Debug, PartialEq)]
+enum ViewerCommand {
+    Play,
+    Pause,
+    /// Jump to this far into the current entry.
+    Seek(Duration),
+    Size(usize, usize),
+    Mode(FrameMode),
+    Charset(CharsetArg),
+}
+
+enum ViewerInput {
+    Command(ViewerCommand),
+    Ping(Vec<u8>),
+    Invalid(String),
+}
+
+/// Completes the WebSocket handshake for the client that sent `key`, then plays the entries
+/// to it on its own, following its commands, until it leaves or nothing can be played.
+pub fn serve(mut stream: TcpStream, key: &str, entries: &[String], args: &Args) -> io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\n\r\n",
+        websocket::accept_key(key)
+    )?;
+    // Viewers often sit paused for a long time, so reads never time out.
+    stream.set_read_timeout(None)?;
+
+    let (sender, inputs) = mpsc::channel::<ViewerInput>();
+    let reader: TcpStream = stream.try_clone()?;
+    thread::spawn(move || {
+        let mut messages: MessageReader<TcpStream> = MessageReader::new(reader);
+
+        while let Ok(message) = messages.next() {
+            let input: ViewerInput = match message {
+                Message::Text(text) => match parse_command(&text) {
+                    Ok(command) => ViewerInput::Command(command),
+                    Err(reason) => ViewerInput::Invalid(reason),
+                },
+                Message::Ping(data) => ViewerInput::Ping(data),
+                Message::Binary(_) | Message::Pong(_) => continue,
+                Message::Close => break,
+            };
+            if sender.send(input).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut view: View = View {
+        size: args.client_size,
+        mode: FrameMode::Cells,
+        charset: args.charset,
+        auto_exposure: args.auto_exposure(),
+    };
+    let mut player: Player<'_> = Player::new(entries, args);
+    let mut shown: Option<(DecodedImage, Duration)> = None;
+    let mut playing: bool = true;
+    let mut due: Instant = Instant::now();
+    // While paused, how long the frame on screen still had to go.
+    let mut remaining: Duration = Duration::ZERO;
+
+    loop {
+        let input: Result<ViewerInput, RecvTimeoutError> = match playing {
+            true => inputs.recv_timeout(due.saturating_duration_since(Instant::now())),
+            false => inputs.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+        let mut redraw: bool = false;
+
+        match input {
+            Err(RecvTimeoutError::Timeout) => {
+                let Some((frame, position)) = player.next() else {
+                    break;
+                };
+                // Time lost to slow decoding is dropped rather than made up.
+                due = due.max(Instant::now()) + frame.delay;
+                view.send(&mut stream, &frame.image, position, args)?;
+                shown = Some((frame.image, position));
+            }
+            Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            Ok(ViewerInput::Ping(data)) => {
+                websocket::write_message(&mut stream, &Message::Pong(data))?
+            }
+            Ok(ViewerInput::Invalid(reason)) => {
+                websocket::write_message(&mut stream, &Message::Text(format!("error {reason}")))?
+            }
+            Ok(ViewerInput::Command(command)) => match command {
+                ViewerCommand::Pause if playing => {
+                    playing = false;
+                    remaining = due.saturating_duration_since(Instant::now());
+                }
+                ViewerCommand::Play if !playing => {
+                    playing = true;
+                    due = Instant::now() + remaining;
+                }
+                ViewerCommand::Play | ViewerCommand::Pause => {}
+                ViewerCommand::Seek(target) => {
+                    if let Err(error) = player.seek(target) {
+                        let error: Message = Message::Text(format!("error cannot seek: {error}"));
+                        websocket::write_message(&mut stream, &error)?;
+                    }
+                    // The frame at the target shows at once, even while paused.
+                    if let Some((frame, position)) = player.next() {
+                        view.send(&mut stream, &frame.image, position, args)?;
+                        shown = Some((frame.image, position));
+                        due = Instant::now() + frame.delay;
+                        remaining = frame.delay;
+                    }
+                }
+                ViewerCommand::Size(columns, rows) => {
+                    view.size = (columns, rows);
+                    redraw = true;
+                }
+                ViewerCommand::Mode(mode) => {
+                    view.mode = mode;
+                    redraw = true;
+                }
+                ViewerCommand::Charset(charset) => {
+                    view.charset = charset;
+                    redraw = true;
+                }
+            },
+        }
+
+        if redraw && let Some((image, position)) = &shown {
+            view.send(&mut stream, image, *position, args)?;
+        }
+    }
+
+    websocket::write_message(&mut stream, &Message::Close)
+}
+
+fn parse_command(text: &str) -> Result<ViewerCommand, String> {
+    let (name, value) = text.trim().split_once(' ').unwrap_or((text.trim(), ""));
+    let invalid = || format!("invalid {name}: '{value}'");
+
+    Ok(match name {
+        "play" => ViewerCommand::Play,
+        "pause" => ViewerCommand::Pause,
+        "seek" => ViewerCommand::Seek(
+            value
+                .parse::<f64>()
+                .ok()
+                .and_then(|seconds: f64| Duration::try_from_secs_f64(seconds).ok())
+                .ok_or_else(invalid)?,
+        ),
+        "size" => {
+            let (columns, rows) = args::parse_size(value)?;
+            if !(1..=MAX_CELLS).contains(&columns) || !(1..=MAX_CELLS).contains(&rows) {
+                return Err(invalid());
+            }
+            ViewerCommand::Size(columns, rows)
+        }
+        "mode" => ViewerCommand::Mode(FrameMode::from_str(value, true).map_err(|_| invalid())?),
+        "charset" => {
+            ViewerCommand::Charset(CharsetArg::from_str(value, true).map_err(|_| invalid())?)
+        }
+        _ => return Err(format!("unknown command '{name}'")),
+    })
+}
+
+/// How one viewer wants frames drawn.
+struct View {
+    size: (usize, usize),
+    mode: FrameMode,
+    charset: CharsetArg,
+    auto_exposure: AutoExposure,
+}
+
+impl View {
+    fn send(
+        &mut self,
+        stream: &mut TcpStream,
+        image: &DecodedImage,
+        position: Duration,
+        args: &Args,
+    ) -> io::Result<()> {
+        let (columns, rows) = self.size;
+        let layout: PlacementLayout =
+            args.placement()
+                .layout(image.width(), image.height(), columns, rows);
+        let style: TextStyle = TextStyle {
+            color: self.mode == FrameMode::Cells,
+            charset: self.charset,
+        };
+        let text: String = render::render_text(
+            image.clone(),
+            &layout,
+            args,
+            &mut self.auto_exposure,
+            style,
+            &FrameOverlays::default(),
+        );
+
+        let message: Message = match self.mode {
+            FrameMode::Cells => Message::Binary(cell_buffer(&text, position)),
+            FrameMode::Text => Message::Text(format!("frame {}\n{text}", position.as_millis())),
+        };
+
+        websocket::write_message(stream, &message)
+    }
+}
+
+/// Packs colored glyph text into a cell buffer: the frame's position in milliseconds (`u32`),
+/// columns and rows (`u16` each), then every cell row by row as the glyph's code point (`u32`)
+/// followed by its red, green and blue bytes. Numbers are little-endian; short rows are padded
+/// with blank cells.
+fn cell_buffer(text: &str, position: Duration) -> Vec<u8> {
+    let rows: Vec<Vec<(char, [u8; 3])>> = text.lines().map(colored_cells).collect();
+    let columns: usize = rows.iter().map(Vec::len).max().unwrap_or(0);
+    let mut buffer: Vec<u8> = Vec::with_capacity(8 + columns * rows.len() * 7);
+
+    buffer.extend_from_slice(&(position.as_millis().min(u32::MAX as u128) as u32).to_le_bytes());
+    buffer.extend_from_slice(&(columns as u16).to_le_bytes());
+    buffer.extend_from_slice(&(rows.len() as u16).to_le_bytes());
+    for row in &rows {
+        let padding = std::iter::repeat_n((' ', DEFAULT_COLOR), columns - row.len());
+
+        for (glyph, color) in row.iter().copied().chain(padding) {
+            buffer.extend_from_slice(&(glyph as u32).to_le_bytes());
+            buffer.extend_from_slice(&color);
+        }
+    }
+
+    buffer
+}
+
+/// Splits a line of glyph text into cells, following the 24-bit foreground escapes the color
+/// engine writes; any other escape resets the color.
+fn colored_cells(line: &str) -> Vec<(char, [u8; 3])> {
+    let mut cells: Vec<(char, [u8; 3])> = Vec::with_capacity(line.len());
+    let mut color: [u8; 3] = DEFAULT_COLOR;
+    let mut chars = line.chars();
+
+    while let Some(glyph) = chars.next() {
+        if glyph != '\x1b' {
+            cells.push((glyph, color));
+            continue;
+        }
+
+        let sequence: String = chars
+            .by_ref()
+            .take_while(|c: &char| !c.is_ascii_alphabetic())
+            .collect();
+        let parameters: Vec<u8> = sequence
+            .trim_start_matches('[')
+            .split(';')
+            .filter_map(|parameter: &str| parameter.parse::<u8>().ok())
+            .collect();
+
+        color = match parameters[..] {
+            [38, 2, r, g, b] => [r, g, b],
+            _ => DEFAULT_COLOR,
+        };
+    }
+
+    cells
+}
+
+/// One viewer's own pass through the entries, which it can pause and seek without affecting
+/// anyone else. Entries play in order and start over after the last.
+struct Player<'a> {
+    entries: &'a [String],
+    args: &'a Args,
+    index: usize,
+    source: Option<Box<dyn FrameSource>>,
+    /// The frame after the one last returned, read early so a lone still is known to be one.
+    ahead: Option<TimedFrame>,
+    /// How far into the current entry the next frame starts.
+    position: Duration,
+    /// Frames returned from the current entry.
+    frames: usize,
+    /// Entries in a row that played nothing; once every entry has, playback is over.
+    empty: usize,
+}
+
+impl<'a> Player<'a> {
+    fn new(entries: &'a [String], args: &'a Args) -> Self {
+        Self {
+            entries,
+            args,
+            index: 0,
+            source: None,
+            ahead: None,
+            position: Duration::ZERO,
+            frames: 0,
+            empty: 0,
+        }
+    }
+
+    /// The next frame and where it starts in its entry, moving on to the next entry as each
+    /// ends.
+    fn next(&mut self) -> Option<(TimedFrame, Duration)> {
+        while self.empty < self.entries.len() {
+            if self.source.is_none() {
+                let entry: &str = &self.entries[self.index];
+
+                match sources::open_frames(entry, self.args) {
+                    Ok(source) => self.source = Some(source),
+                    Err(error) => {
+                        eprintln!("{entry}: {error}");
+                        self.finish_entry();
+                        continue;
+                    }
+                }
+            }
+
+            let Some(mut frame) = self.ahead.take().or_else(|| self.read()) else {
+                self.finish_entry();
+                continue;
+            };
+            self.ahead = self.read();
+            if self.frames == 0 && self.ahead.is_none() {
+                frame.delay = frame.delay.max(STILL_DURATION);
+            }
+
+            let position: Duration = self.position;
+            self.position += frame.delay;
+            self.frames += 1;
+            self.empty = 0;
+
+            return Some((frame, position));
+        }
+
+        None
+    }
+
+    /// Restarts the current entry and skips to the frame showing at `target`. Past the end, the
+    /// next entry starts.
+    fn seek(&mut self, target: Duration) -> Result<(), DecodeError> {
+        let Some(source) = &mut self.source else {
+            return Ok(());
+        };
+        source.rewind()?;
+        self.ahead = None;
+        self.position = Duration::ZERO;
+        self.frames = 0;
+
+        while let Some(frame) = self.read() {
+            if self.position + frame.delay > target {
+                self.ahead = Some(frame);
+                break;
+            }
+            self.position += frame.delay;
+            self.frames += 1;
+        }
+
+        Ok(())
+    }
+
+    fn read(&mut self) -> Option<TimedFrame> {
+        match self.source.as_mut()?.next_frame()? {
+            Ok(frame) => Some(frame),
+            Err(error) => {
+                eprintln!("{}: {error}", self.entries[self.index]);
+                None
+            }
+        }
+    }
+
+    fn finish_entry(&mut self) {
+        if self.frames == 0 {
+            self.empty += 1;
+        }
+        self.source = None;
+        self.ahead = None;
+        self.position = Duration::ZERO;
+        self.frames = 0;
+        self.index = (self.index + 1) % self.entries.len();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        io::{Read, Write},
+        net::{TcpListener, TcpStream},
+        sync::Arc,
+        time::Duration,
+    };
+
+    use clap::Parser;
+    use pretty_assertions::assert_eq;
+
+    use crate::args::Args;
+    use crate::frame_feed::FrameFeed;
+    use crate::http;
+    use crate::viewer::{FrameMode, ViewerCommand, cell_buffer, parse_command};
+    use crate::websocket::Message;
+
+    #[test]
+    fn commands_are_parsed() {
+        assert_eq!(
+            parse_command("seek 1.5"),
+            Ok(ViewerCommand::Seek(Duration::from_millis(1500)))
+        );
+        assert_eq!(
+            parse_command("size 120x40"),
+            Ok(ViewerCommand::Size(120, 40))
+        );
+        assert_eq!(
+            parse_command("mode text"),
+            Ok(ViewerCommand::Mode(FrameMode::Text))
+        );
+        assert!(parse_command("seek -3").is_err());
+        assert!(parse_command("size 0x10").is_err());
+        assert!(parse_command("rewind").is_err());
+    }
+
+    #[test]
+    fn cell_buffer_keeps_colors_and_pads_rows() {
+        let text: &str = "\x1b[38;2;255;0;10m@\x1b[0m.\n#";
+
+        let buffer: Vec<u8> = cell_buffer(text, Duration::from_millis(258));
+
+        assert_eq!(
+            buffer,
+            [
+                vec![2, 1, 0, 0, 2, 0, 2, 0],
+                vec![b'@', 0, 0, 0, 255, 0, 10],
+                vec![b'.', 0, 0, 0, 255, 255, 255],
+                vec![b'#', 0, 0, 0, 255, 255, 255],
+                vec![b' ', 0, 0, 0, 255, 255, 255],
+            ]
+            .concat()
+        );
+    }
+
+    /// A short text message, masked as clients must; a zero mask leaves the payload as is.
+    fn send(client: &mut TcpStream, text: &str) {
+        let header: [u8; 6] = [0x81, 0x80 | text.len() as u8, 0, 0, 0, 0];
+
+        client
+            .write_all(&[&header[..], text.as_bytes()].concat())
+            .unwrap();
+    }
+
+    /// An unmasked, unfragmented server message.
+    fn receive(client: &mut TcpStream) -> Message {
+        let mut header: [u8; 2] = [0; 2];
+        client.read_exact(&mut header).unwrap();
+        let length: usize = match header[1] {
+            126 => {
+                let mut length: [u8; 2] = [0; 2];
+                client.read_exact(&mut length).unwrap();
+                u16::from_be_bytes(length) as usize
+            }
+            127 => {
+                let mut length: [u8; 8] = [0; 8];
+                client.read_exact(&mut length).unwrap();
+                u64::from_be_bytes(length) as usize
+            }
+            length => length as usize,
+        };
+        let mut payload: Vec<u8> = vec![0; length];
+        client.read_exact(&mut payload).unwrap();
+
+        match header[0] & 0x0F {
+            0x1 => Message::Text(String::from_utf8(payload).unwrap()),
+            0x2 => Message::Binary(payload),
+            opcode => panic!("unexpected opcode {opcode}"),
+        }
+    }
+
+    #[test]
+    fn viewer_receives_frames_and_follows_commands() {
+        let path = std::env::temp_dir().join(format!("viewer-test-{}.pgm", std::process::id()));
+        std::fs::write(&path, "P2\n4 2\n255\n0 80 160 255 255 160 80 0\n").unwrap();
+        let listener: TcpListener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+        let entries: Arc<Vec<String>> = Arc::new(vec![path.to_string_lossy().into_owned()]);
+        let args: Args = Args::parse_from(["glyphforge-cli", "unused"]);
+        http::spawn(listener, FrameFeed::new(), entries, Arc::new(args));
+
+        let mut client: TcpStream = TcpStream::connect(address).unwrap();
+        client
+            .set_read_timeout(Some(Duration::from_secs(5)))
+            .unwrap();
+        client
+            .write_all(
+                b"GET /ws HTTP/1.1\r\nHost: localhost\r\nUpgrade: websocket\r\n\
+                  Connection: Upgrade\r\nSec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\
+                  Sec-WebSocket-Version: 13\r\n\r\n",
+            )
+            .unwrap();
+        let mut response: Vec<u8> = Vec::new();
+        while !response.ends_with(b"\r\n\r\n") {
+            let mut byte: [u8; 1] = [0];
+            client.read_exact(&mut byte).unwrap();
+            response.push(byte[0]);
+        }
+        let response: String = String::from_utf8(response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 101"));
+        assert!(response.contains("Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo=\r\n"));
+
+        let Message::Binary(cells) = receive(&mut client) else {
+            panic!("expected a cell buffer first");
+        };
+        assert_eq!(&cells[..4], &[0, 0, 0, 0]);
+
+        send(&mut client, "pause");
+        send(&mut client, "mode text");
+        send(&mut client, "size 4x2");
+        // Each change redraws the paused frame; the last one is at the requested size.
+        let frames: Vec<Message> = (0..2).map(|_| receive(&mut client)).collect();
+        let _ = std::fs::remove_file(&path);
+
+        assert_eq!(
+            frames.last(),
+            Some(&Message::Text("frame 0\n -*@\n@*- ".to_string()))
+        );
+    }
+}
@@ -0,0 +1,292 @@
+use std::io::{self, Read, Write};
+
+/// Appended to a client's key to prove the server speaks WebSocket (RFC 6455, section 1.3).
+const HANDSHAKE_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Largest message accepted from a client; viewers only send short commands.
+const MAX_MESSAGE: usize = 64 * 1024;
+
+const CONTINUATION: u8 = 0x0;
+const TEXT: u8 = 0x1;
+const BINARY: u8 = 0x2;
+const CLOSE: u8 = 0x8;
+const PING: u8 = 0x9;
+const PONG: u8 = 0xA;
+
+/// A complete WebSocket message.
+#[derive(Debug, PartialEq)]
+pub enum Message {
+    Text(String),
+    Binary(Vec<u8>),
+    Ping(Vec<u8>),
+    Pong(Vec<u8>),
+    Close,
+}
+
+/// The `Sec-WebSocket-Accept` value answering a client's `Sec-WebSocket-Key`.
+pub fn accept_key(key: &str) -> String {
+    base64(&sha1(format!("{}{HANDSHAKE_GUID}", key.trim()).as_bytes()))
+}
+
+/// Reads messages from a client, joining fragmented ones back together.
+pub struct MessageReader<R: Read> {
+    reader: R,
+    /// Opcode and payload so far of a message whose final fragment has not arrived.
+    fragments: Option<(u8, Vec<u8>)>,
+}
+
+impl<R: Read> MessageReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            fragments: None,
+        }
+    }
+
+    /// Reads frames until a message is complete. Control messages may arrive in the middle of
+    /// a fragmented one and are returned straight away.
+    pub fn next(&mut self) -> io::Result<Message> {
+        loop {
+            let (fin, opcode, payload) = self.read_frame()?;
+
+            let (opcode, payload): (u8, Vec<u8>) = match (opcode, self.fragments.take()) {
+                (PING | PONG | CLOSE, fragments) => {
+                    self.fragments = fragments;
+                    (opcode, payload)
+                }
+                (CONTINUATION, Some((opcode, mut message))) => {
+                    message.extend_from_slice(&payload);
+                    (opcode, message)
+                }
+                (TEXT | BINARY, None) => (opcode, payload),
+                _ => return Err(invalid("unexpected frame")),
+            };
+
+            if payload.len() > MAX_MESSAGE {
+                return Err(invalid("message too large"));
+            }
+            if !fin && !matches!(opcode, PING | PONG | CLOSE) {
+                self.fragments = Some((opcode, payload));
+                continue;
+            }
+
+            return Ok(match opcode {
+                TEXT => Message::Text(
+                    String::from_utf8(payload).map_err(|_| invalid("text is not UTF-8"))?,
+                ),
+                BINARY => Message::Binary(payload),
+                PING => Message::Ping(payload),
+                PONG => Message::Pong(payload),
+                _ => Message::Close,
+            });
+        }
+    }
+
+    fn read_frame(&mut self) -> io::Result<(bool, u8, Vec<u8>)> {
+        let mut header: [u8; 2] = [0; 2];
+        self.reader.read_exact(&mut header)?;
+
+        let fin: bool = header[0] & 0x80 != 0;
+        let opcode: u8 = header[0] & 0x0F;
+        // Clients must mask everything they send (RFC 6455, section 5.1).
+        if header[1] & 0x80 == 0 {
+            return Err(invalid("unmasked client frame"));
+        }
+
+        let length: u64 = match header[1] & 0x7F {
+            126 => {
+                let mut length: [u8; 2] = [0; 2];
+                self.reader.read_exact(&mut length)?;
+                u16::from_be_bytes(length) as u64
+            }
+            127 => {
+                let mut length: [u8; 8] = [0; 8];
+                self.reader.read_exact(&mut length)?;
+                u64::from_be_bytes(length)
+            }
+            length => length as u64,
+        };
+        if length > MAX_MESSAGE as u64 {
+            return Err(invalid("message too large"));
+        }
+
+        let mut mask: [u8; 4] = [0; 4];
+        self.reader.read_exact(&mut mask)?;
+        let mut payload: Vec<u8> = vec![0; length as usize];
+        self.reader.read_exact(&mut payload)?;
+        for (i, byte) in payload.iter_mut().enumerate() {
+            *byte ^= mask[i % 4];
+        }
+
+        Ok((fin, opcode, payload))
+    }
+}
+
+/// Writes `message` as a single unmasked frame, as servers send them.
+pub fn write_message(writer: &mut impl Write, message: &Message) -> io::Result<()> {
+    match message {
+        Message::Text(text) => write_frame(writer, TEXT, text.as_bytes(), None),
+        Message::Binary(data) => write_frame(writer, BINARY, data, None),
+        Message::Ping(data) => write_frame(writer, PING, data, None),
+        Message::Pong(data) => write_frame(writer, PONG, data, None),
+        Message::Close => write_frame(writer, CLOSE, &[], None),
+    }
+}
+
+fn write_frame(
+    writer: &mut impl Write,
+    opcode: u8,
+    payload: &[u8],
+    mask: Option<[u8; 4]>,
+) -> io::Result<()> {
+    let mut frame: Vec<u8> = Vec::with_capacity(payload.len() + 14);
+    let mask_bit: u8 = if mask.is_some() { 0x80 } else { 0 };
+
+    frame.push(0x80 | opcode);
+    match payload.len() {
+        length @ 0..=125 => frame.push(mask_bit | length as u8),
+        length @ 126..=0xFFFF => {
+            frame.push(mask_bit | 126);
+            frame.extend_from_slice(&(length as u16).to_be_bytes());
+        }
+        length => {
+            frame.push(mask_bit | 127);
+            frame.extend_from_slice(&(length as u64).to_be_bytes());
+        }
+    }
+    match mask {
+        Some(mask) => {
+            frame.extend_from_slice(&mask);
+            frame.extend(
+                payload
+                    .iter()
+                    .enumerate()
+                    .map(|(i, byte)| byte ^ mask[i % 4]),
+            );
+        }
+        None => frame.extend_from_slice(payload),
+    }
+
+    writer.write_all(&frame)
+}
+
+fn invalid(reason: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, reason.to_string())
+}
+
+/// SHA-1 (FIPS 180-4), needed only for the handshake.
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut state: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+    let mut message: Vec<u8> = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for block in message.chunks_exact(64) {
+        let mut words: [u32; 80] = [0; 80];
+        for (i, word) in block.chunks_exact(4).enumerate() {
+            words[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            words[i] = (words[i - 3] ^ words[i - 8] ^ words[i - 14] ^ words[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        for (i, word) in words.iter().enumerate() {
+            let (f, k): (u32, u32) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let temp: u32 = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*word);
+            (e, d, c, b, a) = (d, c, b.rotate_left(30), a, temp);
+        }
+
+        for (value, add) in state.iter_mut().zip([a, b, c, d, e]) {
+            *value = value.wrapping_add(add);
+        }
+    }
+
+    let mut digest: [u8; 20] = [0; 20];
+    for (bytes, value) in digest.chunks_exact_mut(4).zip(state) {
+        bytes.copy_from_slice(&value.to_be_bytes());
+    }
+
+    digest
+}
+
+/// Standard padded base64 (RFC 4648), needed only for the handshake.
+fn base64(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded: String = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let bits: u32 = chunk.iter().enumerate().fold(0, |bits: u32, (i, byte)| {
+            bits | (*byte as u32) << (16 - 8 * i)
+        });
+
+        for i in 0..4 {
+            encoded.push(match i <= chunk.len() {
+                true => ALPHABET[(bits >> (18 - 6 * i) & 0x3F) as usize] as char,
+                false => '=',
+            });
+        }
+    }
+
+    encoded
+}
+
+#[cfg(test)]
+mod test {
+    use pretty_assertions::assert_eq;
+
+    use crate::websocket::{Message, MessageReader, TEXT, accept_key, base64, sha1, write_frame};
+
+    #[test]
+    fn accept_key_matches_rfc_example() {
+        let accept: String = accept_key("dGhlIHNhbXBsZSBub25jZQ==");
+
+        assert_eq!(accept, "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+    }
+
+    #[test]
+    fn sha1_and_base64_match_known_values() {
+        assert_eq!(base64(&sha1(b"abc")), "qZk+NkcGgWq6PiVxeFDCbJzQ2J0=");
+        assert_eq!(base64(b"ab"), "YWI=");
+        assert_eq!(base64(b""), "");
+    }
+
+    #[test]
+    fn masked_fragments_are_joined_around_a_ping() {
+        let mut bytes: Vec<u8> = Vec::new();
+        let mask: Option<[u8; 4]> = Some([1, 2, 3, 4]);
+        write_frame(&mut bytes, TEXT, b"pau", mask).unwrap();
+        // Clear FIN on the first fragment, then continue it after a ping.
+        bytes[0] &= 0x7F;
+        write_frame(&mut bytes, 0x9, b"hi", mask).unwrap();
+        write_frame(&mut bytes, 0x0, b"se", mask).unwrap();
+
+        let mut reader: MessageReader<&[u8]> = MessageReader::new(&bytes[..]);
+
+        assert_eq!(reader.next().unwrap(), Message::Ping(b"hi".to_vec()));
+        assert_eq!(reader.next().unwrap(), Message::Text("pause".to_string()));
+    }
+
+    #[test]
+    fn unmasked_client_frames_are_refused() {
+        let mut bytes: Vec<u8> = Vec::new();
+        write_frame(&mut bytes, TEXT, b"play", None).unwrap();
+
+        let result = MessageReader::new(&bytes[..]).next();
+
+        assert!(result.is_err());
+    }
+}